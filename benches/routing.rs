@@ -0,0 +1,120 @@
+//! Measures the cost of routing a request as the number of routes grows.
+//!
+//! Run with `cargo bench --bench routing`. Each route set size is timed
+//! against a request matching the route that was inserted last, which is the
+//! worst case for a linear scan. The per request cost should stay roughly flat
+//! across all sizes: the bench fails if routing a request among the largest
+//! route set takes more than `MAX_RATIO` times as long as among the smallest
+//! one. A linear scan would be about a thousand times slower.
+
+use futures::Future;
+use futures::future::{self, FutureResult};
+use http::Method;
+use tower_service::NewService;
+use tower_web::ServiceBuilder;
+use tower_web::error::Map;
+use tower_web::response::Serializer;
+use tower_web::routing::{self, IntoResource, Resource, RouteMatch, RouteSet};
+use tower_web::util::BufStream;
+use tower_web::util::http::HttpService;
+
+use std::marker::PhantomData;
+use std::time::Instant;
+
+const ITERATIONS: u32 = 20_000;
+
+/// Maximum ratio between the per request cost of the largest and the smallest
+/// route sets.
+const MAX_RATIO: u64 = 5;
+
+/// A resource with `n` routes of the form `/resource{i}/items/:id`.
+struct Routes {
+    n: usize,
+}
+
+struct Handler<B> {
+    _p: PhantomData<B>,
+}
+
+impl<S, B> IntoResource<S, B> for Routes
+where S: Serializer,
+      B: BufStream,
+{
+    type Destination = usize;
+    type Resource = Handler<B>;
+
+    fn routes(&self) -> RouteSet<usize> {
+        let mut builder = routing::Builder::new();
+
+        for i in 0..self.n {
+            let path = format!("/resource{}/items/:id", i);
+            builder.insert(routing::Route::new(i).method(Method::GET).path(&path));
+        }
+
+        builder.build()
+    }
+
+    fn into_resource(self, _: S) -> Self::Resource {
+        Handler { _p: PhantomData }
+    }
+}
+
+impl<B> Clone for Handler<B> {
+    fn clone(&self) -> Self {
+        Handler { _p: PhantomData }
+    }
+}
+
+impl<B: BufStream> Resource for Handler<B> {
+    type Destination = usize;
+    type RequestBody = B;
+    type Buf = <Self::Body as BufStream>::Item;
+    type Body = Map<&'static str>;
+    type Future = FutureResult<http::Response<Self::Body>, tower_web::Error>;
+
+    fn dispatch(&mut self, _: usize, _: &RouteMatch<'_>, _: B) -> Self::Future {
+        future::ok(http::Response::new(Map::new("ok")))
+    }
+}
+
+fn main() {
+    let sizes = [10, 100, 1_000, 10_000];
+    let mut costs = vec![];
+
+    for &n in &sizes {
+        let mut service = ServiceBuilder::new()
+            .resource(Routes { n })
+            .build_new_service()
+            .new_service()
+            .wait().unwrap();
+
+        let uri = format!("/resource{}/items/123", n - 1);
+
+        let start = Instant::now();
+
+        for _ in 0..ITERATIONS {
+            let request = http::Request::builder()
+                .uri(&uri[..])
+                .body(String::new())
+                .unwrap();
+
+            let response = service.call_http(request).wait().ok().unwrap();
+            assert_eq!(response.status(), http::StatusCode::OK);
+        }
+
+        let elapsed = start.elapsed();
+        let nanos = elapsed.as_secs() * 1_000_000_000 + elapsed.subsec_nanos() as u64;
+
+        let cost = nanos / ITERATIONS as u64;
+        println!("{:>6} routes: {:>8} ns/request", n, cost);
+
+        costs.push(cost);
+    }
+
+    let smallest = costs[0].max(1);
+    let largest = costs[costs.len() - 1];
+
+    assert!(largest <= smallest * MAX_RATIO,
+            "routing cost grows with the number of routes; {} routes: {} ns/request, {} routes: {} ns/request",
+            sizes[0], smallest, sizes[sizes.len() - 1], largest);
+}
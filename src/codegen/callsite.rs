@@ -2,11 +2,17 @@ use self::Source::*;
 
 use http::header::HeaderName;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct CallSite {
     /// Where to extract the argument when the type does not provide the
     /// information.
     source: Source,
+
+    /// The argument name, used to match captures defined by a mount prefix.
+    param: Option<&'static str>,
+
+    /// Maximum size of the request body, overriding the service wide limit.
+    body_limit: Option<u64>,
 }
 
 #[derive(Debug, Clone)]
@@ -15,29 +21,65 @@ pub(crate) enum Source {
     Header(HeaderName),
     QueryString,
     Body,
+
+    /// Every capture of the route, by name. The names of the route's own
+    /// captures are listed in path order.
+    Captures(&'static [&'static str]),
+
     Unknown,
 }
 
 impl CallSite {
     pub fn new_capture(index: usize) -> CallSite {
-        CallSite { source: Capture(index) }
+        CallSite { source: Capture(index), param: None, body_limit: None }
     }
 
     pub fn new_header(name: &'static str) -> CallSite {
-        CallSite { source: Header(HeaderName::from_static(name)) }
+        CallSite { source: Header(HeaderName::from_static(name)), param: None, body_limit: None }
+    }
+
+    /// The argument named `param` does not match a capture in the route path.
+    ///
+    /// It is extracted from the mount prefix capture with the same name if
+    /// there is one, otherwise from the header.
+    pub fn new_param(param: &'static str, header: &'static str) -> CallSite {
+        CallSite {
+            source: Header(HeaderName::from_static(header)),
+            param: Some(param),
+            body_limit: None,
+        }
     }
 
     pub fn new_query_string() -> CallSite {
-        CallSite { source: QueryString }
+        CallSite { source: QueryString, param: None, body_limit: None }
     }
 
     pub fn new_body() -> CallSite {
-        CallSite { source: Body }
+        CallSite { source: Body, param: None, body_limit: None }
+    }
+
+    /// The argument is extracted from every capture of the route at once.
+    pub fn new_captures(names: &'static [&'static str]) -> CallSite {
+        CallSite { source: Captures(names), param: None, body_limit: None }
     }
 
     /// Cannot infer where to extract the argument based on the call site.
     pub fn new_unknown() -> CallSite {
-        CallSite { source: Unknown }
+        CallSite { source: Unknown, param: None, body_limit: None }
+    }
+
+    /// Limit the size of the request body to `limit` bytes.
+    pub fn with_body_limit(mut self, limit: u64) -> CallSite {
+        self.body_limit = Some(limit);
+        self
+    }
+
+    pub(crate) fn param(&self) -> Option<&'static str> {
+        self.param
+    }
+
+    pub(crate) fn body_limit(&self) -> Option<u64> {
+        self.body_limit
     }
 
     pub(crate) fn source(&self) -> &Source {
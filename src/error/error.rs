@@ -3,6 +3,7 @@ use self::KindPriv::*;
 use std::error;
 use std::fmt;
 use http::StatusCode;
+use http::header::{HeaderName, HeaderValue};
 use serde::Serialize;
 
 /// Builder for Error objects.
@@ -27,6 +28,11 @@ pub struct Error {
     #[serde(skip)]
     status: StatusCode,
 
+    /// Header to set on the error response, such as `Accept-Post` on a
+    /// `415 Unsupported Media Type` response.
+    #[serde(skip)]
+    header: Option<(HeaderName, HeaderValue)>,
+
     // TODO: this property isn't used and should be removed
     #[serde(skip)]
     error_kind: ErrorKind,
@@ -109,6 +115,7 @@ impl Error {
             title: title.to_owned(),
             detail: None,
             status,
+            header: None,
 
             // TODO: this property isn't used and should be removed
             error_kind: ErrorKind::new(&status),
@@ -126,6 +133,17 @@ impl Error {
         self.status
     }
 
+    /// Set a header to send along with the error response.
+    pub(crate) fn set_header(&mut self, name: HeaderName, value: HeaderValue) -> &mut Self {
+        self.header = Some((name, value));
+        self
+    }
+
+    /// Take the header to send along with the error response.
+    pub(crate) fn take_header(&mut self) -> Option<(HeaderName, HeaderValue)> {
+        self.header.take()
+    }
+
     /// Create an error builder object.
     pub fn builder() -> Builder {
         Builder::new()
@@ -179,6 +197,7 @@ impl From<StatusCode> for Error {
             title: title.to_owned(),
             detail: None,
             status,
+            header: None,
 
             // TODO: this property isn't used and should be removed
             error_kind: ErrorKind::new(&status),
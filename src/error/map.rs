@@ -1,5 +1,6 @@
 use http::status::StatusCode;
 use crate::util::BufStream;
+use crate::util::buf_stream::SizeHint;
 
 use futures::{Future, Poll};
 
@@ -68,4 +69,13 @@ impl<T: BufStream> BufStream for Map<T> {
             Immediate(ref mut e) => Err(e.take().unwrap()),
         }
     }
+
+    fn size_hint(&self) -> SizeHint {
+        use self::State::*;
+
+        match self.inner {
+            Inner(ref f) => f.size_hint(),
+            Immediate(_) => SizeHint::default(),
+        }
+    }
 }
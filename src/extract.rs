@@ -7,10 +7,13 @@
 //!
 //! Currently, [`Extract`] implementations are provided for the following types:
 //!
+//! * [`BodyStream`](struct.BodyStream.html)
 //! * [`Bytes`](https://docs.rs/bytes/0.4/bytes/struct.Bytes.html)
+//! * [`Multipart`](multipart/struct.Multipart.html)
 //! * [`Option`](https://doc.rust-lang.org/std/option/enum.Option.html)
 //! * [`PathBuf`](https://doc.rust-lang.org/std/path/struct.PathBuf.html)
 //! * [`String`](https://doc.rust-lang.org/std/string/struct.String.html)
+//! * [`Urls`](../routing/struct.Urls.html)
 //! * [`Vec<u8>`](https://doc.rust-lang.org/std/vec/struct.Vec.html)
 //!
 //! More implementations can be added by submitting a PR.
@@ -22,12 +25,14 @@
 //! [`Extract`]: trait.Extract.html
 //! [lib]: ../index.html
 
+mod body_stream;
 mod bytes;
 mod context;
 mod error;
 pub mod http_date_time;
 mod http;
 mod immediate;
+pub mod multipart;
 mod num;
 pub mod option;
 mod osstring;
@@ -35,9 +40,12 @@ mod pathbuf;
 #[doc(hidden)]
 pub mod serde;
 mod str;
+mod urls;
 
+pub use self::body_stream::BodyStream;
 pub use self::error::Error;
 pub use self::context::Context;
+pub(crate) use self::context::BodyLimit;
 pub use self::immediate::Immediate;
 
 use crate::codegen::CallSite;
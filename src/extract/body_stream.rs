@@ -0,0 +1,163 @@
+use crate::codegen::CallSite;
+use crate::extract::{Context, Error, Extract, Immediate};
+use crate::util::BufStream;
+use crate::util::buf_stream::{IntoBytes, Limit, SizeHint};
+
+use bytes::Bytes;
+use futures::{Poll, Stream, try_ready};
+use http::StatusCode;
+
+use std::fmt;
+use std::io::Cursor;
+
+/// The request body, streamed to the resource method.
+///
+/// Extracting `BodyStream` does not read any of the request body. The body is
+/// moved into the resource method as is, which can then process it as it
+/// arrives. This allows handling large uploads, for example by piping them to
+/// disk, without buffering the request in memory.
+///
+/// `BodyStream` yields the body chunks as `Bytes` through both the `Stream`
+/// and the `BufStream` implementations.
+///
+/// ```rust
+/// # #[macro_use] extern crate tower_web;
+/// # extern crate futures;
+/// use tower_web::extract::BodyStream;
+/// use futures::{Future, Stream};
+///
+/// struct MyApp;
+///
+/// impl_web! {
+///     impl MyApp {
+///         #[post("/upload")]
+///         fn upload(&self, body: BodyStream) -> impl Future<Item = String, Error = ()> {
+///             body.fold(0, |len, chunk| Ok::<_, tower_web::extract::Error>(len + chunk.len()))
+///                 .map(|len| format!("received {} bytes", len))
+///                 .map_err(|_| ())
+///         }
+///     }
+/// }
+/// # fn main() {}
+/// ```
+///
+/// `BodyStream` must be extracted from the request body, i.e. the argument
+/// must be named `body`. Any other argument fails every request with `500
+/// Internal Server Error`. The body is subject to the request body limit set
+/// with `ServiceBuilder::body_limit` or `#[web(body_limit = "...")]`, and a
+/// body exceeding it fails with `413 Payload Too Large` while it is read.
+pub struct BodyStream {
+    inner: Box<dyn BufStream<Item = Cursor<Bytes>, Error = Error> + Send>,
+}
+
+/// Converts every buffer yielded by the inner body into `Bytes`.
+struct MapBytes<B> {
+    inner: B,
+}
+
+// ===== impl BodyStream =====
+
+impl BodyStream {
+    /// Erase the type of the request body `body`.
+    pub fn new<B>(body: B) -> BodyStream
+    where
+        B: BufStream + Send + 'static,
+        B::Item: IntoBytes,
+    {
+        // `!0` is the largest `u64`, leaving the body effectively unlimited
+        BodyStream::limited(body.limit(!0))
+    }
+
+    /// Erase the type of the request body `body`, which is already limited to
+    /// the maximum request body size.
+    pub(crate) fn limited<B>(body: Limit<B>) -> BodyStream
+    where
+        B: BufStream + Send + 'static,
+        B::Item: IntoBytes,
+    {
+        BodyStream {
+            inner: Box::new(MapBytes { inner: body }),
+        }
+    }
+}
+
+impl<B> Extract<B> for BodyStream
+where
+    B: BufStream + Send + 'static,
+    B::Item: IntoBytes,
+{
+    type Future = Immediate<BodyStream>;
+
+    fn extract(_: &Context<'_>) -> Self::Future {
+        Immediate::err(not_body_argument("BodyStream"))
+    }
+
+    fn extract_body(ctx: &Context<'_>, body: B) -> Self::Future {
+        Immediate::ok(BodyStream::limited(ctx.limit_body(body)))
+    }
+
+    fn requires_body(callsite: &CallSite) -> bool {
+        callsite.requires_body()
+    }
+}
+
+impl BufStream for BodyStream {
+    type Item = Cursor<Bytes>;
+    type Error = Error;
+
+    fn poll(&mut self) -> Poll<Option<Self::Item>, Error> {
+        self.inner.poll()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.inner.size_hint()
+    }
+}
+
+impl Stream for BodyStream {
+    type Item = Bytes;
+    type Error = Error;
+
+    fn poll(&mut self) -> Poll<Option<Bytes>, Error> {
+        let buf = try_ready!(self.inner.poll());
+        Ok(buf.map(Cursor::into_inner).into())
+    }
+}
+
+impl fmt::Debug for BodyStream {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("BodyStream").finish()
+    }
+}
+
+/// The error returned when extracting a body type, such as `BodyStream`, for
+/// an argument that is not named `body`.
+pub(crate) fn not_body_argument(ty: &str) -> Error {
+    let inner = crate::Error::builder()
+        .status(StatusCode::INTERNAL_SERVER_ERROR)
+        .detail(&format!("`{}` must be extracted from the request body", ty))
+        .build();
+
+    inner.into()
+}
+
+// ===== impl MapBytes =====
+
+impl<B> BufStream for MapBytes<B>
+where
+    B: BufStream,
+    B::Item: IntoBytes,
+    Error: From<B::Error>,
+{
+    type Item = Cursor<Bytes>;
+    type Error = Error;
+
+    fn poll(&mut self) -> Poll<Option<Self::Item>, Error> {
+        let buf = try_ready!(self.inner.poll().map_err(Error::from));
+        Ok(buf.map(|buf| Cursor::new(buf.into_bytes())).into())
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.inner.size_hint()
+    }
+}
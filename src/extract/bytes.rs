@@ -1,6 +1,6 @@
 use crate::codegen::CallSite;
 use crate::extract::{Context, Error, Extract, ExtractFuture};
-use crate::util::buf_stream::{self, BufStream};
+use crate::util::buf_stream::{self, BufStream, Limit};
 
 use futures::{Future, Poll, try_ready};
 
@@ -13,7 +13,7 @@ pub struct ExtractBytes<T, B> {
 #[derive(Debug)]
 enum State<T, B> {
     Complete(Result<T, Option<Error>>),
-    Body(buf_stream::Collect<B, Vec<u8>>),
+    Body(buf_stream::Collect<Limit<B>, Vec<u8>>),
 }
 
 impl<B: BufStream> Extract<B> for Vec<u8> {
@@ -52,7 +52,7 @@ impl<B: BufStream> Extract<B> for Vec<u8> {
             Body => {
                 panic!("called `extract` but `body` is required");
             }
-            Unknown => {
+            Captures(_) | Unknown => {
                 unimplemented!();
             }
         }
@@ -63,7 +63,7 @@ impl<B: BufStream> Extract<B> for Vec<u8> {
 
         match ctx.callsite().source() {
             Body => {
-                let state = State::Body(body.collect());
+                let state = State::Body(ctx.limit_body(body).collect());
                 ExtractBytes { state }
             }
             _ => panic!("called `extract_body` but not extracting from body"),
@@ -107,9 +107,7 @@ where T: From<Vec<u8>>,
                     return Ok(().into());
                 }
                 Body(ref mut collect) => {
-                    let res = collect.poll()
-                        // TODO: Is there a better way to handle errors?
-                        .map_err(|_| Error::internal_error());
+                    let res = collect.poll().map_err(Error::from);
 
                     try_ready!(res).into()
                 }
@@ -1,16 +1,19 @@
 use crate::codegen::CallSite;
 use crate::config::Config;
 use crate::routing::{Captures, RouteMatch};
+use crate::util::buf_stream::{BufStream, Limit};
 
 use http::Request;
 
+use std::borrow::Cow;
+
 /// Context available when extracting data from the HTTP request.
 ///
 /// Primarily, `Context` includes a reference to the HTTP request in question.
 #[derive(Debug)]
 pub struct Context<'a> {
     /// Reference too the callsite
-    callsite: &'a CallSite,
+    callsite: Cow<'a, CallSite>,
 
     /// Reference to the HTTP request
     request: &'a Request<()>,
@@ -20,6 +23,10 @@ pub struct Context<'a> {
     config: &'a Config,
 }
 
+/// The service wide request body limit, stored in the config.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct BodyLimit(pub u64);
+
 impl<'a> Context<'a> {
     // Used as part of codegen, but not part of the public API.
     #[doc(hidden)]
@@ -28,6 +35,14 @@ impl<'a> Context<'a> {
         let captures = route_match.captures();
         let config = route_match.config();
 
+        // Arguments that do not match a route capture may match a capture
+        // defined by the prefix the resource is mounted under or by the host
+        // pattern the resource is bound to.
+        let callsite = match callsite.param().and_then(|p| captures.named_index(p)) {
+            Some(index) => Cow::Owned(CallSite::new_capture(index)),
+            None => Cow::Borrowed(callsite),
+        };
+
         Context {
             callsite,
             request,
@@ -37,7 +52,7 @@ impl<'a> Context<'a> {
     }
 
     pub(crate) fn callsite(&self) -> &CallSite {
-        self.callsite
+        &self.callsite
     }
 
     pub(crate) fn captures(&self) -> &Captures {
@@ -50,6 +65,22 @@ impl<'a> Context<'a> {
         &self.request
     }
 
+    /// Returns the maximum size of the request body, in bytes.
+    ///
+    /// The limit set on the resource method with `#[web(body_limit = "..")]`
+    /// takes precedence over the one set with `ServiceBuilder::body_limit`.
+    /// Returns `None` when the request body is not limited.
+    pub fn body_limit(&self) -> Option<u64> {
+        self.callsite.body_limit()
+            .or_else(|| self.config::<BodyLimit>().map(|limit| limit.0))
+    }
+
+    /// Limit `body` to the maximum request body size.
+    pub(crate) fn limit_body<B: BufStream>(&self, body: B) -> Limit<B> {
+        // `!0` is the largest `u64`, leaving the body effectively unlimited
+        body.limit(self.body_limit().unwrap_or(!0))
+    }
+
     /// Returns the stored configuration value of type `T`.
     pub fn config<T: Send + Sync + 'static>(&self) -> Option<&T> { self.config.get::<T>() }
 }
@@ -1,5 +1,8 @@
 use self::Kind::*;
 
+use crate::util::buf_stream::limit;
+
+use http::Method;
 use http::status::StatusCode;
 
 /// Errors that can happen while extracting data from an HTTP request.
@@ -63,6 +66,24 @@ impl Error {
         }
     }
 
+    /// The request body has no `Content-Type` or one that cannot be
+    /// extracted.
+    ///
+    /// The accepted media types are listed in the `Accept-Post` or
+    /// `Accept-Patch` header of the response.
+    pub(crate) fn unsupported_media_type(method: &Method, media_types: &[&str]) -> Error {
+        let mut inner = crate::Error::builder()
+            .status(StatusCode::UNSUPPORTED_MEDIA_TYPE)
+            .detail(&format!("expected one of {}", media_types.join(", ")))
+            .build();
+
+        if let Some((name, value)) = crate::routing::accept_header(method, media_types) {
+            inner.set_header(name, value);
+        }
+
+        Self::invalid(inner)
+    }
+
     pub(crate) fn internal_error() -> Error {
         crate::Error::from(StatusCode::BAD_REQUEST).into()
     }
@@ -82,3 +103,14 @@ impl From<crate::Error> for Error {
         }
     }
 }
+
+impl<T> From<limit::Error<T>> for Error {
+    fn from(err: limit::Error<T>) -> Self {
+        if err.is_limit_exceeded() {
+            crate::Error::from(StatusCode::PAYLOAD_TOO_LARGE).into()
+        } else {
+            // TODO: Is there a better way to handle errors?
+            Error::internal_error()
+        }
+    }
+}
@@ -0,0 +1,946 @@
+//! Extract `multipart/form-data` request bodies.
+//!
+//! A [`Multipart`] argument streams the parts of the request body as they
+//! arrive, without buffering the body in memory:
+//!
+//! ```rust
+//! # #[macro_use] extern crate tower_web;
+//! # extern crate futures;
+//! use tower_web::extract::multipart::Multipart;
+//! use futures::{Future, Stream};
+//! use futures::future::poll_fn;
+//!
+//! struct MyApp;
+//!
+//! impl_web! {
+//!     impl MyApp {
+//!         #[post("/upload")]
+//!         fn upload(&self, body: Multipart) -> impl Future<Item = String, Error = ()> {
+//!             let mut body = body;
+//!             let mut parts = vec![];
+//!             let mut current = None;
+//!
+//!             poll_fn(move || loop {
+//!                 if current.is_some() {
+//!                     match futures::try_ready!(body.poll_data()) {
+//!                         Some(data) => *current.as_mut().unwrap() += data.len(),
+//!                         None => parts.push(current.take().unwrap()),
+//!                     }
+//!                 } else if futures::try_ready!(body.poll()).is_some() {
+//!                     current = Some(0);
+//!                 } else {
+//!                     return Ok(format!("part lengths: {:?}", parts).into());
+//!                 }
+//!             })
+//!             .map_err(|_: tower_web::extract::Error| ())
+//!         }
+//!     }
+//! }
+//! # fn main() {}
+//! ```
+//!
+//! `derive(Extract)` types can also be deserialized from `multipart/form-data`
+//! request bodies. Text parts populate the field of the same name and file
+//! parts populate [`TempFile`] fields.
+//!
+//! [`Multipart`]: struct.Multipart.html
+//! [`TempFile`]: struct.TempFile.html
+
+use crate::codegen::CallSite;
+use crate::extract::{body_stream, BodyStream, Context, Error, Extract, Immediate};
+use crate::util::BufStream;
+use crate::util::buf_stream::IntoBytes;
+
+use bytes::{Buf, Bytes, BytesMut};
+use futures::{Future, Poll, Stream, try_ready};
+use headers::{ContentType, HeaderMapExt};
+use http::header::{self, HeaderMap, HeaderName, HeaderValue};
+use httparse;
+use mime::{self, Mime};
+use serde::de::{self, Deserialize, DeserializeSeed, Deserializer, IntoDeserializer, Visitor};
+use serde::forward_to_deserialize_any;
+
+use std::env;
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Cursor, Read, Seek, SeekFrom, Write};
+use std::marker::PhantomData;
+use std::mem;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::vec;
+
+/// A streaming `multipart/form-data` request body.
+///
+/// The parts of the body are yielded by the `Stream` implementation. The data
+/// of the part yielded last is read using `poll_data`. Any data that is not
+/// read is skipped when polling for the next part.
+///
+/// `Multipart` must be extracted from the request body, i.e. the argument must
+/// be named `body`. Any other argument fails every request with `500 Internal
+/// Server Error`. A request without a `multipart/form-data` body is rejected
+/// with `415 Unsupported Media Type`, and the body is subject to the request
+/// body limit.
+pub struct Multipart {
+    parser: Parser<BodyStream>,
+}
+
+/// The head of a part of a `multipart/form-data` request body.
+#[derive(Debug)]
+pub struct Part {
+    headers: HeaderMap,
+    name: Option<String>,
+    filename: Option<String>,
+}
+
+/// A file uploaded in a `multipart/form-data` request body.
+///
+/// The contents of the file are kept in memory up to 256KB. Larger files are
+/// written to a temporary file, which is removed when the `TempFile` is
+/// dropped unless it has been persisted.
+///
+/// The contents are read using the `Read` implementation.
+#[derive(Debug)]
+pub struct TempFile {
+    filename: Option<String>,
+    content_type: Option<String>,
+    len: u64,
+    spool: Spool,
+}
+
+#[derive(Debug)]
+enum Spool {
+    Memory(Cursor<Vec<u8>>),
+    File(File, TempPath),
+}
+
+/// Path of a temporary file, removed on drop.
+#[derive(Debug)]
+struct TempPath {
+    path: PathBuf,
+    keep: bool,
+}
+
+/// Collects the text and file parts of a `multipart/form-data` body.
+#[derive(Debug)]
+pub(crate) struct Collect<B> {
+    parser: Parser<B>,
+    fields: Vec<(String, Field)>,
+    current: Option<(String, Field)>,
+}
+
+/// The value of a collected part.
+#[derive(Debug)]
+pub(crate) enum Field {
+    Text(Vec<u8>),
+    File(TempFile),
+}
+
+/// Incrementally parses a `multipart/form-data` body.
+#[derive(Debug)]
+struct Parser<B> {
+    body: B,
+    buf: BytesMut,
+
+    /// `\r\n--` followed by the boundary.
+    delimiter: Bytes,
+
+    state: ParseState,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ParseState {
+    /// Reading the data of a part, or the preamble.
+    Data,
+
+    /// A delimiter has been read, the next two bytes tell if another part
+    /// follows.
+    Delimiter,
+
+    /// Reading the headers of a part.
+    Headers,
+
+    /// The closing delimiter has been read.
+    Done,
+}
+
+/// Moves a `TempFile` into `TempFile`'s `Deserialize` implementation.
+///
+/// The file is handed over as a sequence of its filename, content type,
+/// length and contents. The contents are either the data kept in memory or
+/// the path of the temporary file, which the receiving `TempFile` takes
+/// ownership of.
+pub(crate) struct TempFileSeq<E> {
+    parts: vec::IntoIter<FilePart>,
+    _error: PhantomData<E>,
+}
+
+enum FilePart {
+    Text(Option<String>),
+    Len(u64),
+    Memory(Vec<u8>),
+    File(TempPath),
+}
+
+struct FilePartDeserializer<E> {
+    part: FilePart,
+    _error: PhantomData<E>,
+}
+
+/// Name `TempFile` passes to `deserialize_newtype_struct`. Deserializers
+/// supporting file uploads respond by visiting the sequence returned by
+/// `TempFile::into_seq`.
+pub(crate) const TEMP_FILE_TOKEN: &str = "$tower_web::extract::multipart::TempFile";
+
+/// Part data is kept in memory up to this size.
+const SPOOL_LIMIT: usize = 256 * 1024;
+
+/// Maximum number of headers in a part.
+const MAX_HEADERS: usize = 32;
+
+/// Maximum size of the headers of a part.
+const MAX_HEAD_LEN: usize = 8 * 1024;
+
+// ===== impl Multipart =====
+
+impl Multipart {
+    /// Attempt to pull out the next chunk of data of the current part.
+    ///
+    /// Returns `None` once all of the data of the current part has been read.
+    pub fn poll_data(&mut self) -> Poll<Option<Bytes>, Error> {
+        self.parser.poll_data()
+    }
+}
+
+impl Stream for Multipart {
+    type Item = Part;
+    type Error = Error;
+
+    fn poll(&mut self) -> Poll<Option<Part>, Error> {
+        self.parser.poll_part()
+    }
+}
+
+impl<B> Extract<B> for Multipart
+where
+    B: BufStream + Send + 'static,
+    B::Item: IntoBytes,
+{
+    type Future = Immediate<Multipart>;
+
+    fn extract(_: &Context<'_>) -> Self::Future {
+        Immediate::err(body_stream::not_body_argument("Multipart"))
+    }
+
+    fn extract_body(ctx: &Context<'_>, body: B) -> Self::Future {
+        let res = ctx.request().headers()
+            .typed_get::<ContentType>()
+            .and_then(|value| boundary(&Mime::from(value)))
+            .map(|boundary| {
+                let body = BodyStream::limited(ctx.limit_body(body));
+
+                Multipart {
+                    parser: Parser::new(body, &boundary),
+                }
+            })
+            .ok_or_else(|| {
+                Error::unsupported_media_type(ctx.request().method(), &["multipart/form-data"])
+            });
+
+        Immediate::result(res)
+    }
+
+    fn requires_body(callsite: &CallSite) -> bool {
+        callsite.requires_body()
+    }
+}
+
+impl fmt::Debug for Multipart {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("Multipart")
+            .field("state", &self.parser.state)
+            .finish()
+    }
+}
+
+/// Returns the boundary of a `multipart/form-data` content type.
+pub(crate) fn boundary(mime: &Mime) -> Option<String> {
+    if mime.type_() != mime::MULTIPART || mime.subtype() != mime::FORM_DATA {
+        return None;
+    }
+
+    mime.get_param(mime::BOUNDARY)
+        .map(|boundary| boundary.as_str().trim_matches('"').to_string())
+        .filter(|boundary| !boundary.is_empty())
+}
+
+// ===== impl Part =====
+
+impl Part {
+    fn parse(head: &[u8]) -> Result<Part, Error> {
+        let mut headers = [httparse::EMPTY_HEADER; MAX_HEADERS];
+
+        let parsed = match httparse::parse_headers(head, &mut headers) {
+            Ok(httparse::Status::Complete((_, parsed))) => parsed,
+            _ => return Err(Error::invalid_argument(&"invalid multipart headers")),
+        };
+
+        let mut headers = HeaderMap::new();
+
+        for header in parsed {
+            let name = HeaderName::from_bytes(header.name.as_bytes());
+            let value = HeaderValue::from_bytes(header.value);
+
+            match (name, value) {
+                (Ok(name), Ok(value)) => {
+                    headers.append(name, value);
+                }
+                _ => return Err(Error::invalid_argument(&"invalid multipart headers")),
+            }
+        }
+
+        let mut name = None;
+        let mut filename = None;
+
+        if let Some(value) = headers.get(header::CONTENT_DISPOSITION) {
+            let value = value.to_str()
+                .map_err(|_| Error::invalid_argument(&"invalid multipart headers"))?;
+
+            for (key, value) in disposition_params(value) {
+                match &key[..] {
+                    "name" => name = Some(value),
+                    "filename" => filename = Some(value),
+                    _ => {}
+                }
+            }
+        }
+
+        Ok(Part {
+            headers,
+            name,
+            filename,
+        })
+    }
+
+    /// The headers of the part.
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    /// The name of the form field, from the `Content-Disposition` header.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_ref().map(|name| &name[..])
+    }
+
+    /// The name of the uploaded file, from the `Content-Disposition` header.
+    ///
+    /// Only file parts have a file name.
+    pub fn filename(&self) -> Option<&str> {
+        self.filename.as_ref().map(|filename| &filename[..])
+    }
+
+    /// The content type of the part, if specified.
+    pub fn content_type(&self) -> Option<&str> {
+        self.headers.get(header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+    }
+}
+
+/// Returns the parameters of a `Content-Disposition` header value.
+fn disposition_params(value: &str) -> Vec<(String, String)> {
+    let mut params = vec![];
+
+    // Skip the disposition type
+    let mut rest = match value.find(';') {
+        Some(pos) => &value[pos + 1..],
+        None => return params,
+    };
+
+    while let Some(eq) = rest.find('=') {
+        let key = rest[..eq].trim().to_ascii_lowercase();
+        rest = rest[eq + 1..].trim_start();
+
+        let value = if rest.starts_with('"') {
+            let end = rest[1..].find('"')
+                .map(|pos| pos + 1)
+                .unwrap_or(rest.len());
+
+            let value = &rest[1..end];
+            rest = &rest[(end + 1).min(rest.len())..];
+            value
+        } else {
+            let end = rest.find(';').unwrap_or(rest.len());
+
+            let value = rest[..end].trim();
+            rest = &rest[end..];
+            value
+        };
+
+        params.push((key, value.to_string()));
+
+        match rest.find(';') {
+            Some(pos) => rest = &rest[pos + 1..],
+            None => break,
+        }
+    }
+
+    params
+}
+
+// ===== impl TempFile =====
+
+impl TempFile {
+    fn new(filename: Option<String>, content_type: Option<String>) -> TempFile {
+        TempFile {
+            filename,
+            content_type,
+            len: 0,
+            spool: Spool::Memory(Cursor::new(vec![])),
+        }
+    }
+
+    /// The name of the file, as provided by the client.
+    pub fn filename(&self) -> Option<&str> {
+        self.filename.as_ref().map(|filename| &filename[..])
+    }
+
+    /// The content type of the file, as provided by the client.
+    pub fn content_type(&self) -> Option<&str> {
+        self.content_type.as_ref().map(|content_type| &content_type[..])
+    }
+
+    /// The size of the file, in bytes.
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Returns `true` if the file is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The path of the temporary file, or `None` if the contents are kept in
+    /// memory.
+    pub fn path(&self) -> Option<&Path> {
+        match self.spool {
+            Spool::Memory(_) => None,
+            Spool::File(_, ref temp) => Some(&temp.path),
+        }
+    }
+
+    /// Write the contents of the file to `path`.
+    ///
+    /// Temporary files are moved to `path` when possible.
+    pub fn persist<P: AsRef<Path>>(mut self, path: P) -> io::Result<()> {
+        let path = path.as_ref();
+
+        match self.spool {
+            Spool::Memory(ref buf) => fs::write(path, buf.get_ref()),
+            Spool::File(_, ref mut temp) => {
+                if fs::rename(&temp.path, path).is_ok() {
+                    temp.keep = true;
+                    return Ok(());
+                }
+
+                fs::copy(&temp.path, path).map(|_| ())
+            }
+        }
+    }
+
+    fn write(&mut self, data: &[u8]) -> io::Result<()> {
+        self.len += data.len() as u64;
+
+        if let Spool::Memory(ref mut buf) = self.spool {
+            if buf.get_ref().len() + data.len() <= SPOOL_LIMIT {
+                buf.get_mut().extend_from_slice(data);
+                return Ok(());
+            }
+        }
+
+        if let Spool::Memory(ref mut buf) = self.spool {
+            let (mut file, temp) = TempPath::create()?;
+            file.write_all(buf.get_ref())?;
+
+            self.spool = Spool::File(file, temp);
+        }
+
+        match self.spool {
+            Spool::File(ref mut file, _) => file.write_all(data),
+            Spool::Memory(_) => unreachable!(),
+        }
+    }
+
+    /// Returns the sequence moving `self` into `TempFile`'s `Deserialize`
+    /// implementation.
+    pub(crate) fn into_seq<E>(self) -> TempFileSeq<E> {
+        let contents = match self.spool {
+            Spool::Memory(buf) => FilePart::Memory(buf.into_inner()),
+            // The file is reopened from its path
+            Spool::File(_, temp) => FilePart::File(temp),
+        };
+
+        let parts = vec![
+            FilePart::Text(self.filename),
+            FilePart::Text(self.content_type),
+            FilePart::Len(self.len),
+            contents,
+        ];
+
+        TempFileSeq {
+            parts: parts.into_iter(),
+            _error: PhantomData,
+        }
+    }
+
+    /// Prepare the file for reading once all data has been written.
+    fn finish(&mut self) -> io::Result<()> {
+        if let Spool::File(ref mut file, _) = self.spool {
+            file.flush()?;
+            file.seek(SeekFrom::Start(0))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Read for TempFile {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self.spool {
+            Spool::Memory(ref mut cursor) => cursor.read(buf),
+            Spool::File(ref mut file, _) => file.read(buf),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for TempFile {
+    fn deserialize<D>(deserializer: D) -> Result<TempFile, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct FileVisitor;
+
+        impl<'de> Visitor<'de> for FileVisitor {
+            type Value = TempFile;
+
+            fn expecting(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+                fmt.write_str("a file upload")
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<TempFile, A::Error>
+            where
+                A: de::SeqAccess<'de>,
+            {
+                let filename = seq.next_element()?
+                    .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+                let content_type = seq.next_element()?
+                    .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+                let len = seq.next_element()?
+                    .ok_or_else(|| de::Error::invalid_length(2, &self))?;
+                let spool = seq.next_element()?
+                    .ok_or_else(|| de::Error::invalid_length(3, &self))?;
+
+                Ok(TempFile {
+                    filename,
+                    content_type,
+                    len,
+                    spool,
+                })
+            }
+        }
+
+        deserializer.deserialize_newtype_struct(TEMP_FILE_TOKEN, FileVisitor)
+    }
+}
+
+impl<'de> Deserialize<'de> for Spool {
+    fn deserialize<D>(deserializer: D) -> Result<Spool, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct SpoolVisitor;
+
+        impl<'de> Visitor<'de> for SpoolVisitor {
+            type Value = Spool;
+
+            fn expecting(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+                fmt.write_str("the contents of a file upload")
+            }
+
+            fn visit_byte_buf<E: de::Error>(self, data: Vec<u8>) -> Result<Spool, E> {
+                Ok(Spool::Memory(Cursor::new(data)))
+            }
+
+            fn visit_str<E: de::Error>(self, path: &str) -> Result<Spool, E> {
+                let temp = TempPath::from_path(PathBuf::from(path))
+                    .ok_or_else(|| E::custom("not a temporary file path"))?;
+
+                let file = OpenOptions::new()
+                    .read(true)
+                    .write(true)
+                    .open(&temp.path)
+                    .map_err(E::custom)?;
+
+                Ok(Spool::File(file, temp))
+            }
+        }
+
+        deserializer.deserialize_any(SpoolVisitor)
+    }
+}
+
+// ===== impl TempFileSeq =====
+
+impl<'de, E> de::SeqAccess<'de> for TempFileSeq<E>
+where
+    E: de::Error,
+{
+    type Error = E;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, E>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        match self.parts.next() {
+            Some(part) => {
+                let deserializer = FilePartDeserializer {
+                    part,
+                    _error: PhantomData,
+                };
+
+                seed.deserialize(deserializer).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.parts.len())
+    }
+}
+
+impl<'de, E> Deserializer<'de> for FilePartDeserializer<E>
+where
+    E: de::Error,
+{
+    type Error = E;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, E>
+    where
+        V: Visitor<'de>,
+    {
+        match self.part {
+            FilePart::Text(None) => visitor.visit_none(),
+            FilePart::Text(Some(text)) => visitor.visit_some(text.into_deserializer()),
+            FilePart::Len(len) => visitor.visit_u64(len),
+            FilePart::Memory(data) => visitor.visit_byte_buf(data),
+            FilePart::File(mut temp) => {
+                let path = temp.path.to_str()
+                    .ok_or_else(|| E::custom("temporary file path is not valid UTF-8"))?
+                    .to_string();
+
+                // The file now belongs to the receiving `TempFile`
+                temp.keep = true;
+
+                visitor.visit_string(path)
+            }
+        }
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+// ===== impl TempPath =====
+
+impl TempPath {
+    fn create() -> io::Result<(File, TempPath)> {
+        static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+        loop {
+            let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+            let path = env::temp_dir()
+                .join(format!("{}{}", TempPath::prefix(), id));
+
+            let res = OpenOptions::new()
+                .read(true)
+                .write(true)
+                .create_new(true)
+                .open(&path);
+
+            match res {
+                Ok(file) => return Ok((file, TempPath { path, keep: false })),
+                Err(ref e) if e.kind() == io::ErrorKind::AlreadyExists => {}
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// Take ownership of a temporary file handed over by `TempFile::into_seq`.
+    ///
+    /// Returns `None` if `path` was not created by `TempPath::create`, so
+    /// that arbitrary files are never removed.
+    fn from_path(path: PathBuf) -> Option<TempPath> {
+        let created = path.parent() == Some(env::temp_dir().as_path()) &&
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .map(|name| name.starts_with(&TempPath::prefix()))
+                .unwrap_or(false);
+
+        if created {
+            Some(TempPath { path, keep: false })
+        } else {
+            None
+        }
+    }
+
+    /// Prefix of the temporary file names created by this process.
+    fn prefix() -> String {
+        format!("tower-web-{}-", process::id())
+    }
+}
+
+impl Drop for TempPath {
+    fn drop(&mut self) {
+        if !self.keep {
+            let _ = fs::remove_file(&self.path);
+        }
+    }
+}
+
+// ===== impl Collect =====
+
+impl<B> Collect<B>
+where
+    B: BufStream,
+    Error: From<B::Error>,
+{
+    pub(crate) fn new(body: B, boundary: &str) -> Collect<B> {
+        Collect {
+            parser: Parser::new(body, boundary),
+            fields: vec![],
+            current: None,
+        }
+    }
+}
+
+impl<B> Future for Collect<B>
+where
+    B: BufStream,
+    Error: From<B::Error>,
+{
+    type Item = Vec<(String, Field)>;
+    type Error = Error;
+
+    fn poll(&mut self) -> Poll<Self::Item, Error> {
+        loop {
+            if let Some((_, ref mut field)) = self.current {
+                match try_ready!(self.parser.poll_data()) {
+                    Some(data) => {
+                        field.write(&data)?;
+                    }
+                    None => {
+                        let (name, mut field) = self.current.take().unwrap();
+                        field.finish()?;
+                        self.fields.push((name, field));
+                    }
+                }
+
+                continue;
+            }
+
+            match try_ready!(self.parser.poll_part()) {
+                Some(part) => {
+                    // Parts without a name are skipped
+                    if let Some(name) = part.name.clone() {
+                        let field = match part.filename.clone() {
+                            Some(filename) => {
+                                let content_type = part.content_type().map(|s| s.to_string());
+                                Field::File(TempFile::new(Some(filename), content_type))
+                            }
+                            None => Field::Text(vec![]),
+                        };
+
+                        self.current = Some((name, field));
+                    }
+                }
+                None => {
+                    return Ok(mem::replace(&mut self.fields, vec![]).into());
+                }
+            }
+        }
+    }
+}
+
+// ===== impl Field =====
+
+impl Field {
+    fn write(&mut self, data: &[u8]) -> Result<(), Error> {
+        match *self {
+            Field::Text(ref mut text) => {
+                text.extend_from_slice(data);
+                Ok(())
+            }
+            Field::File(ref mut file) => {
+                file.write(data).map_err(|_| Error::internal_error())
+            }
+        }
+    }
+
+    fn finish(&mut self) -> Result<(), Error> {
+        match *self {
+            Field::Text(_) => Ok(()),
+            Field::File(ref mut file) => {
+                file.finish().map_err(|_| Error::internal_error())
+            }
+        }
+    }
+}
+
+// ===== impl Parser =====
+
+impl<B> Parser<B>
+where
+    B: BufStream,
+    Error: From<B::Error>,
+{
+    fn new(body: B, boundary: &str) -> Parser<B> {
+        let mut delimiter = BytesMut::with_capacity(boundary.len() + 4);
+        delimiter.extend_from_slice(b"\r\n--");
+        delimiter.extend_from_slice(boundary.as_bytes());
+
+        // The first delimiter is not preceded by a line break. Adding one
+        // allows matching all delimiters the same way.
+        let mut buf = BytesMut::with_capacity(8 * 1024);
+        buf.extend_from_slice(b"\r\n");
+
+        Parser {
+            body,
+            buf,
+            delimiter: delimiter.freeze(),
+            state: ParseState::Data,
+        }
+    }
+
+    fn poll_part(&mut self) -> Poll<Option<Part>, Error> {
+        use self::ParseState::*;
+
+        loop {
+            match self.state {
+                Data => {
+                    // Skip any data of the current part that was not read.
+                    while try_ready!(self.poll_data()).is_some() {}
+                }
+                Delimiter => {
+                    if self.buf.len() < 2 {
+                        try_ready!(self.fill_or_eof());
+                        continue;
+                    }
+
+                    if self.buf.starts_with(b"--") {
+                        self.state = Done;
+                    } else if self.buf.starts_with(b"\r\n") {
+                        self.buf.split_to(2);
+                        self.state = Headers;
+                    } else {
+                        return Err(Error::invalid_argument(&"invalid multipart delimiter"));
+                    }
+                }
+                Headers => {
+                    let len = if self.buf.starts_with(b"\r\n") {
+                        Some(2)
+                    } else {
+                        find(&self.buf, b"\r\n\r\n").map(|pos| pos + 4)
+                    };
+
+                    match len {
+                        Some(len) => {
+                            let head = self.buf.split_to(len);
+                            let part = Part::parse(&head)?;
+
+                            self.state = Data;
+
+                            return Ok(Some(part).into());
+                        }
+                        None => {
+                            if self.buf.len() > MAX_HEAD_LEN {
+                                return Err(Error::invalid_argument(&"multipart headers too large"));
+                            }
+
+                            try_ready!(self.fill_or_eof());
+                        }
+                    }
+                }
+                Done => {
+                    return Ok(None.into());
+                }
+            }
+        }
+    }
+
+    fn poll_data(&mut self) -> Poll<Option<Bytes>, Error> {
+        loop {
+            if self.state != ParseState::Data {
+                return Ok(None.into());
+            }
+
+            if let Some(pos) = find(&self.buf, &self.delimiter) {
+                let data = self.buf.split_to(pos).freeze();
+                self.buf.split_to(self.delimiter.len());
+                self.state = ParseState::Delimiter;
+
+                if data.is_empty() {
+                    return Ok(None.into());
+                }
+
+                return Ok(Some(data).into());
+            }
+
+            // Hold back enough data to match a delimiter split across
+            // buffers.
+            let keep = self.delimiter.len() - 1;
+
+            if self.buf.len() > keep {
+                let len = self.buf.len() - keep;
+                return Ok(Some(self.buf.split_to(len).freeze()).into());
+            }
+
+            try_ready!(self.fill_or_eof());
+        }
+    }
+
+    /// Read the next buffer of the body, failing if the body is complete.
+    fn fill_or_eof(&mut self) -> Poll<(), Error> {
+        let res = self.body.poll().map_err(Error::from);
+
+        match try_ready!(res) {
+            Some(mut buf) => {
+                self.buf.reserve(buf.remaining());
+
+                while buf.has_remaining() {
+                    let len = {
+                        let bytes = buf.bytes();
+                        self.buf.extend_from_slice(bytes);
+                        bytes.len()
+                    };
+
+                    buf.advance(len);
+                }
+
+                Ok(().into())
+            }
+            None => {
+                Err(Error::invalid_argument(&"unexpected end of multipart body"))
+            }
+        }
+    }
+}
+
+/// Returns the position of the first occurrence of `needle` in `haystack`.
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len())
+        .position(|window| window == needle)
+}
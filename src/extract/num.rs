@@ -44,7 +44,7 @@ macro_rules! num_extract_impls {
                         Body => {
                             unimplemented!();
                         }
-                        Unknown => {
+                        Captures(_) | Unknown => {
                             unimplemented!();
                         }
                     }
@@ -52,7 +52,7 @@ impl<B: BufStream> Extract<B> for OsString {
             Body => {
                 unimplemented!();
             }
-            Unknown => {
+            Captures(_) | Unknown => {
                 unimplemented!();
             }
         }
@@ -2,16 +2,29 @@
 
 use crate::codegen::CallSite;
 use crate::extract::{Context, Error, Extract, ExtractFuture};
+use crate::extract::multipart;
+use http::header::{HeaderMap, HeaderName};
 use http::status::StatusCode;
-use crate::util::buf_stream::{self, BufStream};
+use crate::util::buf_stream::{self, BufStream, Limit};
 
 use futures::{Future, Poll, try_ready};
 use headers::{ContentType, HeaderMapExt};
 use mime::Mime;
-use serde::de::DeserializeOwned;
+use percent_encoding::percent_decode;
+use serde::de::{self, DeserializeOwned, Deserializer, IntoDeserializer, Visitor};
+use serde::de::value::MapDeserializer;
+use serde_plain;
 use serde_urlencoded;
 use serde_json;
 
+use std::borrow::Cow;
+use std::fmt;
+use std::str;
+
+mod path;
+
+use self::path::Path;
+
 /*
  * # TODO: Move this module to `codegen`?
  */
@@ -26,7 +39,8 @@ pub struct SerdeFuture<T, B> {
 #[derive(Debug)]
 enum State<T, B> {
     Complete(Result<T, Option<Error>>),
-    Body(buf_stream::Collect<B, Vec<u8>>),
+    Body(buf_stream::Collect<Limit<B>, Vec<u8>>),
+    Multipart(multipart::Collect<Limit<B>>),
 }
 
 impl<B: BufStream> Extract<B> for serde_json::Value {
@@ -64,11 +78,36 @@ where T: DeserializeOwned,
         use crate::codegen::Source::*;
 
         match ctx.callsite().source() {
-            Capture(_) => {
-                unimplemented!();
+            Capture(idx) => {
+                let path = ctx.request().uri().path();
+                let capture = ctx.captures().get(*idx, path);
+
+                let res = decode(capture)
+                    .and_then(|value| {
+                        serde_plain::from_str(&value)
+                            .map_err(|err| Error::invalid_argument(&err))
+                    })
+                    .map_err(Some);
+
+                let state = State::Complete(res);
+
+                SerdeFuture { state, is_json: false }
             }
-            Header(_) => {
-                unimplemented!();
+            Captures(names) => {
+                let res = from_captures(ctx, names)
+                    .map_err(Some);
+
+                let state = State::Complete(res);
+
+                SerdeFuture { state, is_json: false }
+            }
+            Header(name) => {
+                let res = from_headers(ctx, name)
+                    .map_err(Some);
+
+                let state = State::Complete(res);
+
+                SerdeFuture { state, is_json: false }
             }
             QueryString => {
                 let query = ctx.request().uri()
@@ -114,31 +153,51 @@ where T: DeserializeOwned,
             QueryString => {
                 unimplemented!("QueryString");
             }
+            Captures(_) => {
+                unimplemented!("Captures");
+            }
             Body => {
                 if let Some(value) = ctx.request().headers().typed_get::<ContentType>() {
                     let mime = Mime::from(value);
 
                     match (mime.type_().as_str(), mime.subtype().as_str()) {
                         ("application", "json") => {
-                            let state = State::Body(body.collect());
+                            let state = State::Body(ctx.limit_body(body).collect());
+
+                            SerdeFuture { state, is_json: true }
+                        }
+                        // e.g. `application/merge-patch+json`
+                        ("application", _) if mime.suffix() == Some(mime::JSON) => {
+                            let state = State::Body(ctx.limit_body(body).collect());
 
                             SerdeFuture { state, is_json: true }
                         }
                         ("application", "x-www-form-urlencoded") => {
-                            let state = State::Body(body.collect());
+                            let state = State::Body(ctx.limit_body(body).collect());
+
+                            SerdeFuture { state, is_json: false }
+                        }
+                        ("multipart", "form-data") => {
+                            let state = match multipart::boundary(&mime) {
+                                Some(boundary) => {
+                                    State::Multipart(multipart::Collect::new(ctx.limit_body(body), &boundary))
+                                }
+                                None => {
+                                    let err = Error::invalid_argument(&"missing multipart boundary");
+                                    State::Complete(Err(Some(err)))
+                                }
+                            };
 
                             SerdeFuture { state, is_json: false }
                         }
                         _ => {
-                            let err = crate::Error::from(StatusCode::BAD_REQUEST).into();
-                            let state = State::Complete(Err(Some(err)));
+                            let state = State::Complete(Err(Some(Error::unsupported_media_type(ctx.request().method(), MEDIA_TYPES))));
 
                             SerdeFuture { state, is_json: false }
                         }
                     }
                 } else {
-                    let err = crate::Error::from(StatusCode::BAD_REQUEST).into();
-                    let state = State::Complete(Err(Some(err)));
+                    let state = State::Complete(Err(Some(Error::unsupported_media_type(ctx.request().method(), MEDIA_TYPES))));
 
                     SerdeFuture { state, is_json: false }
                 }
@@ -150,6 +209,423 @@ where T: DeserializeOwned,
     }
 }
 
+/// Deserialize a value from every capture of the route, by name.
+fn from_captures<T>(ctx: &Context<'_>, names: &[&str]) -> Result<T, Error>
+where T: DeserializeOwned,
+{
+    let path = ctx.request().uri().path();
+
+    let mut captures = vec![];
+
+    for (idx, name) in names.iter().enumerate() {
+        captures.push((*name, decode(ctx.captures().get(idx, path))?));
+    }
+
+    for (name, value) in ctx.captures().named(path) {
+        captures.push((name, decode(value)?));
+    }
+
+    let captures = captures.iter()
+        .map(|(name, value)| (*name, Value::Text(value)));
+
+    from_map(captures)
+}
+
+/// Deserialize a value from the request headers.
+///
+/// Structs and maps are deserialized from every header, by name. Any other
+/// value is deserialized from the header `name`.
+///
+/// Only the first value of each header is used. Values that are not valid
+/// UTF-8 are ignored.
+fn from_headers<T>(ctx: &Context<'_>, name: &HeaderName) -> Result<T, Error>
+where T: DeserializeOwned,
+{
+    let deserializer = Headers {
+        headers: ctx.request().headers(),
+        name,
+    };
+
+    T::deserialize(deserializer)
+        .map_err(plain_error)
+}
+
+/// Deserialize a value from the fields of a multipart body, by name.
+///
+/// Text fields that are not valid UTF-8 result in `400 Bad Request`, values
+/// that do not match `T` in `422 Unprocessable Entity`.
+fn from_form<T>(form: Vec<(String, multipart::Field)>) -> Result<T, Error>
+where T: DeserializeOwned,
+{
+    let mut fields = vec![];
+    let mut files = vec![];
+
+    for (name, field) in form {
+        match field {
+            multipart::Field::Text(text) => {
+                let text = match String::from_utf8(text) {
+                    Ok(text) => text,
+                    Err(_) => {
+                        let path = Path::field(&name);
+                        return Err(body_error(StatusCode::BAD_REQUEST, &"invalid UTF-8 string", &path));
+                    }
+                };
+
+                fields.push((name, Some(text)));
+            }
+            multipart::Field::File(file) => {
+                fields.push((name, None));
+                files.push(file);
+            }
+        }
+    }
+
+    let mut files = files.into_iter();
+
+    let fields = fields.iter()
+        .map(|(name, text)| {
+            let value = match *text {
+                Some(ref text) => Value::Text(text),
+                None => Value::File(files.next().unwrap()),
+            };
+
+            (&name[..], value)
+        });
+
+    let deserializer = MapDeserializer::<_, PlainError>::new(fields);
+
+    path::deserialize(deserializer)
+        .map_err(|(err, path)| body_error(StatusCode::UNPROCESSABLE_ENTITY, &err, &path))
+}
+
+/// Media types a request body can be deserialized from.
+const MEDIA_TYPES: &[&str] = &[
+    "application/json",
+    "application/x-www-form-urlencoded",
+    "multipart/form-data",
+];
+
+/// Deserialize a JSON request body.
+///
+/// Syntax errors result in `400 Bad Request`, values that do not match `T`
+/// in `422 Unprocessable Entity`.
+fn from_json<T>(body: &[u8]) -> Result<T, Error>
+where T: DeserializeOwned,
+{
+    use serde_json::error::Category;
+
+    let mut de = serde_json::Deserializer::from_slice(body);
+
+    path::deserialize(&mut de)
+        .and_then(|value| {
+            // Reject trailing characters
+            de.end()
+                .map(|_| value)
+                .map_err(|err| (err, Path::default()))
+        })
+        .map_err(|(err, path)| {
+            let status = match err.classify() {
+                Category::Data => StatusCode::UNPROCESSABLE_ENTITY,
+                Category::Io | Category::Syntax | Category::Eof => StatusCode::BAD_REQUEST,
+            };
+
+            body_error(status, &err, &path)
+        })
+}
+
+/// Deserialize a URL encoded request body.
+///
+/// Any body parses as a list of key-value pairs, so errors always are due to
+/// values that do not match `T`.
+fn from_urlencoded<T>(body: &[u8]) -> Result<T, Error>
+where T: DeserializeOwned,
+{
+    let de = serde_urlencoded::Deserializer::new(url::form_urlencoded::parse(body));
+
+    path::deserialize(de)
+        .map_err(|(err, path)| body_error(StatusCode::UNPROCESSABLE_ENTITY, &err, &path))
+}
+
+/// The problem detail names the path to the value that failed to deserialize.
+fn body_error(status: StatusCode, err: &dyn fmt::Display, path: &Path) -> Error {
+    let detail = if path.is_empty() {
+        err.to_string()
+    } else {
+        format!("{}: {}", path, err)
+    };
+
+    let inner = crate::Error::builder()
+        .status(status)
+        .detail(&detail)
+        .build();
+
+    Error::invalid(inner)
+}
+
+fn from_map<'a, T, I>(entries: I) -> Result<T, Error>
+where T: DeserializeOwned,
+      I: Iterator<Item = (&'a str, Value<'a>)>,
+{
+    let deserializer = MapDeserializer::<_, PlainError>::new(entries);
+
+    T::deserialize(deserializer)
+        .map_err(plain_error)
+}
+
+fn plain_error(err: PlainError) -> Error {
+    match err {
+        PlainError::Missing(_) => Error::missing_argument(),
+        PlainError::Invalid(reason) => Error::invalid_argument(&reason),
+    }
+}
+
+/// Percent-decode a capture.
+fn decode(value: &str) -> Result<Cow<'_, str>, Error> {
+    percent_decode(value.as_bytes())
+        .decode_utf8()
+        .map_err(|err| Error::invalid_argument(&err))
+}
+
+/// Deserializes the request headers.
+struct Headers<'a> {
+    headers: &'a HeaderMap,
+
+    /// Header a single value is deserialized from
+    name: &'a HeaderName,
+}
+
+/// A map value.
+enum Value<'a> {
+    /// Deserialized as plain text.
+    Text(&'a str),
+
+    /// A file uploaded in a multipart body.
+    File(multipart::TempFile),
+}
+
+/// Error deserializing a map of values.
+///
+/// Missing fields are tracked separately from invalid values in order to
+/// respond with the appropriate extract error.
+#[derive(Debug)]
+enum PlainError {
+    Missing(String),
+    Invalid(String),
+}
+
+impl<'a> Value<'a> {
+    fn plain(self) -> Result<serde_plain::Deserializer<'a>, PlainError> {
+        match self {
+            Value::Text(value) => Ok(serde_plain::Deserializer::from_str(value)),
+            Value::File(_) => Err(PlainError::Invalid("expected text, found a file".to_string())),
+        }
+    }
+}
+
+impl<'a> IntoDeserializer<'a, PlainError> for Value<'a> {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+macro_rules! forward_to_plain {
+    ($($method:ident($($arg:ident: $ty:ty),*);)*) => {
+        $(
+            fn $method<V>(self, $($arg: $ty,)* visitor: V) -> Result<V::Value, PlainError>
+            where V: Visitor<'a>,
+            {
+                self.plain()?
+                    .$method($($arg,)* visitor)
+                    .map_err(|err| PlainError::Invalid(err.to_string()))
+            }
+        )*
+    }
+}
+
+impl<'a> Deserializer<'a> for Value<'a> {
+    type Error = PlainError;
+
+    forward_to_plain! {
+        deserialize_any();
+        deserialize_bool();
+        deserialize_i8();
+        deserialize_i16();
+        deserialize_i32();
+        deserialize_i64();
+        deserialize_u8();
+        deserialize_u16();
+        deserialize_u32();
+        deserialize_u64();
+        deserialize_f32();
+        deserialize_f64();
+        deserialize_char();
+        deserialize_str();
+        deserialize_string();
+        deserialize_bytes();
+        deserialize_byte_buf();
+        deserialize_unit();
+        deserialize_unit_struct(name: &'static str);
+        deserialize_seq();
+        deserialize_tuple(len: usize);
+        deserialize_tuple_struct(name: &'static str, len: usize);
+        deserialize_map();
+        deserialize_struct(name: &'static str, fields: &'static [&'static str]);
+        deserialize_enum(name: &'static str, variants: &'static [&'static str]);
+        deserialize_identifier();
+        deserialize_ignored_any();
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, PlainError>
+    where V: Visitor<'a>,
+    {
+        match self {
+            Value::Text(_) => {
+                self.plain()?
+                    .deserialize_option(visitor)
+                    .map_err(|err| PlainError::Invalid(err.to_string()))
+            }
+            Value::File(_) => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_newtype_struct<V>(self, name: &'static str, visitor: V)
+        -> Result<V::Value, PlainError>
+    where V: Visitor<'a>,
+    {
+        match self {
+            Value::File(file) if name == multipart::TEMP_FILE_TOKEN => {
+                visitor.visit_seq(file.into_seq())
+            }
+            _ => {
+                self.plain()?
+                    .deserialize_newtype_struct(name, visitor)
+                    .map_err(|err| PlainError::Invalid(err.to_string()))
+            }
+        }
+    }
+}
+
+impl<'a> Headers<'a> {
+    /// Every header, by name.
+    fn map(self) -> MapDeserializer<'a, impl Iterator<Item = (&'a str, Value<'a>)>, PlainError> {
+        let headers = self.headers;
+
+        let entries = headers.keys()
+            .filter_map(move |name| {
+                let value = headers.get(name)?;
+                let value = str::from_utf8(value.as_bytes()).ok()?;
+
+                Some((name.as_str(), Value::Text(value)))
+            });
+
+        MapDeserializer::new(entries)
+    }
+
+    /// The value of the header `name`.
+    fn value(&self) -> Option<Value<'a>> {
+        let value = self.headers.get(self.name)?;
+        let value = str::from_utf8(value.as_bytes()).ok()?;
+
+        Some(Value::Text(value))
+    }
+
+    fn require_value(self) -> Result<Value<'a>, PlainError> {
+        self.value()
+            .ok_or_else(|| PlainError::Missing(self.name.as_str().to_string()))
+    }
+}
+
+macro_rules! forward_to_value {
+    ($($method:ident($($arg:ident: $ty:ty),*);)*) => {
+        $(
+            fn $method<V>(self, $($arg: $ty,)* visitor: V) -> Result<V::Value, PlainError>
+            where V: Visitor<'a>,
+            {
+                self.require_value()?
+                    .$method($($arg,)* visitor)
+            }
+        )*
+    }
+}
+
+impl<'a> Deserializer<'a> for Headers<'a> {
+    type Error = PlainError;
+
+    forward_to_value! {
+        deserialize_any();
+        deserialize_bool();
+        deserialize_i8();
+        deserialize_i16();
+        deserialize_i32();
+        deserialize_i64();
+        deserialize_u8();
+        deserialize_u16();
+        deserialize_u32();
+        deserialize_u64();
+        deserialize_f32();
+        deserialize_f64();
+        deserialize_char();
+        deserialize_str();
+        deserialize_string();
+        deserialize_bytes();
+        deserialize_byte_buf();
+        deserialize_unit();
+        deserialize_unit_struct(name: &'static str);
+        deserialize_newtype_struct(name: &'static str);
+        deserialize_seq();
+        deserialize_tuple(len: usize);
+        deserialize_tuple_struct(name: &'static str, len: usize);
+        deserialize_enum(name: &'static str, variants: &'static [&'static str]);
+        deserialize_identifier();
+        deserialize_ignored_any();
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, PlainError>
+    where V: Visitor<'a>,
+    {
+        match self.value() {
+            Some(value) => value.deserialize_option(visitor),
+            None => visitor.visit_none(),
+        }
+    }
+
+    fn deserialize_map<V>(self, visitor: V) -> Result<V::Value, PlainError>
+    where V: Visitor<'a>,
+    {
+        self.map().deserialize_map(visitor)
+    }
+
+    fn deserialize_struct<V>(self, name: &'static str, fields: &'static [&'static str], visitor: V)
+        -> Result<V::Value, PlainError>
+    where V: Visitor<'a>,
+    {
+        self.map().deserialize_struct(name, fields, visitor)
+    }
+}
+
+impl de::Error for PlainError {
+    fn custom<T: fmt::Display>(msg: T) -> PlainError {
+        PlainError::Invalid(msg.to_string())
+    }
+
+    fn missing_field(field: &'static str) -> PlainError {
+        PlainError::Missing(field.to_string())
+    }
+}
+
+impl fmt::Display for PlainError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            PlainError::Missing(ref field) => write!(fmt, "missing field `{}`", field),
+            PlainError::Invalid(ref reason) => reason.fmt(fmt),
+        }
+    }
+}
+
+impl ::std::error::Error for PlainError {}
+
 impl<T, B> ExtractFuture for SerdeFuture<T, B>
 where T: DeserializeOwned,
       B: BufStream,
@@ -168,26 +644,20 @@ where T: DeserializeOwned,
                     return Ok(().into());
                 }
                 Body(ref mut collect) => {
-                    let res = collect.poll()
-                        // TODO: Is there a better way to handle errors?
-                        .map_err(|_| Error::internal_error());
+                    let res = collect.poll().map_err(Error::from);
 
                     let res = try_ready!(res);
                     
                     if self.is_json == true {
-                        ::serde_json::from_slice(&res[..])
-                            .map_err(|_| {
-                                // TODO: Handle error better
-                                Some(Error::internal_error())
-                        })
+                        from_json(&res[..]).map_err(Some)
                     } else {
-                        ::serde_urlencoded::from_bytes(&res[..])
-                            .map_err(|_| {
-                                // TODO: Handle error better
-                                Some(Error::internal_error())
-                        })
+                        from_urlencoded(&res[..]).map_err(Some)
                     }
                 }
+                Multipart(ref mut collect) => {
+                    let form = try_ready!(collect.poll());
+                    from_form(form).map_err(Some)
+                }
             };
 
             self.state = State::Complete(res);
@@ -0,0 +1,404 @@
+//! Track the path to the value that failed to deserialize.
+//!
+//! Deserializers report what went wrong, but not where in the data structure
+//! it happened. `deserialize` wraps a deserializer to keep track of the map
+//! keys and sequence indices leading to the value being deserialized, which
+//! is left in place when an error occurs.
+//!
+//! This is the approach of the `serde_path_to_error` crate. That crate is not
+//! a dependency because it is not available in the registry tower-web is
+//! built against. Only the subset needed to report body errors is implemented
+//! here.
+
+use serde::de::{self, Deserialize, DeserializeSeed, Visitor};
+
+use std::cell::RefCell;
+use std::fmt;
+
+/// Path to a value, e.g. `user.emails[1]`.
+#[derive(Debug, Default)]
+pub(crate) struct Path {
+    segments: Vec<Segment>,
+}
+
+#[derive(Debug)]
+enum Segment {
+    Field(String),
+    Index(usize),
+    Unknown,
+}
+
+#[derive(Debug, Default)]
+struct Track {
+    path: RefCell<Path>,
+
+    /// The last map key, set while deserializing the key.
+    key: RefCell<Option<String>>,
+}
+
+/// Wraps a deserializer, a visitor or a seed.
+///
+/// `key` is set when wrapping a map key, which is then recorded.
+struct Wrap<'a, T> {
+    inner: T,
+    track: &'a Track,
+    key: bool,
+}
+
+struct SeqAccess<'a, T> {
+    inner: T,
+    track: &'a Track,
+    index: usize,
+}
+
+struct MapAccess<'a, T> {
+    inner: T,
+    track: &'a Track,
+}
+
+/// Deserialize a `T` from `deserializer`, returning the path to the value that
+/// failed to deserialize along with the error.
+pub(crate) fn deserialize<'de, D, T>(deserializer: D) -> Result<T, (D::Error, Path)>
+where
+    D: de::Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    let track = Track::default();
+
+    T::deserialize(Wrap::new(deserializer, &track, false))
+        .map_err(|err| (err, track.path.into_inner()))
+}
+
+// ===== impl Path =====
+
+impl Path {
+    /// Path to the top level field `name`.
+    pub(crate) fn field(name: &str) -> Path {
+        Path {
+            segments: vec![Segment::Field(name.to_string())],
+        }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    fn push(&mut self, segment: Segment) {
+        self.segments.push(segment);
+    }
+
+    fn pop(&mut self) {
+        self.segments.pop();
+    }
+}
+
+impl fmt::Display for Path {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, segment) in self.segments.iter().enumerate() {
+            match *segment {
+                Segment::Field(ref name) => {
+                    if i > 0 {
+                        fmt.write_str(".")?;
+                    }
+
+                    fmt.write_str(name)?;
+                }
+                Segment::Index(index) => write!(fmt, "[{}]", index)?,
+                Segment::Unknown => {
+                    if i > 0 {
+                        fmt.write_str(".")?;
+                    }
+
+                    fmt.write_str("?")?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// ===== impl Wrap =====
+
+impl<'a, T> Wrap<'a, T> {
+    fn new(inner: T, track: &'a Track, key: bool) -> Self {
+        Wrap { inner, track, key }
+    }
+
+    fn wrap<U>(&self, inner: U) -> Wrap<'a, U> {
+        Wrap::new(inner, self.track, self.key)
+    }
+
+    fn record_key(&self, key: &dyn fmt::Display) {
+        if self.key {
+            *self.track.key.borrow_mut() = Some(key.to_string());
+        }
+    }
+}
+
+macro_rules! forward_deserialize {
+    ($($method:ident($($arg:ident: $ty:ty),*);)*) => {
+        $(
+            fn $method<V>(self, $($arg: $ty,)* visitor: V) -> Result<V::Value, D::Error>
+            where
+                V: Visitor<'de>,
+            {
+                let visitor = self.wrap(visitor);
+                self.inner.$method($($arg,)* visitor)
+            }
+        )*
+    };
+}
+
+impl<'a, 'de, D> de::Deserializer<'de> for Wrap<'a, D>
+where
+    D: de::Deserializer<'de>,
+{
+    type Error = D::Error;
+
+    forward_deserialize! {
+        deserialize_any();
+        deserialize_bool();
+        deserialize_i8();
+        deserialize_i16();
+        deserialize_i32();
+        deserialize_i64();
+        deserialize_i128();
+        deserialize_u8();
+        deserialize_u16();
+        deserialize_u32();
+        deserialize_u64();
+        deserialize_u128();
+        deserialize_f32();
+        deserialize_f64();
+        deserialize_char();
+        deserialize_str();
+        deserialize_string();
+        deserialize_bytes();
+        deserialize_byte_buf();
+        deserialize_option();
+        deserialize_unit();
+        deserialize_unit_struct(name: &'static str);
+        deserialize_newtype_struct(name: &'static str);
+        deserialize_seq();
+        deserialize_tuple(len: usize);
+        deserialize_tuple_struct(name: &'static str, len: usize);
+        deserialize_map();
+        deserialize_struct(name: &'static str, fields: &'static [&'static str]);
+        deserialize_enum(name: &'static str, variants: &'static [&'static str]);
+        deserialize_identifier();
+        deserialize_ignored_any();
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.inner.is_human_readable()
+    }
+}
+
+macro_rules! forward_visit {
+    ($($method:ident($ty:ty);)*) => {
+        $(
+            fn $method<E>(self, value: $ty) -> Result<V::Value, E>
+            where
+                E: de::Error,
+            {
+                self.record_key(&value);
+                self.inner.$method(value)
+            }
+        )*
+    };
+}
+
+impl<'a, 'de, V> Visitor<'de> for Wrap<'a, V>
+where
+    V: Visitor<'de>,
+{
+    type Value = V::Value;
+
+    fn expecting(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.inner.expecting(fmt)
+    }
+
+    forward_visit! {
+        visit_bool(bool);
+        visit_i8(i8);
+        visit_i16(i16);
+        visit_i32(i32);
+        visit_i64(i64);
+        visit_i128(i128);
+        visit_u8(u8);
+        visit_u16(u16);
+        visit_u32(u32);
+        visit_u64(u64);
+        visit_u128(u128);
+        visit_f32(f32);
+        visit_f64(f64);
+        visit_char(char);
+        visit_str(&str);
+        visit_borrowed_str(&'de str);
+        visit_string(String);
+    }
+
+    fn visit_bytes<E>(self, value: &[u8]) -> Result<V::Value, E>
+    where
+        E: de::Error,
+    {
+        self.record_key(&String::from_utf8_lossy(value));
+        self.inner.visit_bytes(value)
+    }
+
+    fn visit_borrowed_bytes<E>(self, value: &'de [u8]) -> Result<V::Value, E>
+    where
+        E: de::Error,
+    {
+        self.record_key(&String::from_utf8_lossy(value));
+        self.inner.visit_borrowed_bytes(value)
+    }
+
+    fn visit_byte_buf<E>(self, value: Vec<u8>) -> Result<V::Value, E>
+    where
+        E: de::Error,
+    {
+        self.record_key(&String::from_utf8_lossy(&value));
+        self.inner.visit_byte_buf(value)
+    }
+
+    fn visit_none<E>(self) -> Result<V::Value, E>
+    where
+        E: de::Error,
+    {
+        self.inner.visit_none()
+    }
+
+    fn visit_unit<E>(self) -> Result<V::Value, E>
+    where
+        E: de::Error,
+    {
+        self.inner.visit_unit()
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<V::Value, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        let deserializer = self.wrap(deserializer);
+        self.inner.visit_some(deserializer)
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<V::Value, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        let deserializer = self.wrap(deserializer);
+        self.inner.visit_newtype_struct(deserializer)
+    }
+
+    fn visit_seq<A>(self, seq: A) -> Result<V::Value, A::Error>
+    where
+        A: de::SeqAccess<'de>,
+    {
+        self.inner.visit_seq(SeqAccess {
+            inner: seq,
+            track: self.track,
+            index: 0,
+        })
+    }
+
+    fn visit_map<A>(self, map: A) -> Result<V::Value, A::Error>
+    where
+        A: de::MapAccess<'de>,
+    {
+        self.inner.visit_map(MapAccess {
+            inner: map,
+            track: self.track,
+        })
+    }
+
+    fn visit_enum<A>(self, data: A) -> Result<V::Value, A::Error>
+    where
+        A: de::EnumAccess<'de>,
+    {
+        // The path does not descend into enum variants.
+        self.inner.visit_enum(data)
+    }
+}
+
+impl<'a, 'de, S> DeserializeSeed<'de> for Wrap<'a, S>
+where
+    S: DeserializeSeed<'de>,
+{
+    type Value = S::Value;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<S::Value, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        let deserializer = self.wrap(deserializer);
+        self.inner.deserialize(deserializer)
+    }
+}
+
+// ===== impl SeqAccess =====
+
+impl<'a, 'de, A> de::SeqAccess<'de> for SeqAccess<'a, A>
+where
+    A: de::SeqAccess<'de>,
+{
+    type Error = A::Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, A::Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        self.track.path.borrow_mut().push(Segment::Index(self.index));
+
+        let value = self.inner.next_element_seed(Wrap::new(seed, self.track, false))?;
+
+        self.track.path.borrow_mut().pop();
+        self.index += 1;
+
+        Ok(value)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.inner.size_hint()
+    }
+}
+
+// ===== impl MapAccess =====
+
+impl<'a, 'de, A> de::MapAccess<'de> for MapAccess<'a, A>
+where
+    A: de::MapAccess<'de>,
+{
+    type Error = A::Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, A::Error>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        self.inner.next_key_seed(Wrap::new(seed, self.track, true))
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, A::Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let segment = match self.track.key.borrow_mut().take() {
+            Some(key) => Segment::Field(key),
+            None => Segment::Unknown,
+        };
+
+        self.track.path.borrow_mut().push(segment);
+
+        let value = self.inner.next_value_seed(Wrap::new(seed, self.track, false))?;
+
+        self.track.path.borrow_mut().pop();
+
+        Ok(value)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.inner.size_hint()
+    }
+}
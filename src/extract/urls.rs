@@ -0,0 +1,19 @@
+use crate::extract::{Context, Error, Extract, Immediate};
+use crate::routing::Urls;
+use crate::util::BufStream;
+
+use http::StatusCode;
+
+impl<B: BufStream> Extract<B> for Urls {
+    type Future = Immediate<Urls>;
+
+    fn extract(ctx: &Context<'_>) -> Self::Future {
+        match ctx.config::<Urls>() {
+            Some(urls) => Immediate::ok(urls.clone()),
+            None => {
+                let err = crate::Error::from(StatusCode::INTERNAL_SERVER_ERROR);
+                Immediate::err(Error::from(err))
+            }
+        }
+    }
+}
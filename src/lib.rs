@@ -59,6 +59,73 @@
 //! * `#[post("/foo")]`
 //! * `#[put("/zomg/hello/world")]`
 //!
+//! The supported verbs are `get`, `post`, `put`, `patch`, `delete`, `head`,
+//! `options` and `trace`. Other methods, such as the WebDAV extension methods,
+//! are routed with the `route` attribute:
+//!
+//! * `#[route(method = "PROPFIND", path = "/dav/*path")]`
+//!
+//! A method may have several routing attributes, for example `#[get("/")]`
+//! along with `#[get("/index.html")]`. All of its paths must declare the same
+//! captures.
+//!
+//! `HEAD` requests that do not match a `head` route are handled by the matching
+//! `GET` route, without reading or sending the response body. The
+//! `Content-Length` header is set when the body knows its exact size.
+//! `OPTIONS` requests that do not match an `options` route are answered with
+//! the list of allowed methods.
+//!
+//! A path prefix shared by every route in an `impl` block may be set on the
+//! block itself:
+//!
+//! ```rust
+//! # #[macro_use] extern crate tower_web;
+//! struct Admin;
+//!
+//! impl_web! {
+//!     #[web(prefix = "/admin")]
+//!     impl Admin {
+//!         // Matches `/admin/users`
+//!         #[get("/users")]
+//!         fn users(&self) -> Result<String, ()> {
+//!             // implementation
+//! #           unimplemented!()
+//!         }
+//!     }
+//! }
+//! # fn main() {}
+//! ```
+//!
+//! Routes are named after their handler method, or with `#[web(name = "...")]`.
+//! [`Urls`] builds the path of a named route from capture values, which avoids
+//! duplicating paths when responding with a `Location` header.
+//!
+//! [`Urls`]: routing/struct.Urls.html
+//!
+//! Request paths are normalized before being matched, so `/a//b` and `/a/%62`
+//! both match `/a/b`. By default, a trailing slash is ignored. This is changed
+//! with [`ServiceBuilder::trailing_slash`].
+//!
+//! [`ServiceBuilder::trailing_slash`]: struct.ServiceBuilder.html#method.trailing_slash
+//!
+//! Routes may also be restricted to requests with a given header, `Accept`
+//! media type, host or query parameter, for example
+//! `#[web(guard(header = "x-api-version", value = "2"))]`. When a guard rejects
+//! a request, the next matching route is tried. See [`Guard`].
+//!
+//! [`Guard`]: routing/struct.Guard.html
+//!
+//! Routes may also be registered at runtime, without the macro, with
+//! [`ServiceBuilder::route`]. The route is handled by a closure taking
+//! `Extract` arguments and returning a `Response`.
+//!
+//! [`ServiceBuilder::route`]: struct.ServiceBuilder.html#method.route
+//!
+//! Existing `tower` HTTP services can be served under a path prefix, next to
+//! the resources, with [`ServiceBuilder::mount_service`].
+//!
+//! [`ServiceBuilder::mount_service`]: struct.ServiceBuilder.html#method.mount_service
+//!
 //! #### Captures
 //!
 //! Path segments that begin with `:` are captures. They match any path segment
@@ -83,6 +150,18 @@
 //! with the capture name and call `index`, passing the value captured from the
 //! path as the first argument.
 //!
+//! A capture may be constrained by following its name with a type or a regular
+//! expression in angle brackets, for example `/users/:id<u32>` or
+//! `/posts/:slug<[a-z-]+>`. A path segment that does not satisfy the constraint
+//! does not match the route, and the request is matched against the remaining
+//! routes instead.
+//!
+//! Captures may also be mixed with literal text within a single path segment,
+//! as in `/files/:name.:ext`, `/v:version/items` or `/@:user`. Captures in the
+//! same segment must be separated by literal text. When a capture could end at
+//! several positions, the longest match is used, so `/files/archive.tar.gz`
+//! captures `archive.tar` and `gz`.
+//!
 //! ### Method Arguments
 //!
 //! `impl_web!` populates resource method arguments using data from the HTTP
@@ -90,6 +169,7 @@
 //! part of the request to use. The rules are as follows:
 //!
 //! * Path captures: when the argument name matches a capture name.
+//! * All path captures: when the argument is named `captures`.
 //! * Query string: when the argument is named `query_string`.
 //! * Request body: when the argument is named `body`.
 //! * All other names are pulled from HTTP headers.
@@ -290,6 +370,104 @@
 //! request body. The request body is deserialized into an instance of `MyData`
 //! and passed to the resource method.
 //!
+//! JSON, URL encoded and `multipart/form-data` request bodies are supported,
+//! based on the `Content-Type` of the request. Media types with the `+json`
+//! suffix, such as `application/merge-patch+json`, are deserialized as JSON,
+//! and any other media type is rejected with `415 Unsupported Media Type`.
+//! A resource method may restrict the media types it accepts with
+//! `#[web(consumes = "application/json")]`. Methods sharing a route may
+//! consume different media types, and the request is rejected with 415 only
+//! when none of them accepts it, listing the accepted media types in the
+//! `Accept-Post` or `Accept-Patch` header of the response. A malformed body
+//! is rejected with `400 Bad Request`, and a body that does not match the
+//! argument type with `422 Unprocessable Entity`. In both cases, the problem
+//! detail names the path to the failing field, e.g.
+//! `emails[1].primary`, along with the position in JSON bodies.
+//! See the [`multipart`] module for handling file uploads. To process the
+//! request body as it arrives, without buffering it in memory, use a
+//! [`BodyStream`] argument.
+//!
+//! The size of request bodies, buffered or streamed, can be limited for the
+//! whole service with [`ServiceBuilder::body_limit`], and for a single resource
+//! method with `#[web(body_limit = "1MB")]`. Requests exceeding the limit are
+//! rejected with `413 Payload Too Large`.
+//!
+//! [`multipart`]: extract/multipart/index.html
+//! [`BodyStream`]: extract/struct.BodyStream.html
+//! [`ServiceBuilder::body_limit`]: struct.ServiceBuilder.html#method.body_limit
+//!
+//! Path captures can be deserialized as well. A single capture binds to any
+//! `derive(Extract)` type that deserializes from a plain string, such as an
+//! enum of unit variants. An argument named `captures` is populated using all
+//! of the route's captures, keyed by name:
+//!
+//! ```rust
+//! # #[macro_use] extern crate tower_web;
+//! struct MyApp;
+//!
+//! #[derive(Extract)]
+//! #[serde(rename_all = "lowercase")]
+//! enum Status {
+//!     Open,
+//!     Closed,
+//! }
+//!
+//! #[derive(Extract)]
+//! struct IssuePath {
+//!     project: String,
+//!     id: u32,
+//! }
+//!
+//! impl_web! {
+//!     impl MyApp {
+//!         #[get("/issues/:status")]
+//!         fn issues(&self, status: Status) -> Result<String, ()> {
+//!             // implementation
+//! #           unimplemented!();
+//!         }
+//!
+//!         #[get("/projects/:project/issues/:id")]
+//!         fn issue(&self, captures: IssuePath) -> Result<String, ()> {
+//!             // implementation
+//! #           unimplemented!();
+//!         }
+//!     }
+//! }
+//! # fn main() {}
+//! ```
+//!
+//! When the argument is pulled from the HTTP headers, each field is populated
+//! using the header of the same name, with underscores replaced by dashes.
+//! The header name can be set with the `#[web(header(name = "..."))]` field
+//! annotation. Missing headers are only accepted for `Option` fields:
+//!
+//! ```rust
+//! # #[macro_use] extern crate tower_web;
+//! struct MyApp;
+//!
+//! #[derive(Extract)]
+//! struct ClientInfo {
+//!     user_agent: String,
+//!     accept_language: Option<String>,
+//!     #[web(header(name = "x-request-id"))]
+//!     request_id: Option<u64>,
+//! }
+//!
+//! impl_web! {
+//!     impl MyApp {
+//!         #[get("/")]
+//!         fn index(&self, client: ClientInfo) -> Result<String, ()> {
+//!             // implementation
+//! #           unimplemented!();
+//!         }
+//!     }
+//! }
+//! # fn main() {}
+//! ```
+//!
+//! Other types, such as enums, are deserialized from the single header named
+//! after the argument, e.g. `x-mode` for an `x_mode: Mode` argument.
+//!
 //! ## `derive(Response)`
 //!
 //! Using `derive(Response)` on a struct generates a `Response` implementation,
@@ -508,6 +686,11 @@ macro_rules! impl_web {
 #[doc(hidden)]
 #[macro_export(local_inner_macros)]
 macro_rules! impl_web_clean_top_level {
+    // Discard tower-web attributes on impl blocks.
+    (($($done:tt)*) #[web $($attr:tt)*] $($rest:tt)*) => {
+        impl_web_clean_top_level!(($($done)*) $($rest)*);
+    };
+
     // Next token is a set of curly braces. Pass to `impl_web_clean_nested!`.
     (($($done:tt)*) { $($nested:tt)* } $($rest:tt)*) => {
         impl_web_clean_nested!(($($done)*) () { $($nested)* } { $($nested)* } $($rest)*);
@@ -544,6 +727,18 @@ macro_rules! impl_web_clean_nested {
     (($($outer:tt)*) ($($done:tt)*) { #[delete $($attr:tt)*] $($nested:tt)* } $dup:tt $($rest:tt)*) => {
         impl_web_clean_nested!(($($outer)*) ($($done)*) { $($nested)* } { $($nested)* } $($rest)*);
     };
+    (($($outer:tt)*) ($($done:tt)*) { #[head $($attr:tt)*] $($nested:tt)* } $dup:tt $($rest:tt)*) => {
+        impl_web_clean_nested!(($($outer)*) ($($done)*) { $($nested)* } { $($nested)* } $($rest)*);
+    };
+    (($($outer:tt)*) ($($done:tt)*) { #[options $($attr:tt)*] $($nested:tt)* } $dup:tt $($rest:tt)*) => {
+        impl_web_clean_nested!(($($outer)*) ($($done)*) { $($nested)* } { $($nested)* } $($rest)*);
+    };
+    (($($outer:tt)*) ($($done:tt)*) { #[trace $($attr:tt)*] $($nested:tt)* } $dup:tt $($rest:tt)*) => {
+        impl_web_clean_nested!(($($outer)*) ($($done)*) { $($nested)* } { $($nested)* } $($rest)*);
+    };
+    (($($outer:tt)*) ($($done:tt)*) { #[route $($attr:tt)*] $($nested:tt)* } $dup:tt $($rest:tt)*) => {
+        impl_web_clean_nested!(($($outer)*) ($($done)*) { $($nested)* } { $($nested)* } $($rest)*);
+    };
     (($($outer:tt)*) ($($done:tt)*) { #[content_type $($attr:tt)*] $($nested:tt)* } $dup:tt $($rest:tt)*) => {
         impl_web_clean_nested!(($($outer)*) ($($done)*) { $($nested)* } { $($nested)* } $($rest)*);
     };
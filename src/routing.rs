@@ -3,21 +3,44 @@
 //! Currently, this module is intended to be used by the `impl_web!` macro and
 //! not the end user.
 
+mod boxed;
 mod builder;
 mod captures;
+mod constraint;
+mod guard;
+mod handler;
+mod host;
+mod mount;
+mod mount_service;
+mod normalize;
 mod path;
 mod resource;
 mod route;
 mod route_match;
 mod service;
 mod set;
+mod trailing_slash;
+mod tree;
+mod urls;
+mod virtual_host;
 
+pub use self::boxed::{BoxBody, BoxBuf, BoxDestination, BoxFuture, BoxResource, BoxedResource};
 pub use self::builder::Builder;
+pub use self::guard::Guard;
+pub use self::handler::{Handler, HandlerArgs, HandlerRoute, HandlerResource};
+pub use self::mount::Mount;
+pub use self::mount_service::{MountService, MountFuture};
 pub use self::resource::{Resource, ResourceFuture, IntoResource, Unit};
-pub use self::route::Route;
+pub use self::route::{Route, RouteInfo};
 pub use self::route_match::RouteMatch;
 pub use self::service::RoutedService;
 pub use self::set::RouteSet;
+pub use self::trailing_slash::TrailingSlash;
+pub use self::urls::{Urls, UrlError};
+pub use self::virtual_host::VirtualHost;
 
 pub(crate) use self::captures::Captures;
+pub(crate) use self::normalize::normalize;
 pub(crate) use self::path::Path;
+pub(crate) use self::set::Lookup;
+pub(crate) use self::service::accept_header;
@@ -0,0 +1,366 @@
+use crate::error::Error;
+use crate::response::Serializer;
+use crate::routing::{IntoResource, Resource, ResourceFuture, RouteMatch, RouteSet};
+use crate::util::{BufStream, Chain};
+use crate::util::buf_stream::SizeHint;
+
+use bytes::Buf;
+use futures::{Async, Poll, try_ready};
+use http;
+
+use std::any::Any;
+use std::fmt;
+use std::sync::Arc;
+
+/// A type-erased resource, not yet bound to a serializer.
+///
+/// `BoxResource` erases the type of any value implementing `IntoResource`.
+/// This allows choosing the resources of a service at runtime, for example
+/// based on configuration, by collecting them in a `Vec`:
+///
+/// ```rust
+/// # #[macro_use] extern crate tower_web;
+/// use tower_web::ServiceBuilder;
+/// use tower_web::routing::BoxResource;
+///
+/// struct Users;
+/// struct Admin;
+///
+/// impl_web! {
+///     impl Users {
+///         // ...
+///     }
+///
+///     impl Admin {
+///         // ...
+///     }
+/// }
+///
+/// # fn main() {
+/// # if false {
+/// # let addr = "127.0.0.1:8080".parse().unwrap();
+/// # let enable_admin = true;
+/// let mut resources = vec![BoxResource::new(Users)];
+///
+/// if enable_admin {
+///     resources.push(BoxResource::new(Admin));
+/// }
+///
+/// ServiceBuilder::new()
+///     .resource(resources)
+///     .run(&addr);
+/// # }
+/// # }
+/// ```
+///
+/// Dispatching a request to a boxed resource costs one dynamic call.
+pub struct BoxResource<S, B> {
+    inner: Box<dyn ErasedIntoResource<S, B> + Send>,
+}
+
+/// The `Resource` a `BoxResource` converts into.
+pub struct BoxedResource<B> {
+    inner: Box<dyn ErasedResource<B> + Send>,
+}
+
+/// Destination of a route handled by a `BoxedResource`.
+#[derive(Clone)]
+pub struct BoxDestination {
+    inner: Arc<dyn Any + Send + Sync>,
+}
+
+/// Buffer yielded by a `BoxBody`.
+pub type BoxBuf = Box<dyn Buf + Send>;
+
+/// A type-erased response body.
+pub struct BoxBody {
+    inner: Box<dyn BufStream<Item = BoxBuf, Error = Error> + Send>,
+}
+
+/// A type-erased response future.
+pub struct BoxFuture {
+    inner: Box<dyn ResourceFuture<Body = BoxBody> + Send>,
+}
+
+/// Boxes every buffer yielded by the inner body.
+struct MapBuf<T> {
+    inner: T,
+}
+
+/// Boxes the body of the response returned by the inner future.
+struct MapBody<T> {
+    inner: T,
+}
+
+/// Object-safe version of `IntoResource`.
+trait ErasedIntoResource<S, B> {
+    fn routes(&self) -> RouteSet<BoxDestination>;
+
+    fn into_resource(self: Box<Self>, serializer: S) -> BoxedResource<B>;
+}
+
+/// Object-safe version of `Resource`.
+trait ErasedResource<B> {
+    fn dispatch(&mut self, destination: BoxDestination, route_match: &RouteMatch<'_>, body: B)
+        -> BoxFuture;
+
+    fn clone_box(&self) -> Box<dyn ErasedResource<B> + Send>;
+}
+
+// ===== impl BoxResource =====
+
+impl<S, B> BoxResource<S, B>
+where
+    S: Serializer,
+    B: BufStream,
+{
+    /// Erase the type of `resource`.
+    pub fn new<T>(resource: T) -> BoxResource<S, B>
+    where
+        T: IntoResource<S, B> + Send + 'static,
+        T::Resource: Send + 'static,
+        <T::Resource as Resource>::Buf: Send + 'static,
+        <T::Resource as Resource>::Body: Send + 'static,
+        <T::Resource as Resource>::Future: Send + 'static,
+    {
+        BoxResource {
+            inner: Box::new(resource),
+        }
+    }
+}
+
+impl<S, B> IntoResource<S, B> for BoxResource<S, B>
+where
+    S: Serializer,
+    B: BufStream,
+{
+    type Destination = BoxDestination;
+    type Resource = BoxedResource<B>;
+
+    fn routes(&self) -> RouteSet<BoxDestination> {
+        self.inner.routes()
+    }
+
+    fn into_resource(self, serializer: S) -> BoxedResource<B> {
+        self.inner.into_resource(serializer)
+    }
+}
+
+impl<S, B, U> Chain<U> for BoxResource<S, B> {
+    type Output = (Self, U);
+
+    fn chain(self, other: U) -> Self::Output {
+        (self, other)
+    }
+}
+
+impl<S, B> fmt::Debug for BoxResource<S, B> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("BoxResource").finish()
+    }
+}
+
+impl<T, S, B> ErasedIntoResource<S, B> for T
+where
+    T: IntoResource<S, B>,
+    T::Resource: Send + 'static,
+    <T::Resource as Resource>::Buf: Send + 'static,
+    <T::Resource as Resource>::Body: Send + 'static,
+    <T::Resource as Resource>::Future: Send + 'static,
+    S: Serializer,
+    B: BufStream,
+{
+    fn routes(&self) -> RouteSet<BoxDestination> {
+        IntoResource::routes(self).map(BoxDestination::new)
+    }
+
+    fn into_resource(self: Box<Self>, serializer: S) -> BoxedResource<B> {
+        BoxedResource {
+            inner: Box::new(IntoResource::into_resource(*self, serializer)),
+        }
+    }
+}
+
+// ===== impl BoxedResource =====
+
+impl<B> Resource for BoxedResource<B>
+where
+    B: BufStream,
+{
+    type Destination = BoxDestination;
+    type RequestBody = B;
+    type Buf = BoxBuf;
+    type Body = BoxBody;
+    type Future = BoxFuture;
+
+    fn dispatch(&mut self, destination: BoxDestination, route_match: &RouteMatch<'_>, body: B)
+        -> BoxFuture
+    {
+        self.inner.dispatch(destination, route_match, body)
+    }
+}
+
+impl<B> Clone for BoxedResource<B> {
+    fn clone(&self) -> Self {
+        BoxedResource {
+            inner: self.inner.clone_box(),
+        }
+    }
+}
+
+impl<B> fmt::Debug for BoxedResource<B> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("BoxedResource").finish()
+    }
+}
+
+impl<T> ErasedResource<T::RequestBody> for T
+where
+    T: Resource + Send + 'static,
+    T::Buf: Send + 'static,
+    T::Body: Send + 'static,
+    T::Future: Send + 'static,
+{
+    fn dispatch(&mut self, destination: BoxDestination, route_match: &RouteMatch<'_>, body: T::RequestBody)
+        -> BoxFuture
+    {
+        let destination = destination.downcast::<T::Destination>();
+        let inner = Resource::dispatch(self, destination, route_match, body);
+
+        BoxFuture::new(MapBody { inner })
+    }
+
+    fn clone_box(&self) -> Box<dyn ErasedResource<T::RequestBody> + Send> {
+        Box::new(self.clone())
+    }
+}
+
+// ===== impl BoxDestination =====
+
+impl BoxDestination {
+    fn new<T>(destination: T) -> BoxDestination
+    where
+        T: Send + Sync + 'static,
+    {
+        BoxDestination {
+            inner: Arc::new(destination),
+        }
+    }
+
+    fn downcast<T>(&self) -> T
+    where
+        T: Clone + 'static,
+    {
+        self.inner.downcast_ref::<T>()
+            .expect("destination does not belong to the resource")
+            .clone()
+    }
+}
+
+impl fmt::Debug for BoxDestination {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("BoxDestination").finish()
+    }
+}
+
+// ===== impl BoxBody =====
+
+impl BoxBody {
+    /// Erase the type of `body`.
+    pub fn new<T>(body: T) -> BoxBody
+    where
+        T: BufStream<Error = Error> + Send + 'static,
+        T::Item: Send + 'static,
+    {
+        BoxBody {
+            inner: Box::new(MapBuf { inner: body }),
+        }
+    }
+}
+
+impl BufStream for BoxBody {
+    type Item = BoxBuf;
+    type Error = Error;
+
+    fn poll(&mut self) -> Poll<Option<Self::Item>, Self::Error> {
+        self.inner.poll()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.inner.size_hint()
+    }
+}
+
+impl fmt::Debug for BoxBody {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("BoxBody").finish()
+    }
+}
+
+// ===== impl BoxFuture =====
+
+impl BoxFuture {
+    /// Erase the type of `future`.
+    pub fn new<T>(future: T) -> BoxFuture
+    where
+        T: ResourceFuture<Body = BoxBody> + Send + 'static,
+    {
+        BoxFuture {
+            inner: Box::new(future),
+        }
+    }
+}
+
+impl ResourceFuture for BoxFuture {
+    type Body = BoxBody;
+
+    fn poll_response(&mut self, request: &http::Request<()>)
+        -> Poll<http::Response<Self::Body>, Error>
+    {
+        self.inner.poll_response(request)
+    }
+}
+
+impl fmt::Debug for BoxFuture {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("BoxFuture").finish()
+    }
+}
+
+// ===== impl MapBuf =====
+
+impl<T> BufStream for MapBuf<T>
+where
+    T: BufStream<Error = Error>,
+    T::Item: Send + 'static,
+{
+    type Item = BoxBuf;
+    type Error = Error;
+
+    fn poll(&mut self) -> Poll<Option<Self::Item>, Self::Error> {
+        let buf = try_ready!(self.inner.poll());
+        Ok(buf.map(|buf| Box::new(buf) as BoxBuf).into())
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.inner.size_hint()
+    }
+}
+
+// ===== impl MapBody =====
+
+impl<T> ResourceFuture for MapBody<T>
+where
+    T: ResourceFuture,
+    T::Body: BufStream<Error = Error> + Send + 'static,
+    <T::Body as BufStream>::Item: Send + 'static,
+{
+    type Body = BoxBody;
+
+    fn poll_response(&mut self, request: &http::Request<()>)
+        -> Poll<http::Response<Self::Body>, Error>
+    {
+        let response = try_ready!(self.inner.poll_response(request));
+        Ok(Async::Ready(response.map(BoxBody::new)))
+    }
+}
@@ -1,22 +1,94 @@
+use super::host::HostCaptures;
+
+use std::sync::Arc;
+
 #[derive(Debug)]
 pub(crate) struct Captures {
     /// Captures extracted from the request
     captures: Vec<(usize, usize)>,
+
+    /// Names of the captures defined by mount prefixes. These are stored after
+    /// the route's own captures.
+    prefix: Option<Arc<Vec<String>>>,
+
+    /// Labels captured from the request host. These are stored after the path
+    /// captures.
+    host: HostCaptures,
 }
 
 impl Captures {
     pub(crate) fn new(captures: Vec<(usize, usize)>) -> Captures {
-        Captures { captures }
+        Captures {
+            captures,
+            prefix: None,
+            host: vec![],
+        }
+    }
+
+    /// Create a `Captures` for a route mounted under a prefix.
+    ///
+    /// `captures` are in path order, so the prefix captures come first. They
+    /// are moved after the route's own captures so that the indices known to
+    /// the route's handler remain valid.
+    pub(crate) fn mounted(mut captures: Vec<(usize, usize)>, prefix: Arc<Vec<String>>) -> Captures {
+        captures.rotate_left(prefix.len());
+
+        Captures {
+            captures,
+            prefix: Some(prefix),
+            host: vec![],
+        }
+    }
+
+    /// Add the labels captured from the request host.
+    pub(crate) fn with_host(mut self, host: HostCaptures) -> Captures {
+        self.host = host;
+        self
     }
 
     #[cfg(test)]
     pub(crate) fn len(&self) -> usize {
-        self.captures.len()
+        self.captures.len() + self.host.len()
     }
 
     /// Get a capture
-    pub fn get<'a>(&self, index: usize, src: &'a str) -> &'a str {
-        let (pos, len) = self.captures[index];
-        &src[pos..(pos + len)]
+    pub fn get<'a>(&'a self, index: usize, src: &'a str) -> &'a str {
+        match self.captures.get(index) {
+            Some(&(pos, len)) => &src[pos..(pos + len)],
+            None => &self.host[index - self.captures.len()].1,
+        }
+    }
+
+    /// Returns the names and values of the captures defined by the mount
+    /// prefix and by the host pattern.
+    pub(crate) fn named<'a>(&'a self, src: &'a str) -> Vec<(&'a str, &'a str)> {
+        let mut named = vec![];
+
+        if let Some(ref prefix) = self.prefix {
+            let offset = self.captures.len() - prefix.len();
+
+            for (i, name) in prefix.iter().enumerate() {
+                named.push((&name[..], self.get(offset + i, src)));
+            }
+        }
+
+        for (name, value) in &self.host {
+            named.push((&name[..], &value[..]));
+        }
+
+        named
+    }
+
+    /// Returns the index of the capture named `name`, defined by a mount
+    /// prefix or by the host pattern.
+    pub(crate) fn named_index(&self, name: &str) -> Option<usize> {
+        if let Some(ref prefix) = self.prefix {
+            if let Some(pos) = prefix.iter().position(|n| n == name) {
+                return Some(self.captures.len() - prefix.len() + pos);
+            }
+        }
+
+        let pos = self.host.iter().position(|(n, _)| n == name)?;
+        Some(self.captures.len() + pos)
     }
 }
@@ -0,0 +1,125 @@
+use regex::Regex;
+
+/// Restricts the values matched by a capture segment.
+///
+/// Constraints are declared by following the capture name with the constraint
+/// in angle brackets, for example `/:id<u32>` or `/:slug<[a-z-]+>`. When the
+/// constraint names a primitive type, the segment must parse as that type.
+/// Otherwise, the constraint is a regular expression that must match the
+/// entire segment.
+#[derive(Debug, Clone)]
+pub(crate) struct Constraint {
+    /// The constraint as written in the route path.
+    source: String,
+
+    kind: Kind,
+}
+
+#[derive(Debug, Clone)]
+enum Kind {
+    U8,
+    U16,
+    U32,
+    U64,
+    Usize,
+    I8,
+    I16,
+    I32,
+    I64,
+    Isize,
+    F32,
+    F64,
+    Bool,
+    Regex(Regex),
+}
+
+impl Constraint {
+    /// Parse a capture constraint.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `source` is not a valid regular expression.
+    pub fn new(source: &str) -> Constraint {
+        use self::Kind::*;
+
+        let kind = match source {
+            "u8" => U8,
+            "u16" => U16,
+            "u32" => U32,
+            "u64" => U64,
+            "usize" => Usize,
+            "i8" => I8,
+            "i16" => I16,
+            "i32" => I32,
+            "i64" => I64,
+            "isize" => Isize,
+            "f32" => F32,
+            "f64" => F64,
+            "bool" => Bool,
+            _ => {
+                // The regular expression must match the entire segment.
+                let anchored = format!("^(?:{})$", source);
+
+                match ::regex::Regex::new(&anchored) {
+                    Ok(regex) => Kind::Regex(regex),
+                    Err(e) => panic!("invalid capture constraint `{}`; {}", source, e),
+                }
+            }
+        };
+
+        Constraint {
+            source: source.to_string(),
+            kind,
+        }
+    }
+
+    /// The constraint as written in the route path.
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Returns `true` if `segment` satisfies the constraint.
+    pub fn test(&self, segment: &str) -> bool {
+        use self::Kind::*;
+
+        match self.kind {
+            U8 => segment.parse::<u8>().is_ok(),
+            U16 => segment.parse::<u16>().is_ok(),
+            U32 => segment.parse::<u32>().is_ok(),
+            U64 => segment.parse::<u64>().is_ok(),
+            Usize => segment.parse::<usize>().is_ok(),
+            I8 => segment.parse::<i8>().is_ok(),
+            I16 => segment.parse::<i16>().is_ok(),
+            I32 => segment.parse::<i32>().is_ok(),
+            I64 => segment.parse::<i64>().is_ok(),
+            Isize => segment.parse::<isize>().is_ok(),
+            F32 => segment.parse::<f32>().is_ok(),
+            F64 => segment.parse::<f64>().is_ok(),
+            Bool => segment.parse::<bool>().is_ok(),
+            Regex(ref regex) => regex.is_match(segment),
+        }
+    }
+}
+
+#[test]
+fn test_constraints() {
+    let id = Constraint::new("u8");
+    assert!(id.test("0"));
+    assert!(id.test("255"));
+    assert!(!id.test("256"));
+    assert!(!id.test("-1"));
+    assert!(!id.test("me"));
+    assert!(!id.test(""));
+
+    let slug = Constraint::new("[a-z-]+");
+    assert!(slug.test("hello-world"));
+    assert!(!slug.test("hello_world"));
+    assert!(!slug.test("Hello"));
+    assert!(!slug.test(""));
+
+    // Alternations are anchored as a whole
+    let either = Constraint::new("foo|bar");
+    assert!(either.test("foo"));
+    assert!(either.test("bar"));
+    assert!(!either.test("foobar"));
+}
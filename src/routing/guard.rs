@@ -0,0 +1,235 @@
+use super::host::{request_host, strip_port};
+
+use http::Request;
+use http::header::{self, HeaderName};
+
+use std::fmt;
+
+/// Additional condition a request must satisfy to match a route.
+///
+/// A route only matches a request when the method, the path and every guard
+/// of the route match. When a guard rejects the request, matching falls
+/// through to the next candidate route.
+///
+/// In `impl_web!`, guards are declared with the `#[web(guard(...))]`
+/// attribute:
+///
+/// * `guard(header = "x-api-version", value = "2")`: the header is present
+///   and set to the value. When `value` is omitted, the header only has to be
+///   present.
+/// * `guard(accept = "application/vnd.acme.v2+json")`: the `Accept` header
+///   lists the media type.
+/// * `guard(host = "api.example.com")`: the request targets the host.
+/// * `guard(query = "debug")`: the query string contains the parameter. A
+///   `value` may be given as well.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Guard {
+    kind: Kind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Kind {
+    Header(HeaderName, Option<String>),
+    Accept(String),
+    Host(String),
+    Query(String, Option<String>),
+}
+
+impl Guard {
+    /// Match requests that include the header `name`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` is not a valid header name.
+    pub fn header(name: &str) -> Guard {
+        Guard { kind: Kind::Header(header_name(name), None) }
+    }
+
+    /// Match requests that include the header `name` set to `value`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` is not a valid header name.
+    pub fn header_value(name: &str, value: &str) -> Guard {
+        Guard { kind: Kind::Header(header_name(name), Some(value.to_string())) }
+    }
+
+    /// Match requests listing the media type `media_type` in the `Accept`
+    /// header.
+    ///
+    /// Parameters and wildcard media ranges are ignored. A request accepting
+    /// `*/*` does not match the guard.
+    pub fn accept(media_type: &str) -> Guard {
+        Guard { kind: Kind::Accept(media_type.to_ascii_lowercase()) }
+    }
+
+    /// Match requests targeting `host`.
+    ///
+    /// The port is only compared when `host` includes one.
+    pub fn host(host: &str) -> Guard {
+        Guard { kind: Kind::Host(host.to_ascii_lowercase()) }
+    }
+
+    /// Match requests with the query parameter `name`.
+    pub fn query(name: &str) -> Guard {
+        Guard { kind: Kind::Query(name.to_string(), None) }
+    }
+
+    /// Match requests with the query parameter `name` set to `value`.
+    pub fn query_value(name: &str, value: &str) -> Guard {
+        Guard { kind: Kind::Query(name.to_string(), Some(value.to_string())) }
+    }
+
+    /// Returns `true` if the request satisfies the guard.
+    pub(crate) fn test(&self, request: &Request<()>) -> bool {
+        match self.kind {
+            Kind::Header(ref name, ref expect) => {
+                request.headers().get_all(name).iter()
+                    .any(|value| {
+                        match *expect {
+                            Some(ref expect) => value.to_str()
+                                .map(|value| value.trim() == expect)
+                                .unwrap_or(false),
+                            None => true,
+                        }
+                    })
+            }
+            Kind::Accept(ref media_type) => {
+                request.headers().get_all(header::ACCEPT).iter()
+                    .filter_map(|value| value.to_str().ok())
+                    .flat_map(|value| value.split(','))
+                    .any(|range| accepts(range, media_type))
+            }
+            Kind::Host(ref expect) => {
+                match request_host(request) {
+                    Some(host) => {
+                        let host = host.to_ascii_lowercase();
+
+                        if strip_port(expect).len() != expect.len() {
+                            host == *expect
+                        } else {
+                            strip_port(&host) == expect
+                        }
+                    }
+                    None => false,
+                }
+            }
+            Kind::Query(ref name, ref expect) => {
+                let query = request.uri().query().unwrap_or("");
+
+                let params: Vec<(String, String)> = match ::serde_urlencoded::from_str(query) {
+                    Ok(params) => params,
+                    Err(_) => return false,
+                };
+
+                params.iter()
+                    .any(|(key, value)| {
+                        key == name && expect.as_ref().map(|e| e == value).unwrap_or(true)
+                    })
+            }
+        }
+    }
+}
+
+impl fmt::Display for Guard {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            Kind::Header(ref name, Some(ref value)) => write!(fmt, "header {}: {}", name, value),
+            Kind::Header(ref name, None) => write!(fmt, "header {}", name),
+            Kind::Accept(ref media_type) => write!(fmt, "accept {}", media_type),
+            Kind::Host(ref host) => write!(fmt, "host {}", host),
+            Kind::Query(ref name, Some(ref value)) => write!(fmt, "query {}={}", name, value),
+            Kind::Query(ref name, None) => write!(fmt, "query {}", name),
+        }
+    }
+}
+
+fn header_name(name: &str) -> HeaderName {
+    match HeaderName::from_bytes(name.as_bytes()) {
+        Ok(name) => name,
+        Err(_) => panic!("invalid guard header name `{}`", name),
+    }
+}
+
+/// Returns `true` if the `Accept` media range lists `media_type`, with a
+/// non-zero quality.
+fn accepts(range: &str, media_type: &str) -> bool {
+    let mut params = range.split(';');
+
+    let range = params.next().unwrap_or("").trim();
+
+    if !range.eq_ignore_ascii_case(media_type) {
+        return false;
+    }
+
+    for param in params {
+        let mut kv = param.splitn(2, '=');
+
+        let key = kv.next().unwrap_or("").trim();
+        let value = kv.next().unwrap_or("").trim();
+
+        if key.eq_ignore_ascii_case("q") {
+            return value.parse::<f32>().map(|q| q > 0.0).unwrap_or(false);
+        }
+    }
+
+    true
+}
+
+#[test]
+fn test_guards() {
+    fn request(uri: &str, headers: &[(&str, &str)]) -> Request<()> {
+        let mut request = Request::builder();
+        request.uri(uri);
+
+        for &(name, value) in headers {
+            request.header(name, value);
+        }
+
+        request.body(()).unwrap()
+    }
+
+    let guard = Guard::header_value("x-api-version", "2");
+    assert!(guard.test(&request("/", &[("x-api-version", "2")])));
+    assert!(!guard.test(&request("/", &[("x-api-version", "1")])));
+    assert!(!guard.test(&request("/", &[])));
+
+    let guard = Guard::header("X-Debug");
+    assert!(guard.test(&request("/", &[("x-debug", "")])));
+    assert!(!guard.test(&request("/", &[])));
+
+    let guard = Guard::accept("application/vnd.acme.v2+json");
+    assert!(guard.test(&request("/", &[("accept", "text/html, application/vnd.acme.v2+json; q=0.9")])));
+    assert!(guard.test(&request("/", &[("accept", "Application/Vnd.Acme.V2+Json")])));
+    assert!(!guard.test(&request("/", &[("accept", "application/vnd.acme.v2+json;q=0")])));
+    assert!(!guard.test(&request("/", &[("accept", "*/*")])));
+    assert!(!guard.test(&request("/", &[])));
+
+    let guard = Guard::host("api.example.com");
+    assert!(guard.test(&request("/", &[("host", "API.example.com:8080")])));
+    assert!(guard.test(&request("http://api.example.com/", &[])));
+    assert!(!guard.test(&request("/", &[("host", "www.example.com")])));
+    assert!(!guard.test(&request("/", &[])));
+
+    let guard = Guard::host("api.example.com:8080");
+    assert!(guard.test(&request("/", &[("host", "api.example.com:8080")])));
+    assert!(!guard.test(&request("/", &[("host", "api.example.com")])));
+
+    let guard = Guard::host("[::1]");
+    assert!(guard.test(&request("/", &[("host", "[::1]:8080")])));
+    assert!(guard.test(&request("/", &[("host", "[::1]")])));
+    assert!(!guard.test(&request("/", &[("host", "[::2]")])));
+
+    let guard = Guard::host("[::1]:8080");
+    assert!(guard.test(&request("/", &[("host", "[::1]:8080")])));
+    assert!(!guard.test(&request("/", &[("host", "[::1]")])));
+
+    let guard = Guard::query("debug");
+    assert!(guard.test(&request("/?a=1&debug", &[])));
+    assert!(!guard.test(&request("/?a=1", &[])));
+    assert!(!guard.test(&request("/", &[])));
+
+    let guard = Guard::query_value("version", "2 beta");
+    assert!(guard.test(&request("/?version=2+beta", &[])));
+    assert!(!guard.test(&request("/?version=1", &[])));
+}
@@ -0,0 +1,347 @@
+use crate::codegen::CallSite;
+use crate::error::Error;
+use crate::extract::{self, Extract, ExtractFuture};
+use crate::response::{Context, Response, Serializer};
+use crate::routing::{self, BoxBody, BoxBuf, BoxFuture};
+use crate::routing::{IntoResource, Path, Resource, ResourceFuture, Route, RouteMatch, RouteSet};
+use crate::util::{BufStream, Chain};
+use crate::util::mime_types::BY_EXTENSION;
+use crate::util::tuple::*;
+
+use futures::{Future, Poll, try_ready};
+use http::{self, Method};
+
+use std::fmt;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+/// A function handling the requests of a route registered at runtime.
+///
+/// `Handler` is implemented for functions and closures taking up to eight
+/// arguments implementing `Extract` and returning a value implementing
+/// `Response`.
+///
+/// The first arguments are extracted from the path captures, in the order
+/// the captures appear in the path. The remaining arguments are extracted
+/// from the request body. At most one argument may read the request body.
+pub trait Handler<Args>: Send + Sync + 'static {
+    /// The response returned by the handler.
+    type Response: Response;
+
+    /// Handle a request with the extracted arguments.
+    fn call(&self, args: Args) -> Self::Response;
+}
+
+/// Arguments of a `Handler`, extracted from the request.
+///
+/// Implemented for tuples of types implementing `Extract`.
+pub trait HandlerArgs<B: BufStream>: Sized + 'static {
+    #[doc(hidden)]
+    type Future: Future<Item = (), Error = extract::Error> + Send;
+
+    #[doc(hidden)]
+    fn callsites(captures: usize) -> Vec<(CallSite, bool)>;
+
+    #[doc(hidden)]
+    fn extract(route_match: &RouteMatch<'_>, callsites: &[(CallSite, bool)], body: B)
+        -> Self::Future;
+
+    #[doc(hidden)]
+    fn from_future(future: Self::Future) -> Self;
+}
+
+/// A route handled by a function.
+///
+/// Created by `ServiceBuilder::route`.
+pub struct HandlerRoute<F, Args> {
+    method: Method,
+    path: String,
+    handler: F,
+    _p: PhantomData<fn(Args)>,
+}
+
+/// Resource dispatching requests to a `Handler`.
+///
+/// The handler type is erased, the resource only depends on the request body
+/// type.
+pub struct HandlerResource<B> {
+    dispatch: Arc<Dispatch<B>>,
+}
+
+type Dispatch<B> = dyn Fn(&RouteMatch<'_>, B) -> BoxFuture + Send + Sync;
+
+/// State shared by the clones of a `HandlerResource`.
+struct Inner<F, S> {
+    handler: F,
+    serializer: S,
+    callsites: Vec<(CallSite, bool)>,
+}
+
+/// Extracts the arguments, then calls the handler.
+struct HandlerFuture<F, Args, S, B>
+where
+    Args: HandlerArgs<B>,
+    B: BufStream,
+{
+    inner: Arc<Inner<F, S>>,
+    extract: Option<Args::Future>,
+}
+
+// ===== impl HandlerRoute =====
+
+impl<F, Args> HandlerRoute<F, Args>
+where
+    F: Handler<Args>,
+{
+    /// Route requests with `method` and matching `path` to `handler`.
+    pub fn new(method: Method, path: &str, handler: F) -> HandlerRoute<F, Args> {
+        HandlerRoute {
+            method,
+            path: path.to_string(),
+            handler,
+            _p: PhantomData,
+        }
+    }
+}
+
+impl<F, Args, S, B> IntoResource<S, B> for HandlerRoute<F, Args>
+where
+    F: Handler<Args>,
+    Args: HandlerArgs<B>,
+    S: Serializer,
+    B: BufStream + 'static,
+    <F::Response as Response>::Buf: Send + 'static,
+    <F::Response as Response>::Body: Send + 'static,
+{
+    type Destination = ();
+    type Resource = HandlerResource<B>;
+
+    fn routes(&self) -> RouteSet<()> {
+        let mut routes = routing::Builder::new();
+
+        routes.insert({
+            Route::new(())
+                .method(self.method.clone())
+                .path(&self.path)
+        });
+
+        routes.build()
+    }
+
+    fn into_resource(self, serializer: S) -> HandlerResource<B> {
+        let captures = Path::new(&self.path).captures().len();
+        let callsites = Args::callsites(captures);
+
+        let body_args = callsites.iter()
+            .filter(|&&(_, requires_body)| requires_body)
+            .count();
+
+        if body_args > 1 {
+            panic!("unimplemented: multi body extract; path={:?}", self.path);
+        }
+
+        let inner = Arc::new(Inner {
+            handler: self.handler,
+            serializer,
+            callsites,
+        });
+
+        HandlerResource {
+            dispatch: Arc::new(move |route_match, body| {
+                let extract = Args::extract(route_match, &inner.callsites, body);
+
+                BoxFuture::new(HandlerFuture::<F, Args, S, B> {
+                    inner: inner.clone(),
+                    extract: Some(extract),
+                })
+            }),
+        }
+    }
+}
+
+impl<F, Args, U> Chain<U> for HandlerRoute<F, Args> {
+    type Output = (Self, U);
+
+    fn chain(self, other: U) -> Self::Output {
+        (self, other)
+    }
+}
+
+impl<F, Args> fmt::Debug for HandlerRoute<F, Args> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("HandlerRoute")
+            .field("method", &self.method)
+            .field("path", &self.path)
+            .finish()
+    }
+}
+
+// ===== impl HandlerResource =====
+
+impl<B> Resource for HandlerResource<B>
+where
+    B: BufStream,
+{
+    type Destination = ();
+    type RequestBody = B;
+    type Buf = BoxBuf;
+    type Body = BoxBody;
+    type Future = BoxFuture;
+
+    fn dispatch(&mut self, _: (), route_match: &RouteMatch<'_>, body: B) -> BoxFuture {
+        (self.dispatch)(route_match, body)
+    }
+}
+
+impl<B> Clone for HandlerResource<B> {
+    fn clone(&self) -> Self {
+        HandlerResource {
+            dispatch: self.dispatch.clone(),
+        }
+    }
+}
+
+impl<B> fmt::Debug for HandlerResource<B> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("HandlerResource").finish()
+    }
+}
+
+// ===== impl HandlerFuture =====
+
+impl<F, Args, S, B> ResourceFuture for HandlerFuture<F, Args, S, B>
+where
+    F: Handler<Args>,
+    Args: HandlerArgs<B>,
+    S: Serializer,
+    B: BufStream,
+    <F::Response as Response>::Buf: Send + 'static,
+    <F::Response as Response>::Body: Send + 'static,
+{
+    type Body = BoxBody;
+
+    fn poll_response(&mut self, request: &http::Request<()>)
+        -> Poll<http::Response<Self::Body>, Error>
+    {
+        try_ready!(self.extract.as_mut().expect("polled after ready").poll());
+
+        let args = Args::from_future(self.extract.take().unwrap());
+        let response = self.inner.handler.call(args);
+
+        let mut context = Context::new(request, &self.inner.serializer);
+
+        if let Some(extension) = request.uri().path().rsplit('.').next() {
+            if let Some(content_type) = BY_EXTENSION.get(extension) {
+                context.set_content_type(content_type);
+            }
+        }
+
+        let response = response.into_http(&context)?;
+        Ok(response.map(BoxBody::new).into())
+    }
+}
+
+// ===== impl Handler =====
+
+/// Captures are bound to the first arguments, the body to the others.
+fn callsite(index: usize, captures: usize) -> CallSite {
+    if index < captures {
+        CallSite::new_capture(index)
+    } else {
+        CallSite::new_body()
+    }
+}
+
+impl<F, R> Handler<()> for F
+where
+    F: Fn() -> R + Send + Sync + 'static,
+    R: Response,
+{
+    type Response = R;
+
+    fn call(&self, _: ()) -> R {
+        (self)()
+    }
+}
+
+impl<B> HandlerArgs<B> for ()
+where
+    B: BufStream,
+{
+    type Future = Join0;
+
+    fn callsites(_: usize) -> Vec<(CallSite, bool)> {
+        vec![]
+    }
+
+    fn extract(_: &RouteMatch<'_>, _: &[(CallSite, bool)], _: B) -> Join0 {
+        Join0::new()
+    }
+
+    fn from_future(_: Join0) {
+    }
+}
+
+macro_rules! handler {
+    ($join:ident; $($arg:ident $index:tt),+) => {
+        impl<F, R, $($arg),+> Handler<($($arg,)+)> for F
+        where
+            F: Fn($($arg),+) -> R + Send + Sync + 'static,
+            R: Response,
+        {
+            type Response = R;
+
+            fn call(&self, args: ($($arg,)+)) -> R {
+                (self)($(args.$index),+)
+            }
+        }
+
+        impl<B, $($arg),+> HandlerArgs<B> for ($($arg,)+)
+        where
+            B: BufStream,
+            $($arg: Extract<B>,
+              <$arg as Extract<B>>::Future: Send,)+
+        {
+            type Future = $join<$(<$arg as Extract<B>>::Future),+>;
+
+            fn callsites(captures: usize) -> Vec<(CallSite, bool)> {
+                vec![$({
+                    let callsite = callsite($index, captures);
+                    let requires_body = <$arg as Extract<B>>::requires_body(&callsite);
+
+                    (callsite, requires_body)
+                }),+]
+            }
+
+            fn extract(route_match: &RouteMatch<'_>, callsites: &[(CallSite, bool)], body: B)
+                -> Self::Future
+            {
+                let mut body = Some(body);
+
+                $join::new($({
+                    let context = extract::Context::new(route_match, &callsites[$index].0);
+
+                    if callsites[$index].1 {
+                        <$arg as Extract<B>>::extract_body(&context, body.take().unwrap())
+                    } else {
+                        <$arg as Extract<B>>::extract(&context)
+                    }
+                }),+)
+            }
+
+            fn from_future(future: Self::Future) -> Self {
+                let futures = future.into_inner();
+                ($(futures.$index.extract(),)+)
+            }
+        }
+    }
+}
+
+handler!(Join1; A0 0);
+handler!(Join2; A0 0, A1 1);
+handler!(Join3; A0 0, A1 1, A2 2);
+handler!(Join4; A0 0, A1 1, A2 2, A3 3);
+handler!(Join5; A0 0, A1 1, A2 2, A3 3, A4 4);
+handler!(Join6; A0 0, A1 1, A2 2, A3 3, A4 4, A5 5);
+handler!(Join7; A0 0, A1 1, A2 2, A3 3, A4 4, A5 5, A6 6);
+handler!(Join8; A0 0, A1 1, A2 2, A3 3, A4 4, A5 5, A6 6, A7 7);
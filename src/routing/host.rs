@@ -0,0 +1,229 @@
+use http::Request;
+use http::header;
+
+use std::cmp::Reverse;
+
+/// Matches the host targeted by a request.
+///
+/// A pattern is a list of labels separated by `.`. Labels starting with `:`
+/// capture a single label of the host, as in `:tenant.example.com`. A leading
+/// `*` label matches one or more labels, as in `*.example.com`.
+///
+/// Hosts are compared without their port and case-insensitively.
+#[derive(Debug, Clone)]
+pub(crate) struct HostPattern {
+    /// The pattern as written.
+    source: String,
+
+    labels: Vec<Label>,
+
+    /// When `true`, the pattern starts with a `*` label.
+    wildcard: bool,
+}
+
+#[derive(Debug, Clone)]
+enum Label {
+    Literal(String),
+    Capture(String),
+}
+
+/// Names and values of the labels captured from the host.
+pub(crate) type HostCaptures = Vec<(String, String)>;
+
+impl HostPattern {
+    /// Parse a host pattern.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the pattern is empty, contains an empty label, or uses `*`
+    /// other than as the first label.
+    pub fn new(source: &str) -> HostPattern {
+        let mut labels = vec![];
+        let mut wildcard = false;
+
+        for (i, label) in source.split('.').enumerate() {
+            if label.is_empty() {
+                panic!("host pattern labels must not be empty; host={:?}", source);
+            }
+
+            if label == "*" {
+                if i != 0 {
+                    panic!("`*` must be the first label of a host pattern; host={:?}", source);
+                }
+
+                wildcard = true;
+            } else if label.starts_with(':') {
+                if label.len() == 1 {
+                    panic!("expected capture name; host={:?}", source);
+                }
+
+                labels.push(Label::Capture(label[1..].to_string()));
+            } else {
+                labels.push(Label::Literal(label.to_ascii_lowercase()));
+            }
+        }
+
+        HostPattern {
+            source: source.to_string(),
+            labels,
+            wildcard,
+        }
+    }
+
+    /// The pattern as written.
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Ranks the pattern by specificity. Patterns that sort first are more
+    /// specific.
+    ///
+    /// Patterns without a leading `*` rank above patterns with one. Then,
+    /// patterns with more labels rank first and, label by label, literals rank
+    /// above captures.
+    pub fn rank(&self) -> (bool, Reverse<usize>, Vec<bool>) {
+        let captures = self.labels.iter()
+            .map(|label| match *label {
+                Label::Literal(..) => false,
+                Label::Capture(..) => true,
+            })
+            .collect();
+
+        (self.wildcard, Reverse(self.labels.len()), captures)
+    }
+
+    /// Match the host targeted by `request`, returning the captured labels.
+    pub fn test_request(&self, request: &Request<()>) -> Option<HostCaptures> {
+        self.test(request_host(request)?)
+    }
+
+    /// Match `host`, returning the captured labels.
+    pub fn test(&self, host: &str) -> Option<HostCaptures> {
+        let host = strip_port(host).trim_end_matches('.');
+
+        let labels: Vec<_> = host.split('.').collect();
+
+        let skip = if self.wildcard {
+            // The wildcard matches at least one label
+            if labels.len() <= self.labels.len() {
+                return None;
+            }
+
+            labels.len() - self.labels.len()
+        } else {
+            if labels.len() != self.labels.len() {
+                return None;
+            }
+
+            0
+        };
+
+        let mut captures = vec![];
+
+        for (pattern, label) in self.labels.iter().zip(&labels[skip..]) {
+            if label.is_empty() {
+                return None;
+            }
+
+            match *pattern {
+                Label::Literal(ref value) => {
+                    if !value.eq_ignore_ascii_case(label) {
+                        return None;
+                    }
+                }
+                Label::Capture(ref name) => {
+                    captures.push((name.clone(), label.to_ascii_lowercase()));
+                }
+            }
+        }
+
+        Some(captures)
+    }
+}
+
+/// Returns the host targeted by the request, including the port if any.
+pub(crate) fn request_host(request: &Request<()>) -> Option<&str> {
+    if let Some(host) = request.headers().get(header::HOST) {
+        return host.to_str().ok();
+    }
+
+    request.uri().authority_part().map(|authority| authority.as_str())
+}
+
+/// Returns `host` without its port, if any.
+///
+/// The port of an IPv6 literal, such as `[::1]:8080`, follows the closing
+/// bracket.
+pub(crate) fn strip_port(host: &str) -> &str {
+    let start = if host.starts_with('[') {
+        host.find(']').map(|pos| pos + 1).unwrap_or(host.len())
+    } else {
+        0
+    };
+
+    match host[start..].find(':') {
+        Some(pos) => &host[..start + pos],
+        None => host,
+    }
+}
+
+#[test]
+fn test_strip_port() {
+    assert_eq!(strip_port("example.com"), "example.com");
+    assert_eq!(strip_port("example.com:8080"), "example.com");
+    assert_eq!(strip_port("127.0.0.1:8080"), "127.0.0.1");
+    assert_eq!(strip_port("[::1]"), "[::1]");
+    assert_eq!(strip_port("[::1]:8080"), "[::1]");
+}
+
+#[test]
+fn test_host_pattern() {
+    fn names(captures: HostCaptures) -> Vec<String> {
+        captures.into_iter().map(|(name, value)| format!("{}={}", name, value)).collect()
+    }
+
+    let host = HostPattern::new("api.example.com");
+    assert!(host.test("api.example.com").is_some());
+    assert!(host.test("API.Example.com:8080").is_some());
+    assert!(host.test("api.example.com.").is_some());
+    assert!(host.test("www.example.com").is_none());
+    assert!(host.test("v1.api.example.com").is_none());
+
+    let host = HostPattern::new("*.example.com");
+    assert!(host.test("api.example.com").is_some());
+    assert!(host.test("v1.api.example.com").is_some());
+    assert!(host.test("example.com").is_none());
+    assert!(host.test("example.org").is_none());
+
+    let host = HostPattern::new(":tenant.example.com");
+    assert_eq!(names(host.test("Acme.example.com").unwrap()), ["tenant=acme"]);
+    assert!(host.test("a.b.example.com").is_none());
+    assert!(host.test(".example.com").is_none());
+
+    let host = HostPattern::new("*.:region.example.com");
+    assert_eq!(names(host.test("a.b.eu.example.com").unwrap()), ["region=eu"]);
+
+    let mut hosts: Vec<_> = [
+        "*.example.com",
+        ":tenant.example.com",
+        "*.api.example.com",
+        "api.example.com",
+        "example.com",
+    ].iter().map(|host| HostPattern::new(host)).collect();
+
+    hosts.sort_by_key(HostPattern::rank);
+
+    let sources: Vec<_> = hosts.iter().map(HostPattern::source).collect();
+    assert_eq!(sources, [
+        "api.example.com",
+        ":tenant.example.com",
+        "example.com",
+        "*.api.example.com",
+        "*.example.com",
+    ]);
+
+    let host = HostPattern::new("[::1]");
+    assert!(host.test("[::1]").is_some());
+    assert!(host.test("[::1]:8080").is_some());
+    assert!(host.test("[::2]:8080").is_none());
+}
@@ -0,0 +1,54 @@
+use crate::response::Serializer;
+use crate::routing::{IntoResource, RouteSet};
+use crate::util::{BufStream, Chain};
+
+/// A resource mounted under a path prefix.
+///
+/// Every route reported by the inner resource is prefixed with the mount
+/// path. Captures in the prefix, such as `/tenants/:tenant`, are available to
+/// the inner resource's handlers by argument name.
+///
+/// Created by `ServiceBuilder::mount`.
+#[derive(Debug)]
+pub struct Mount<T> {
+    prefix: String,
+    inner: T,
+}
+
+impl<T> Mount<T> {
+    /// Mount `inner` under `prefix`.
+    pub fn new(prefix: &str, inner: T) -> Mount<T> {
+        assert!(prefix.starts_with("/"), "mount prefix must start with `/`; prefix={:?}", prefix);
+
+        Mount {
+            prefix: prefix.to_string(),
+            inner,
+        }
+    }
+}
+
+impl<T, S, B> IntoResource<S, B> for Mount<T>
+where
+    T: IntoResource<S, B>,
+    S: Serializer,
+    B: BufStream,
+{
+    type Destination = T::Destination;
+    type Resource = T::Resource;
+
+    fn routes(&self) -> RouteSet<Self::Destination> {
+        self.inner.routes().mount(&self.prefix)
+    }
+
+    fn into_resource(self, serializer: S) -> Self::Resource {
+        self.inner.into_resource(serializer)
+    }
+}
+
+impl<T, U> Chain<U> for Mount<T> {
+    type Output = (Self, U);
+
+    fn chain(self, other: U) -> Self::Output {
+        (self, other)
+    }
+}
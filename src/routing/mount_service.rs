@@ -0,0 +1,209 @@
+use crate::error::{self, Error};
+use crate::response::Serializer;
+use crate::routing::{self, IntoResource, Path, Resource, Route, RouteMatch, RouteSet};
+use crate::routing::service::with_path;
+use crate::util::{BufStream, Chain};
+use crate::util::http::HttpService;
+
+use futures::{Async, Future, Poll, try_ready};
+use http::{self, Method, StatusCode};
+
+use std::fmt;
+
+/// An `HttpService` mounted under a path prefix.
+///
+/// Requests with any standard method whose path starts with the prefix are
+/// forwarded to the service. The prefix is removed from the request URI and
+/// the request body is forwarded unchanged. Extension methods, such as the
+/// WebDAV methods, are not forwarded.
+///
+/// Routes of other resources that are more specific than the prefix take
+/// precedence over the mounted service.
+///
+/// Created by `ServiceBuilder::mount_service`.
+pub struct MountService<T> {
+    prefix: String,
+
+    /// Index of the capture holding the remaining path, following the
+    /// captures defined by the prefix
+    glob: usize,
+
+    service: T,
+}
+
+/// Response future returned by `MountService`.
+pub struct MountFuture<T>
+where
+    T: HttpService,
+{
+    state: State<T>,
+}
+
+enum State<T>
+where
+    T: HttpService,
+{
+    /// Waiting for the service to be ready.
+    Ready(T, Option<http::Request<T::RequestBody>>),
+    Calling(error::Map<T::Future>),
+}
+
+/// Methods forwarded to a mounted service.
+const METHODS: &[Method] = &[
+    Method::GET,
+    Method::HEAD,
+    Method::POST,
+    Method::PUT,
+    Method::PATCH,
+    Method::DELETE,
+    Method::OPTIONS,
+    Method::TRACE,
+    Method::CONNECT,
+];
+
+// ===== impl MountService =====
+
+impl<T> MountService<T> {
+    /// Mount `service` under `prefix`.
+    pub fn new(prefix: &str, service: T) -> MountService<T> {
+        assert!(prefix.starts_with("/"), "mount prefix must start with `/`; prefix={:?}", prefix);
+
+        MountService {
+            prefix: prefix.to_string(),
+            glob: Path::new(prefix).captures().len(),
+            service,
+        }
+    }
+}
+
+impl<T, S, B> IntoResource<S, B> for MountService<T>
+where
+    T: HttpService<RequestBody = B> + Clone,
+    S: Serializer,
+    B: BufStream,
+{
+    // `true` when the request path extends past the prefix
+    type Destination = bool;
+    type Resource = Self;
+
+    fn routes(&self) -> RouteSet<bool> {
+        let prefix = self.prefix.trim_end_matches('/');
+        let root = if prefix.is_empty() { "/" } else { prefix };
+        let rest = format!("{}/*rest", prefix);
+
+        let mut routes = routing::Builder::new();
+
+        for method in METHODS {
+            routes.insert(Route::new(false).method(method.clone()).path(root));
+            routes.insert(Route::new(true).method(method.clone()).path(&rest));
+        }
+
+        routes.build()
+    }
+
+    fn into_resource(self, _: S) -> Self {
+        self
+    }
+}
+
+impl<T> Resource for MountService<T>
+where
+    T: HttpService + Clone,
+{
+    type Destination = bool;
+    type RequestBody = T::RequestBody;
+    type Buf = <T::ResponseBody as BufStream>::Item;
+    type Body = error::Map<T::ResponseBody>;
+    type Future = MountFuture<T>;
+
+    fn dispatch(
+        &mut self,
+        rest: bool,
+        route_match: &RouteMatch<'_>,
+        body: Self::RequestBody,
+    ) -> Self::Future {
+        let request = route_match.request();
+
+        // The remaining path is captured by the route's glob
+        let path = if rest {
+            format!("/{}", route_match.captures().get(self.glob, request.uri().path()))
+        } else {
+            "/".to_string()
+        };
+
+        let mut forward = http::Request::new(body);
+        *forward.method_mut() = request.method().clone();
+        *forward.uri_mut() = with_path(request.uri(), &path);
+        *forward.version_mut() = request.version();
+        *forward.headers_mut() = request.headers().clone();
+
+        MountFuture {
+            state: State::Ready(self.service.clone(), Some(forward)),
+        }
+    }
+}
+
+impl<T: Clone> Clone for MountService<T> {
+    fn clone(&self) -> Self {
+        MountService {
+            prefix: self.prefix.clone(),
+            glob: self.glob,
+            service: self.service.clone(),
+        }
+    }
+}
+
+impl<T, U> Chain<U> for MountService<T> {
+    type Output = (Self, U);
+
+    fn chain(self, other: U) -> Self::Output {
+        (self, other)
+    }
+}
+
+impl<T> fmt::Debug for MountService<T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("MountService")
+            .field("prefix", &self.prefix)
+            .finish()
+    }
+}
+
+// ===== impl MountFuture =====
+
+impl<T> Future for MountFuture<T>
+where
+    T: HttpService,
+{
+    type Item = http::Response<error::Map<T::ResponseBody>>;
+    type Error = Error;
+
+    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
+        loop {
+            let future = match self.state {
+                State::Ready(ref mut service, ref mut request) => {
+                    try_ready!(service.poll_http_ready()
+                        .map_err(|_| Error::from(StatusCode::INTERNAL_SERVER_ERROR)));
+
+                    let request = request.take().expect("polled after ready");
+                    error::Map::new(service.call_http(request))
+                }
+                State::Calling(ref mut future) => {
+                    let response = try_ready!(future.poll());
+                    return Ok(Async::Ready(response.map(error::Map::new)));
+                }
+            };
+
+            self.state = State::Calling(future);
+        }
+    }
+}
+
+impl<T> fmt::Debug for MountFuture<T>
+where
+    T: HttpService,
+{
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("MountFuture").finish()
+    }
+}
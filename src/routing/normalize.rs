@@ -0,0 +1,124 @@
+/// Normalize a request path before it is matched against the routes.
+///
+/// * Percent-encoded unreserved characters are decoded and the hexadecimal
+///   digits of the remaining percent-encoded octets are upper cased. Reserved
+///   characters, such as `/`, stay encoded, so decoding twice is harmless.
+/// * Empty segments are removed, so `/a//b` becomes `/a/b`.
+/// * `.` and `..` segments are resolved. `..` never goes above the root.
+///
+/// A trailing slash is kept. Returns `None` if the path is already normal.
+pub(crate) fn normalize(path: &str) -> Option<String> {
+    // Paths such as `*` in `OPTIONS *` are left alone.
+    if !path.starts_with('/') {
+        return None;
+    }
+
+    let decoded = decode_unreserved(path);
+
+    let mut segments: Vec<&str> = vec![];
+    let mut trailing_slash = false;
+
+    for segment in decoded[1..].split('/') {
+        trailing_slash = true;
+
+        match segment {
+            "" | "." => {}
+            ".." => {
+                segments.pop();
+            }
+            _ => {
+                segments.push(segment);
+                trailing_slash = false;
+            }
+        }
+    }
+
+    let mut normalized = String::with_capacity(path.len());
+
+    for segment in &segments {
+        normalized.push('/');
+        normalized.push_str(segment);
+    }
+
+    if trailing_slash || segments.is_empty() {
+        normalized.push('/');
+    }
+
+    if normalized == path {
+        None
+    } else {
+        Some(normalized)
+    }
+}
+
+fn decode_unreserved(path: &str) -> String {
+    let bytes = path.as_bytes();
+    let mut decoded = String::with_capacity(path.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            if let (Some(hi), Some(lo)) = (hex(bytes[i + 1]), hex(bytes[i + 2])) {
+                let byte = hi << 4 | lo;
+
+                if is_unreserved(byte) {
+                    decoded.push(byte as char);
+                } else {
+                    decoded.push('%');
+                    decoded.push(HEX[hi as usize] as char);
+                    decoded.push(HEX[lo as usize] as char);
+                }
+
+                i += 3;
+                continue;
+            }
+        }
+
+        decoded.push(bytes[i] as char);
+        i += 1;
+    }
+
+    decoded
+}
+
+const HEX: &[u8; 16] = b"0123456789ABCDEF";
+
+fn hex(byte: u8) -> Option<u8> {
+    match byte {
+        b'0'..=b'9' => Some(byte - b'0'),
+        b'a'..=b'f' => Some(byte - b'a' + 10),
+        b'A'..=b'F' => Some(byte - b'A' + 10),
+        _ => None,
+    }
+}
+
+/// Unreserved characters, as defined by RFC 3986.
+fn is_unreserved(byte: u8) -> bool {
+    match byte {
+        b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => true,
+        _ => false,
+    }
+}
+
+#[test]
+fn test_normalize() {
+    assert_eq!(normalize("/"), None);
+    assert_eq!(normalize("/a/b"), None);
+    assert_eq!(normalize("/a/b/"), None);
+    assert_eq!(normalize("*"), None);
+
+    assert_eq!(normalize("//").unwrap(), "/");
+    assert_eq!(normalize("/a//b//").unwrap(), "/a/b/");
+    assert_eq!(normalize("/a/./b/.").unwrap(), "/a/b/");
+    assert_eq!(normalize("/a/../b").unwrap(), "/b");
+    assert_eq!(normalize("/a/b/..").unwrap(), "/a/");
+    assert_eq!(normalize("/../../a").unwrap(), "/a");
+
+    assert_eq!(normalize("/a/%62").unwrap(), "/a/b");
+    assert_eq!(normalize("/a/%7e%2D").unwrap(), "/a/~-");
+    assert_eq!(normalize("/a%2fb").unwrap(), "/a%2Fb");
+    assert_eq!(normalize("/a%2Fb"), None);
+    assert_eq!(normalize("/%2E%2E/a").unwrap(), "/a");
+    assert_eq!(normalize("/100%"), None);
+    assert_eq!(normalize("/%zz"), None);
+}
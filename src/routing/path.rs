@@ -1,66 +1,289 @@
+#[cfg(test)]
 use super::Captures;
+use super::constraint::Constraint;
+use super::urls::UrlError;
 
-#[derive(Debug)]
+use percent_encoding::{utf8_percent_encode, PATH_SEGMENT_ENCODE_SET};
+use tower_web_path::parse_segment;
+
+use std::sync::Arc;
+
+#[derive(Debug, Clone)]
 pub(crate) struct Path {
+    /// The path as written in the route definition, including mount prefixes.
+    source: String,
+
     segments: Vec<Segment>,
+
+    /// Capture names, in the order they appear in the path.
+    captures: Vec<String>,
+
+    /// Names of the captures defined by mount prefixes.
+    prefix: Option<Arc<Vec<String>>>,
 }
 
-#[derive(Debug)]
-enum Segment {
+#[derive(Debug, Clone)]
+pub(crate) enum Segment {
     Literal(String),
-    Capture,
+    Capture(Option<Constraint>),
+    Pattern(Pattern),
     Glob,
 }
 
+/// A path segment mixing literal text and captures, such as `:name.:ext`.
+#[derive(Debug, Clone)]
+pub(crate) struct Pattern {
+    /// The segment as written in the route path.
+    source: String,
+
+    parts: Vec<Part>,
+}
+
+#[derive(Debug, Clone)]
+enum Part {
+    Literal(String),
+    Capture(Option<Constraint>),
+}
+
 impl Path {
     /// Create a new path condition
-    pub fn new(mut path: &str) -> Path {
-        if path.ends_with("/") {
-            path = &path[0..path.len() - 1];
-        }
+    pub fn new(path: &str) -> Path {
+        let mut captures = vec![];
 
         let segments = path.split("/")
+            .map(|segment| Segment::new(segment, &mut captures))
+            .collect();
+
+        Path {
+            source: path.to_string(),
+            segments,
+            captures,
+            prefix: None,
+        }
+    }
+
+    /// Returns a new path matching `self` under `prefix`.
+    ///
+    /// The captures defined by `prefix` are tracked separately, as the route's
+    /// handler only knows about the captures in its own path.
+    pub fn mount(&self, prefix: &str) -> Path {
+        let mount = Path::new(prefix);
+
+        for segment in &mount.segments {
+            if let Segment::Glob = *segment {
+                panic!("mount prefix cannot contain a glob; prefix={:?}", prefix);
+            }
+        }
+
+        let mut names = mount.captures;
+
+        if let Some(ref prefix) = self.prefix {
+            names.extend(prefix.iter().cloned());
+        }
+
+        let prefix = prefix.trim_end_matches('/');
+
+        // Mounting the root path matches the prefix itself.
+        let mut path = if self.source == "/" && !prefix.is_empty() {
+            Path::new(prefix)
+        } else {
+            Path::new(&format!("{}{}", prefix, self.source))
+        };
+
+        if !names.is_empty() {
+            path.prefix = Some(Arc::new(names));
+        }
+
+        path
+    }
+
+    /// The path as written in the route definition.
+    pub(crate) fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Capture names, in the order they appear in the path.
+    pub(crate) fn captures(&self) -> &[String] {
+        &self.captures[..]
+    }
+
+    /// Names of the captures defined by mount prefixes, if any.
+    pub(crate) fn prefix(&self) -> Option<&Arc<Vec<String>>> {
+        self.prefix.as_ref()
+    }
+
+    /// Returns the path segments. A trailing slash results in a trailing
+    /// empty segment.
+    pub(crate) fn segments(&self) -> &[Segment] {
+        &self.segments[..]
+    }
+
+    /// Returns the path segments, ignoring a trailing slash.
+    pub(crate) fn lenient_segments(&self) -> &[Segment] {
+        match self.segments.split_last() {
+            Some((Segment::Literal(value), rest)) if value.is_empty() && !rest.is_empty() => rest,
+            _ => &self.segments[..],
+        }
+    }
+
+    /// Returns a key identifying the request paths matched by this path.
+    ///
+    /// Two paths with the same shape match exactly the same request paths,
+    /// for example `/users/:id<u32>` and `/users/:user_id<u32>`. When
+    /// `lenient` is set, trailing slashes are ignored.
+    pub(crate) fn shape(&self, lenient: bool) -> String {
+        let segments = if lenient {
+            self.lenient_segments()
+        } else {
+            self.segments()
+        };
+
+        let segments: Vec<_> = segments.iter()
             .map(|segment| {
-                let c = segment.chars().next();
-
-                if c == Some(':') {
-                    Segment::Capture
-                } else if c == Some('*') {
-                    Segment::Glob
-                } else {
-                    Segment::Literal(segment.to_string())
+                match *segment {
+                    Segment::Literal(ref value) => value.clone(),
+                    Segment::Capture(ref constraint) => capture_shape(constraint),
+                    Segment::Pattern(ref pattern) => {
+                        pattern.parts.iter()
+                            .map(|part| {
+                                match *part {
+                                    Part::Literal(ref value) => value.clone(),
+                                    Part::Capture(ref constraint) => capture_shape(constraint),
+                                }
+                            })
+                            .collect()
+                    }
+                    Segment::Glob => "*".to_string(),
                 }
             })
             .collect();
 
-        Path { segments }
+        segments.join("/")
+    }
+
+    /// Build a path matching `self` by filling in the captures.
+    ///
+    /// Capture values are percent-encoded. Glob values may contain `/`, which
+    /// is kept as a path separator.
+    pub fn url(&self, captures: &[(&str, &str)]) -> Result<String, UrlError> {
+        let mut names = self.captures.iter();
+        let mut url = String::new();
+
+        // Returns the encoded value of the next capture.
+        let mut next = |constraint: Option<&Constraint>| {
+            let name = names.next().unwrap();
+
+            let value = captures.iter()
+                .find(|&&(n, _)| n == name)
+                .map(|&(_, value)| value)
+                .ok_or_else(|| UrlError::missing_capture(name))?;
+
+            if let Some(constraint) = constraint {
+                if !constraint.test(value) {
+                    return Err(UrlError::invalid_capture(name, value));
+                }
+            }
+
+            Ok(value)
+        };
+
+        for (i, segment) in self.segments.iter().enumerate() {
+            if i > 0 {
+                url.push('/');
+            }
+
+            match *segment {
+                Segment::Literal(ref value) => url.push_str(value),
+                Segment::Capture(ref constraint) => {
+                    let value = next(constraint.as_ref())?;
+                    url.extend(utf8_percent_encode(value, PATH_SEGMENT_ENCODE_SET));
+                }
+                Segment::Pattern(ref pattern) => {
+                    for part in &pattern.parts {
+                        match *part {
+                            Part::Literal(ref value) => url.push_str(value),
+                            Part::Capture(ref constraint) => {
+                                let value = next(constraint.as_ref())?;
+                                url.extend(utf8_percent_encode(value, PATH_SEGMENT_ENCODE_SET));
+                            }
+                        }
+                    }
+                }
+                Segment::Glob => {
+                    let value = next(None)?;
+
+                    for (i, segment) in value.split("/").enumerate() {
+                        if i > 0 {
+                            url.push('/');
+                        }
+
+                        url.extend(utf8_percent_encode(segment, PATH_SEGMENT_ENCODE_SET));
+                    }
+                }
+            }
+        }
+
+        if url.is_empty() {
+            url.push('/');
+        }
+
+        Ok(url)
     }
 
     /// Test the path component of a request
+    #[cfg(test)]
     pub fn test(&self, mut path: &str) -> Option<Captures> {
         if path.ends_with("/") {
             path = &path[0..path.len() - 1];
         }
 
+        let segments = self.lenient_segments();
         let mut i = 0;
         let mut captures = vec![];
         let base = path.as_ptr() as usize;
 
         for segment in path.split("/") {
-            if i == self.segments.len() {
+            if i == segments.len() {
                 return None;
             }
 
-            match self.segments[i] {
-                Segment::Capture => {
+            match segments[i] {
+                Segment::Capture(ref constraint) => {
+                    if segment.is_empty() {
+                        return None;
+                    }
+
+                    if let Some(ref constraint) = *constraint {
+                        if !constraint.test(segment) {
+                            return None;
+                        }
+                    }
+
                     let ptr = segment.as_ptr() as usize;
 
                     captures.push((ptr - base, segment.len()));
                 }
+                Segment::Pattern(ref pattern) => {
+                    let ptr = segment.as_ptr() as usize;
+                    let start = captures.len();
+
+                    if !pattern.test(segment, &mut captures) {
+                        return None;
+                    }
+
+                    for capture in &mut captures[start..] {
+                        capture.0 += ptr - base;
+                    }
+                }
                 Segment::Glob => {
                     let ptr = segment.as_ptr() as usize;
                     let start_offset = ptr - base;
                     let len = path.len() - start_offset;
+
+                    if len == 0 {
+                        return None;
+                    }
+
                     captures.push((start_offset, len));
                     i += 1;
                     break;
@@ -75,7 +298,7 @@ impl Path {
             i += 1;
         }
 
-        if i != self.segments.len() {
+        if i != segments.len() {
             return None;
         }
 
@@ -83,6 +306,118 @@ impl Path {
     }
 }
 
+// ===== impl Segment =====
+
+impl Segment {
+    /// Parse a path segment, pushing the names of its captures onto
+    /// `captures`.
+    fn new(segment: &str, captures: &mut Vec<String>) -> Segment {
+        use tower_web_path::Part as Syntax;
+
+        let parts = match parse_segment(segment) {
+            tower_web_path::Segment::Glob(name) => {
+                captures.push(name.to_string());
+                return Segment::Glob;
+            }
+            tower_web_path::Segment::Parts(parts) => parts,
+        };
+
+        let mut parts: Vec<_> = parts.into_iter()
+            .map(|part| match part {
+                Syntax::Literal(value) => Part::Literal(value.to_string()),
+                Syntax::Capture { name, constraint } => {
+                    // The capture name is used to match handler arguments
+                    captures.push(name.to_string());
+                    Part::Capture(constraint.map(Constraint::new))
+                }
+            })
+            .collect();
+
+        if parts.len() > 1 {
+            return Segment::Pattern(Pattern {
+                source: segment.to_string(),
+                parts,
+            });
+        }
+
+        match parts.pop().unwrap() {
+            Part::Literal(value) => Segment::Literal(value),
+            Part::Capture(constraint) => Segment::Capture(constraint),
+        }
+    }
+}
+
+fn capture_shape(constraint: &Option<Constraint>) -> String {
+    match *constraint {
+        Some(ref constraint) => format!(":<{}>", constraint.source()),
+        None => ":".to_string(),
+    }
+}
+
+// ===== impl Pattern =====
+
+impl Pattern {
+    /// The segment as written in the route path.
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Test a request path segment against the pattern.
+    ///
+    /// On success, the position of each capture relative to the start of the
+    /// segment is pushed onto `captures`. When a capture could end at several
+    /// positions, the longest match is preferred.
+    pub fn test(&self, segment: &str, captures: &mut Vec<(usize, usize)>) -> bool {
+        let len = captures.len();
+
+        if test_parts(&self.parts, segment, 0, captures) {
+            return true;
+        }
+
+        captures.truncate(len);
+        false
+    }
+}
+
+fn test_parts(parts: &[Part], segment: &str, offset: usize, captures: &mut Vec<(usize, usize)>) -> bool {
+    let rest = &segment[offset..];
+
+    let (part, tail) = match parts.split_first() {
+        Some(v) => v,
+        None => return rest.is_empty(),
+    };
+
+    match *part {
+        Part::Literal(ref value) => {
+            rest.starts_with(&value[..]) &&
+                test_parts(tail, segment, offset + value.len(), captures)
+        }
+        Part::Capture(ref constraint) => {
+            for end in (1..rest.len() + 1).rev() {
+                if !rest.is_char_boundary(end) {
+                    continue;
+                }
+
+                if let Some(ref constraint) = *constraint {
+                    if !constraint.test(&rest[..end]) {
+                        continue;
+                    }
+                }
+
+                captures.push((offset, end));
+
+                if test_parts(tail, segment, offset + end, captures) {
+                    return true;
+                }
+
+                captures.pop();
+            }
+
+            false
+        }
+    }
+}
+
 #[test]
 fn test_segments() {
     let slash = Path::new("/");
@@ -163,3 +498,129 @@ fn test_glob_and_capture_segments() {
     assert_eq!(captures.get(0, path), "42");
     assert_eq!(captures.get(1, path), "a/b");
 }
+
+#[test]
+fn test_constrained_segments() {
+    let typed = Path::new("/users/:id<u32>");
+
+    let path = "/users/42";
+    let captures = typed.test(path).unwrap();
+    assert_eq!(captures.len(), 1);
+    assert_eq!(captures.get(0, path), "42");
+
+    assert!(typed.test("/users/me").is_none());
+    assert!(typed.test("/users/-1").is_none());
+
+    let regex = Path::new("/posts/:slug<[a-z-]+>/comments");
+
+    let path = "/posts/hello-world/comments";
+    let captures = regex.test(path).unwrap();
+    assert_eq!(captures.len(), 1);
+    assert_eq!(captures.get(0, path), "hello-world");
+
+    assert!(regex.test("/posts/Hello/comments").is_none());
+}
+
+#[test]
+fn test_pattern_segments() {
+    let ext = Path::new("/files/:name.:ext");
+
+    let path = "/files/report.pdf";
+    let captures = ext.test(path).unwrap();
+    assert_eq!(captures.len(), 2);
+    assert_eq!(captures.get(0, path), "report");
+    assert_eq!(captures.get(1, path), "pdf");
+
+    // The longest match is preferred
+    let path = "/files/archive.tar.gz";
+    let captures = ext.test(path).unwrap();
+    assert_eq!(captures.get(0, path), "archive.tar");
+    assert_eq!(captures.get(1, path), "gz");
+
+    assert!(ext.test("/files/report").is_none());
+    assert!(ext.test("/files/.pdf").is_none());
+    assert!(ext.test("/files/report.").is_none());
+
+    let version = Path::new("/v:version<u32>/items");
+
+    let path = "/v2/items";
+    let captures = version.test(path).unwrap();
+    assert_eq!(captures.len(), 1);
+    assert_eq!(captures.get(0, path), "2");
+
+    assert!(version.test("/vx/items").is_none());
+    assert!(version.test("/2/items").is_none());
+
+    let user = Path::new("/@:user");
+
+    let path = "/@carllerche";
+    let captures = user.test(path).unwrap();
+    assert_eq!(captures.get(0, path), "carllerche");
+
+    assert!(user.test("/carllerche").is_none());
+
+    // Constraints are taken into account when splitting the segment
+    let typed = Path::new("/:a<[a-z.]+>.:b<u8>");
+
+    let path = "/a.b.1";
+    let captures = typed.test(path).unwrap();
+    assert_eq!(captures.get(0, path), "a.b");
+    assert_eq!(captures.get(1, path), "1");
+}
+
+#[test]
+fn test_mount() {
+    let path = Path::new("/users/:id").mount("/tenants/:tenant/");
+    assert_eq!(path.source, "/tenants/:tenant/users/:id");
+    assert_eq!(path.captures, ["tenant", "id"]);
+    assert_eq!(**path.prefix().unwrap(), ["tenant"]);
+
+    let path = "/tenants/acme/users/1";
+    let captures = Path::new("/tenants/:tenant/users/:id").test(path).unwrap();
+    assert_eq!(captures.get(0, path), "acme");
+    assert_eq!(captures.get(1, path), "1");
+
+    // Nested mounts
+    let path = Path::new("/").mount("/:b").mount("/:a");
+    assert_eq!(path.source, "/:a/:b");
+    assert_eq!(**path.prefix().unwrap(), ["a", "b"]);
+
+    let path = Path::new("/").mount("/api");
+    assert_eq!(path.source, "/api");
+    assert!(path.prefix().is_none());
+    assert!(path.test("/api").is_some());
+}
+
+#[test]
+fn test_shape() {
+    let shape = |path: &str, lenient| Path::new(path).shape(lenient);
+
+    assert_eq!(shape("/users/:id<u32>", true), shape("/users/:user_id<u32>/", true));
+    assert_eq!(shape("/files/:name.:ext", true), shape("/files/:a.:b", true));
+    assert_eq!(shape("/static/*path", true), shape("/static/*file", true));
+
+    assert_ne!(shape("/users/:id<u32>", true), shape("/users/:id", true));
+    assert_ne!(shape("/users/:id", false), shape("/users/:id/", false));
+    assert_ne!(shape("/users/:id", true), shape("/users/me", true));
+}
+
+#[test]
+fn test_url() {
+    let path = Path::new("/");
+    assert_eq!(path.url(&[]).unwrap(), "/");
+
+    let path = Path::new("/users/:id<u32>");
+    assert_eq!(path.url(&[("id", "1")]).unwrap(), "/users/1");
+
+    let path = Path::new("/users/:id<u32>/");
+    assert_eq!(path.url(&[("id", "1")]).unwrap(), "/users/1/");
+    assert!(path.url(&[("id", "me")]).is_err());
+    assert!(path.url(&[("other", "1")]).is_err());
+
+    let path = Path::new("/files/:name.:ext");
+    assert_eq!(path.url(&[("ext", "txt"), ("name", "a b/c")]).unwrap(), "/files/a%20b%2Fc.txt");
+    assert!(path.url(&[("name", "a")]).is_err());
+
+    let path = Path::new("/static/*path");
+    assert_eq!(path.url(&[("path", "css/a%.css")]).unwrap(), "/static/css/a%25.css");
+}
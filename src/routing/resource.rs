@@ -1,7 +1,7 @@
 use crate::error;
 use crate::response::Serializer;
-use crate::routing::{RouteMatch, RouteSet};
-use crate::util::BufStream;
+use crate::routing::{self, RouteMatch, RouteSet};
+use crate::util::{BufStream, Chain};
 
 use bytes::Buf;
 use futures::{Future, Poll};
@@ -136,3 +136,59 @@ impl<B> Clone for Unit<B> {
         Unit::new()
     }
 }
+
+/// Resources collected in a `Vec`, for example `BoxResource` values chosen at
+/// runtime.
+impl<T, S, B> IntoResource<S, B> for Vec<T>
+where
+    T: IntoResource<S, B>,
+    S: Serializer,
+    B: BufStream,
+{
+    type Destination = (usize, T::Destination);
+    type Resource = Vec<T::Resource>;
+
+    fn routes(&self) -> RouteSet<Self::Destination> {
+        let mut routes = routing::Builder::new();
+
+        for (index, resource) in self.iter().enumerate() {
+            routes.insert_all(resource.routes().map(|destination| (index, destination)));
+        }
+
+        routes.build()
+    }
+
+    fn into_resource(self, serializer: S) -> Self::Resource {
+        self.into_iter()
+            .map(|resource| resource.into_resource(serializer.clone()))
+            .collect()
+    }
+}
+
+impl<T> Resource for Vec<T>
+where
+    T: Resource,
+{
+    type Destination = (usize, T::Destination);
+    type RequestBody = T::RequestBody;
+    type Buf = T::Buf;
+    type Body = T::Body;
+    type Future = T::Future;
+
+    fn dispatch(
+        &mut self,
+        (index, destination): Self::Destination,
+        route_match: &RouteMatch<'_>,
+        body: Self::RequestBody,
+    ) -> Self::Future {
+        self[index].dispatch(destination, route_match, body)
+    }
+}
+
+impl<T, U> Chain<U> for Vec<T> {
+    type Output = (Self, U);
+
+    fn chain(self, other: U) -> Self::Output {
+        (self, other)
+    }
+}
@@ -1,6 +1,10 @@
-use super::{Captures, Path};
+use super::{Guard, Path};
+use super::host::HostPattern;
 
-use http::{Method, Request};
+use http::{header, Method, Request};
+
+use std::fmt;
+use std::sync::Arc;
 
 /// Matches an HTTP request with a resource method.
 #[derive(Debug)]
@@ -13,6 +17,41 @@ pub struct Route<T> {
 
     /// Path used to match the route
     path: Path,
+
+    /// Additional conditions the request must satisfy
+    guards: Vec<Guard>,
+
+    /// Host the route is bound to. Routes without a host match any host.
+    host: Option<Arc<HostPattern>>,
+
+    /// Name used to build URLs for the route
+    name: Option<String>,
+
+    /// Resource type and method handling the route
+    handler: Option<(String, String)>,
+
+    /// Content type declared by the handler
+    content_type: Option<String>,
+
+    /// Media types accepted in the request body. Any media type is accepted
+    /// when empty.
+    consumes: Vec<String>,
+}
+
+/// Describes a route served by a service.
+///
+/// Returned by [`NewWebService::routes`].
+///
+/// [`NewWebService::routes`]: ../service/struct.NewWebService.html#method.routes
+#[derive(Debug, Clone, Copy)]
+pub struct RouteInfo<'a> {
+    method: &'a Method,
+    path: &'a str,
+    host: Option<&'a str>,
+    guards: &'a [Guard],
+    name: Option<&'a str>,
+    handler: Option<&'a (String, String)>,
+    content_type: Option<&'a str>,
 }
 
 impl<T> Route<T> {
@@ -25,6 +64,12 @@ impl<T> Route<T> {
             destination,
             method,
             path,
+            guards: vec![],
+            host: None,
+            name: None,
+            handler: None,
+            content_type: None,
+            consumes: vec![],
         }
     }
 
@@ -40,6 +85,164 @@ impl<T> Route<T> {
         self
     }
 
+    /// Add a guard that requests must satisfy to match this route.
+    ///
+    /// See [`Guard`](struct.Guard.html).
+    pub fn guard(mut self, guard: Guard) -> Self {
+        self.guards.push(guard);
+        self
+    }
+
+    /// Set the name used to build URLs for this route.
+    ///
+    /// See [`Urls`](struct.Urls.html).
+    pub fn name(mut self, name: &str) -> Self {
+        self.name = Some(name.to_string());
+        self
+    }
+
+    /// Set the resource type and method handling this route.
+    ///
+    /// This is only used to describe the route.
+    pub fn handler(mut self, resource: &str, method: &str) -> Self {
+        self.handler = Some((resource.to_string(), method.to_string()));
+        self
+    }
+
+    /// Set the content type declared by the handler of this route.
+    ///
+    /// This is only used to describe the route.
+    pub fn content_type(mut self, content_type: &str) -> Self {
+        self.content_type = Some(content_type.to_string());
+        self
+    }
+
+    /// Accept request bodies of the media type `media_type`.
+    ///
+    /// Once a route declares the media types it consumes, requests with a
+    /// different or missing `Content-Type` are rejected with `415 Unsupported
+    /// Media Type`. The media types are listed in the `Accept-Post` or
+    /// `Accept-Patch` header of the response.
+    ///
+    /// `type/*` accepts any subtype. `application/json` also accepts media
+    /// types with the `+json` suffix, such as `application/merge-patch+json`.
+    pub fn consumes(mut self, media_type: &str) -> Self {
+        self.consumes.push(media_type.trim().to_ascii_lowercase());
+        self
+    }
+
+    /// Returns a description of this route.
+    pub(crate) fn info(&self) -> RouteInfo<'_> {
+        RouteInfo {
+            method: &self.method,
+            path: self.path.source(),
+            host: self.host.as_ref().map(|host| host.source()),
+            guards: &self.guards,
+            name: self.route_name(),
+            handler: self.handler.as_ref(),
+            content_type: self.content_type.as_ref().map(|s| &s[..]),
+        }
+    }
+
+    /// Prefix the path matched by this route with `prefix`.
+    pub(crate) fn mount(mut self, prefix: &str) -> Self {
+        self.path = self.path.mount(prefix);
+        self
+    }
+
+    /// Bind this route to the hosts matching `host`.
+    pub(crate) fn bind_host(mut self, host: Arc<HostPattern>) -> Self {
+        if let Some(ref current) = self.host {
+            panic!("route is already bound to host {:?}", current.source());
+        }
+
+        self.host = Some(host);
+        self
+    }
+
+    /// Returns the host pattern this route is bound to.
+    pub(crate) fn host(&self) -> Option<&Arc<HostPattern>> {
+        self.host.as_ref()
+    }
+
+    /// Returns `true` if `self` and `other` match exactly the same requests.
+    ///
+    /// When `lenient` is set, trailing slashes are ignored.
+    pub(crate) fn conflicts_with<U>(&self, other: &Route<U>, lenient: bool) -> bool {
+        self.method == other.method &&
+            self.host.as_ref().map(|h| h.source()) == other.host.as_ref().map(|h| h.source()) &&
+            self.guards == other.guards &&
+            self.content_type == other.content_type &&
+            same_media_types(&self.consumes, &other.consumes) &&
+            self.path.shape(lenient) == other.path.shape(lenient)
+    }
+
+    /// Returns the HTTP request method matched by this route.
+    pub(crate) fn http_method(&self) -> &Method {
+        &self.method
+    }
+
+    /// Returns `true` if the request satisfies every guard of this route.
+    pub(crate) fn test_guards(&self, request: &Request<()>) -> bool {
+        self.guards.iter().all(|guard| guard.test(request))
+    }
+
+    /// Returns `true` if the route consumes the media type of the request body.
+    pub(crate) fn test_content_type(&self, request: &Request<()>) -> bool {
+        if self.consumes.is_empty() {
+            return true;
+        }
+
+        let content_type = match request.headers().get(header::CONTENT_TYPE) {
+            Some(value) => value.to_str().unwrap_or(""),
+            None => return false,
+        };
+
+        // Parameters, such as the charset, are ignored.
+        let media_type = content_type.split(';').next()
+            .unwrap_or("")
+            .trim()
+            .to_ascii_lowercase();
+
+        self.consumes.iter()
+            .any(|consumes| consumes_media_type(consumes, &media_type))
+    }
+
+    /// Returns the media types accepted in the request body.
+    pub(crate) fn consumed(&self) -> &[String] {
+        &self.consumes
+    }
+
+    /// Returns the path pattern matched by this route.
+    pub(crate) fn pattern(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the name used to build URLs for this route.
+    pub(crate) fn route_name(&self) -> Option<&str> {
+        self.name.as_ref().map(|name| &name[..])
+    }
+
+    /// Returns the name of this route unless it is the default one, the name
+    /// of the method handling the route.
+    pub(crate) fn explicit_name(&self) -> Option<&str> {
+        match self.handler {
+            Some((_, ref method)) if self.route_name() == Some(&method[..]) => None,
+            _ => self.route_name(),
+        }
+    }
+
+    /// Returns the name of the method handling this route, qualified by the
+    /// resource type, as in `Users::show_user`.
+    pub(crate) fn qualified_name(&self) -> Option<String> {
+        self.handler.as_ref()
+            .map(|(resource, method)| format!("{}::{}", resource, method))
+    }
+
+    pub(crate) fn destination(&self) -> &T {
+        &self.destination
+    }
+
     pub(crate) fn map<F, U>(self, f: F) -> Route<U>
     where
         F: Fn(T) -> U,
@@ -50,27 +253,104 @@ impl<T> Route<T> {
             destination,
             method: self.method,
             path: self.path,
+            guards: self.guards,
+            host: self.host,
+            name: self.name,
+            handler: self.handler,
+            content_type: self.content_type,
+            consumes: self.consumes,
         }
     }
 }
 
-impl<T> Route<T>
-where
-    T: Clone,
-{
-    /// Try to match a request against this route.
-    pub(crate) fn test<'a>(
-        &'a self,
-        request: &Request<()>,
-    ) -> Option<(T, Captures)> {
-
-        if *request.method() != self.method {
-            return None;
+/// Returns `true` if a route consuming `consumes` accepts `media_type`.
+fn consumes_media_type(consumes: &str, media_type: &str) -> bool {
+    if consumes == "*/*" || consumes == media_type {
+        return true;
+    }
+
+    if consumes.ends_with("/*") {
+        let prefix = &consumes[..consumes.len() - 1];
+        return media_type.starts_with(prefix);
+    }
+
+    consumes == "application/json" &&
+        media_type.starts_with("application/") &&
+        media_type.ends_with("+json")
+}
+
+/// Returns `true` if `a` and `b` contain the same media types, in any order.
+fn same_media_types(a: &[String], b: &[String]) -> bool {
+    a.iter().all(|media_type| b.contains(media_type)) &&
+        b.iter().all(|media_type| a.contains(media_type))
+}
+
+// ===== impl RouteInfo =====
+
+impl<'a> RouteInfo<'a> {
+    /// Returns the HTTP method matched by the route.
+    pub fn method(&self) -> &'a Method {
+        self.method
+    }
+
+    /// Returns the path pattern matched by the route, as it was defined.
+    ///
+    /// This includes the prefix the resource is mounted under.
+    pub fn path(&self) -> &'a str {
+        self.path
+    }
+
+    /// Returns the host pattern the route is bound to, if any.
+    pub fn host(&self) -> Option<&'a str> {
+        self.host
+    }
+
+    /// Returns the guards the request must satisfy to match the route.
+    pub fn guards(&self) -> &'a [Guard] {
+        self.guards
+    }
+
+    /// Returns the name used to build URLs for the route.
+    pub fn name(&self) -> Option<&'a str> {
+        self.name
+    }
+
+    /// Returns the name of the resource type handling the route.
+    pub fn resource(&self) -> Option<&'a str> {
+        self.handler.map(|(resource, _)| &resource[..])
+    }
+
+    /// Returns the name of the resource method handling the route.
+    pub fn handler(&self) -> Option<&'a str> {
+        self.handler.map(|(_, method)| &method[..])
+    }
+
+    /// Returns the content type declared by the route handler.
+    pub fn content_type(&self) -> Option<&'a str> {
+        self.content_type
+    }
+}
+
+impl<'a> fmt::Display for RouteInfo<'a> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "{} {}", self.method, self.path)?;
+
+        if let Some(host) = self.host {
+            write!(fmt, " [host {}]", host)?;
+        }
+
+        for guard in self.guards {
+            write!(fmt, " [{}]", guard)?;
+        }
+
+        if let Some((resource, method)) = self.handler {
+            write!(fmt, " -> {}::{}", resource, method)?;
+        }
+
+        if let Some(content_type) = self.content_type {
+            write!(fmt, " ({})", content_type)?;
         }
 
-        self.path.test(request.uri().path())
-            .map(|captures| {
-                (self.destination.clone(), captures)
-            })
+        Ok(())
     }
 }
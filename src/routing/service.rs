@@ -1,15 +1,23 @@
 use crate::config::Config;
 use crate::error::{self, Error, Catch};
 use http::status::StatusCode;
-use crate::routing::{Resource, ResourceFuture, RouteSet, RouteMatch};
+use crate::routing::{Resource, ResourceFuture, RouteInfo, RouteSet, RouteMatch, Lookup, TrailingSlash};
+use crate::routing::normalize;
+use crate::util::BufStream;
+use crate::util::buf_stream::Empty;
 use crate::util::http::HttpFuture;
-use crate::util::tuple::Either2;
+use crate::util::tuple::Either3;
 
 use futures::{Future, Poll, try_ready};
 use http;
+use http::header::{self, HeaderName, HeaderValue};
+use http::{Method, Uri};
+use http::uri::{self, PathAndQuery};
 use tower_service::Service;
 
+use std::borrow::Borrow;
 use std::fmt;
+use std::io;
 use std::sync::Arc;
 
 /// Web service
@@ -29,22 +37,38 @@ where
     /// Route set. Processes request to determine how the resource will process
     /// it.
     routes: Arc<RouteSet<T::Destination>>,
+
+    /// How trailing slashes in request paths are handled.
+    trailing_slash: TrailingSlash,
 }
 
 /// Response future returned by `RoutedService`
 #[derive(Debug)]
 pub struct RoutedResponse<T, U>
-where U: Catch,
+where T: ResourceFuture,
+      U: Catch,
 {
     request: http::Request<()>,
     catch: U,
     state: State<T, U::Future>,
+
+    /// Header to set on the error response, such as `Allow` when the request
+    /// method is not allowed.
+    error_header: Option<(HeaderName, HeaderValue)>,
 }
 
+/// Response body returned by `RoutedService`
+///
+/// Responses to `HEAD` and implicit `OPTIONS` requests have an empty body.
+type RoutedBody<T, U> = Either3<T, U, Empty<io::Cursor<&'static [u8]>, Error>>;
+
 #[derive(Debug)]
 enum State<T, U> {
     Pending(T),
     Catching(U),
+
+    /// The response has no body and is ready to be returned.
+    Done(Option<http::Response<()>>),
 }
 
 impl<T, U> Clone for RoutedService<T, U>
@@ -58,6 +82,7 @@ where
             catch: self.catch.clone(),
             config: self.config.clone(),
             routes: self.routes.clone(),
+            trailing_slash: self.trailing_slash,
         }
     }
 }
@@ -83,7 +108,14 @@ impl<T, U> RoutedService<T, U>
 where T: Resource,
 {
     /// Create a new `RoutedService`
-    pub(crate) fn new(resource: T, catch: U, config: Config, routes: RouteSet<T::Destination>) -> Self {
+    pub(crate) fn new(
+        resource: T,
+        catch: U,
+        config: Config,
+        mut routes: RouteSet<T::Destination>,
+        trailing_slash: TrailingSlash,
+    ) -> Self {
+        routes.build_tree(trailing_slash);
         let routes = Arc::new(routes);
 
         RoutedService {
@@ -91,8 +123,18 @@ where T: Resource,
             catch,
             config,
             routes,
+            trailing_slash,
         }
     }
+
+    /// Describe the routes served by the service, in the order they were
+    /// added.
+    ///
+    /// This is not the order in which requests are matched against them:
+    /// more specific paths are preferred regardless of declaration order.
+    pub fn routes(&self) -> Vec<RouteInfo<'_>> {
+        self.routes.routes()
+    }
 }
 
 impl<T, U> Service for RoutedService<T, U>
@@ -111,11 +153,34 @@ where T: Resource,
 
     fn call(&mut self, request: Self::Request) -> Self::Future {
         // TODO: Use the body
-        let (head, body) = request.into_parts();
+        let (mut head, body) = request.into_parts();
+        let mut redirect = None;
+
+        if let Some(path) = normalize(head.uri.path()) {
+            if self.trailing_slash == TrailingSlash::Redirect {
+                redirect = Some(path);
+            } else {
+                head.uri = with_path(&head.uri, &path);
+            }
+        }
+
         let request = http::Request::from_parts(head, ());
 
+        let mut error_header = None;
+
+        if let Some(path) = redirect {
+            let response = redirect_response(request.uri(), &path);
+
+            return RoutedResponse {
+                request,
+                catch: self.catch.clone(),
+                state: State::Done(Some(response)),
+                error_header,
+            };
+        }
+
         let state = match self.routes.test(&request) {
-            Some((destination, captures)) => {
+            Lookup::Found(destination, captures) => {
                 // Create the `RouteMatch` for the routing result
                 let route_match = RouteMatch::new(&request, captures, &self.config);
 
@@ -125,12 +190,44 @@ where T: Resource,
 
                 State::Pending(pending)
             }
-            None => {
-                let error = Error::from(StatusCode::NOT_FOUND);
+            Lookup::Options(methods) => {
+                let response = http::Response::builder()
+                    .header(header::ALLOW, allow_header(&methods))
+                    .header(header::CONTENT_LENGTH, 0)
+                    .body(())
+                    .unwrap();
+
+                State::Done(Some(response))
+            }
+            Lookup::MethodNotAllowed(methods) => {
+                error_header = Some((header::ALLOW, allow_header(&methods)));
+
+                let error = Error::from(StatusCode::METHOD_NOT_ALLOWED);
                 let catching = self.catch.catch(&request, error);
 
                 State::Catching(catching)
             }
+            Lookup::UnsupportedMediaType(media_types) => {
+                error_header = accept_header(request.method(), &media_types);
+
+                let error = Error::from(StatusCode::UNSUPPORTED_MEDIA_TYPE);
+                let catching = self.catch.catch(&request, error);
+
+                State::Catching(catching)
+            }
+            Lookup::NotFound => {
+                match self.canonical_path(&request) {
+                    Some(path) => {
+                        State::Done(Some(redirect_response(request.uri(), &path)))
+                    }
+                    None => {
+                        let error = Error::from(StatusCode::NOT_FOUND);
+                        let catching = self.catch.catch(&request, error);
+
+                        State::Catching(catching)
+                    }
+                }
+            }
         };
 
         let catch = self.catch.clone();
@@ -139,17 +236,103 @@ where T: Resource,
             request,
             catch,
             state,
+            error_header,
+        }
+    }
+}
+
+impl<T, U> RoutedService<T, U>
+where T: Resource,
+{
+    /// When redirecting to canonical paths, returns the path with the
+    /// trailing slash added or removed if a route matches it.
+    fn canonical_path(&self, request: &http::Request<()>) -> Option<String> {
+        let path = request.uri().path();
+
+        if self.trailing_slash != TrailingSlash::Redirect || path == "/" || !path.starts_with('/') {
+            return None;
+        }
+
+        let toggled = if path.ends_with('/') {
+            path[..path.len() - 1].to_string()
+        } else {
+            format!("{}/", path)
+        };
+
+        if self.routes.matches_path(request, &toggled) {
+            Some(toggled)
+        } else {
+            None
         }
     }
 }
 
+/// Returns `path` followed by the query of `uri`, if any.
+fn path_and_query(uri: &Uri, path: &str) -> PathAndQuery {
+    let mut path_and_query = path.to_string();
+
+    if let Some(query) = uri.query() {
+        path_and_query.push('?');
+        path_and_query.push_str(query);
+    }
+
+    path_and_query.parse()
+        .expect("normalized path is a valid URI path")
+}
+
+/// Replace the path of `uri`, keeping the query.
+pub(crate) fn with_path(uri: &Uri, path: &str) -> Uri {
+    let mut parts = uri::Parts::from(uri.clone());
+    parts.path_and_query = Some(path_and_query(uri, path));
+
+    Uri::from_parts(parts)
+        .expect("normalized path is a valid URI path")
+}
+
+/// Permanently redirect the request to `path`, keeping the query.
+fn redirect_response(uri: &Uri, path: &str) -> http::Response<()> {
+    let location = path_and_query(uri, path);
+
+    http::Response::builder()
+        .status(StatusCode::PERMANENT_REDIRECT)
+        .header(header::LOCATION, location.as_str())
+        .header(header::CONTENT_LENGTH, 0)
+        .body(())
+        .unwrap()
+}
+
+fn allow_header(methods: &[Method]) -> HeaderValue {
+    let methods: Vec<_> = methods.iter()
+        .map(|method| method.as_str())
+        .collect();
+
+    HeaderValue::from_str(&methods.join(", "))
+        .expect("method names are valid header values")
+}
+
+/// Lists the media types accepted by `POST` or `PATCH` requests, as defined by
+/// the `Accept-Post` and `Accept-Patch` headers.
+pub(crate) fn accept_header<S>(method: &Method, media_types: &[S]) -> Option<(HeaderName, HeaderValue)>
+where S: Borrow<str>,
+{
+    let name = match *method {
+        Method::POST => HeaderName::from_static("accept-post"),
+        Method::PATCH => HeaderName::from_static("accept-patch"),
+        _ => return None,
+    };
+
+    HeaderValue::from_str(&media_types.join(", ")).ok()
+        .map(|value| (name, value))
+}
+
 // ===== impl RoutedResponse =====
 
 impl<T, U> Future for RoutedResponse<T, U>
 where T: ResourceFuture,
+      T::Body: BufStream<Error = Error>,
       U: Catch,
 {
-    type Item = http::Response<Either2<T::Body, error::Map<U::Body>>>;
+    type Item = http::Response<RoutedBody<T::Body, error::Map<U::Body>>>;
     type Error = Error;
 
     fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
@@ -158,28 +341,64 @@ where T: ResourceFuture,
         use futures::Async::*;
 
         loop {
-            let catching = match self.state {
+            let response = match self.state {
                 Pending(ref mut fut) => {
-                    let error = match fut.poll_response(&self.request) {
-                        Ok(Ready(v)) => {
-                            let v = v.map(A);
-                            return Ok(Ready(v))
-                        }
+                    match fut.poll_response(&self.request) {
+                        Ok(Ready(v)) => v.map(A),
                         Ok(NotReady) => return Ok(NotReady),
-                        Err(e) => e,
-                    };
+                        Err(mut error) => {
+                            if let Some(header) = error.take_header() {
+                                self.error_header = Some(header);
+                            }
 
-                    self.catch.catch(&self.request, error)
+                            let catching = self.catch.catch(&self.request, error);
+                            self.state = Catching(catching);
+                            continue;
+                        }
+                    }
                 }
                 Catching(ref mut fut) => {
-                    let resp = try_ready!(HttpFuture::poll_http(fut))
+                    let mut resp = try_ready!(HttpFuture::poll_http(fut))
                         .map(|body| B(error::Map::new(body)));
 
-                    return Ok(Ready(resp));
+                    if let Some((name, value)) = self.error_header.take() {
+                        resp.headers_mut().insert(name, value);
+                    }
+
+                    resp
+                }
+                Done(ref mut head) => {
+                    let head = head.take().expect("polled after ready");
+                    return Ok(Ready(head.map(|_| Either3::C(Empty::new()))));
                 }
             };
 
-            self.state = Catching(catching);
+            if self.request.method() != Method::HEAD {
+                let response = response.map(|body| {
+                    match body {
+                        A(body) => Either3::A(body),
+                        B(body) => Either3::B(body),
+                    }
+                });
+
+                return Ok(Ready(response));
+            }
+
+            // The response to a `HEAD` request has no body, and the body is
+            // never polled. It carries the `Content-Length` of the body that
+            // would have been sent when the body knows its exact size.
+            let (parts, body) = response.into_parts();
+            let mut head = http::Response::from_parts(parts, ());
+
+            if !head.headers().contains_key(header::CONTENT_LENGTH) {
+                let hint = body.size_hint();
+
+                if hint.upper() == Some(hint.lower()) {
+                    head.headers_mut().insert(header::CONTENT_LENGTH, HeaderValue::from(hint.lower()));
+                }
+            }
+
+            return Ok(Ready(head.map(|_| Either3::C(Empty::new()))));
         }
     }
 }
@@ -1,6 +1,10 @@
-use super::{Route, Captures};
+use super::{Route, RouteInfo, Captures, TrailingSlash};
+use super::host::{HostCaptures, HostPattern};
+use super::tree::{Rejection, Tree};
 
-use http::Request;
+use http::{Method, Request};
+
+use std::sync::Arc;
 
 /// A set of routes
 ///
@@ -9,6 +13,47 @@ use http::Request;
 #[derive(Debug)]
 pub struct RouteSet<T> {
     routes: Vec<Route<T>>,
+
+    /// Prefix trees used to match requests. Built once the set is complete.
+    trees: Option<Trees>,
+}
+
+/// Prefix trees used to match requests.
+///
+/// The host is matched before the path. Every host pattern has its own tree,
+/// indexing the routes bound to that host along with the routes that are not
+/// bound to any host.
+#[derive(Debug)]
+struct Trees {
+    /// Trees for each host pattern, most specific host pattern first. Host
+    /// patterns that are equally specific are kept in insertion order.
+    hosts: Vec<(Arc<HostPattern>, Tree)>,
+
+    /// Tree used when the request host matches no host pattern.
+    any: Tree,
+}
+
+/// Result of matching a request against a `RouteSet`
+#[derive(Debug)]
+pub(crate) enum Lookup<T> {
+    /// A route matched both the request method and path.
+    Found(T, Captures),
+
+    /// The request is an `OPTIONS` request for a path that has no explicit
+    /// `OPTIONS` route. Contains the methods that are accepted.
+    Options(Vec<Method>),
+
+    /// At least one route matched the request path, but none of them accept
+    /// the request method. Contains the methods that are accepted.
+    MethodNotAllowed(Vec<Method>),
+
+    /// Routes matched the request method and path, but none of them consume
+    /// the media type of the request body. Contains the media types that are
+    /// accepted.
+    UnsupportedMediaType(Vec<String>),
+
+    /// No route matched the request path.
+    NotFound,
 }
 
 // ===== impl RouteSet =====
@@ -16,23 +61,169 @@ pub struct RouteSet<T> {
 impl<T> RouteSet<T> {
     /// Create a new, empty, `RouteSet`.
     pub fn new() -> RouteSet<T> {
-        RouteSet { routes: vec![] }
+        RouteSet {
+            routes: vec![],
+            trees: None,
+        }
     }
 
     pub(crate) fn map<F, U>(self, f: F) -> RouteSet<U>
     where F: Fn(T) -> U,
     {
         RouteSet {
-            routes: self.routes.into_iter().map(|r| r.map(&f)).collect()
+            routes: self.routes.into_iter().map(|r| r.map(&f)).collect(),
+            trees: self.trees,
         }
     }
 
     pub(crate) fn insert(&mut self, route: Route<T>) {
         self.routes.push(route);
+        self.trees = None;
     }
 
     pub(crate) fn insert_all(&mut self, set: RouteSet<T>) {
         self.routes.extend(set.routes);
+        self.trees = None;
+    }
+
+    /// Prefix the path of every route in the set with `prefix`.
+    pub(crate) fn mount(self, prefix: &str) -> RouteSet<T> {
+        RouteSet {
+            routes: self.routes.into_iter().map(|r| r.mount(prefix)).collect(),
+            trees: None,
+        }
+    }
+
+    /// Bind every route in the set to the hosts matching `host`.
+    pub(crate) fn host(self, host: &str) -> RouteSet<T> {
+        let host = Arc::new(HostPattern::new(host));
+
+        RouteSet {
+            routes: self.routes.into_iter().map(|r| r.bind_host(host.clone())).collect(),
+            trees: None,
+        }
+    }
+
+    pub(crate) fn iter(&self) -> impl Iterator<Item = &Route<T>> {
+        self.routes.iter()
+    }
+
+    /// Describe the routes in the set, in the order they were inserted.
+    pub fn routes(&self) -> Vec<RouteInfo<'_>> {
+        self.routes.iter().map(Route::info).collect()
+    }
+
+    /// Build the prefix tree used to match requests.
+    ///
+    /// This must be called once all routes have been inserted and before
+    /// calling `test`.
+    pub(crate) fn build_tree(&mut self, trailing_slash: TrailingSlash) {
+        let lenient = trailing_slash == TrailingSlash::Lenient;
+        let mut hosts: Vec<(Arc<HostPattern>, Tree)> = vec![];
+
+        for route in &self.routes {
+            let host = match route.host() {
+                Some(host) => host,
+                None => continue,
+            };
+
+            if hosts.iter().any(|(h, _)| h.source() == host.source()) {
+                continue;
+            }
+
+            let tree = Tree::new(&self.routes, lenient, |route| {
+                route.host().map(|h| h.source() == host.source()).unwrap_or(true)
+            });
+
+            hosts.push((host.clone(), tree));
+        }
+
+        hosts.sort_by_key(|(host, _)| host.rank());
+
+        let any = Tree::new(&self.routes, lenient, |route| route.host().is_none());
+
+        self.trees = Some(Trees { hosts, any });
+    }
+
+    /// Returns the pairs of routes that match exactly the same requests.
+    ///
+    /// For each pair, the first route is the one that was inserted first and
+    /// takes priority.
+    pub(crate) fn conflicts(&self, trailing_slash: TrailingSlash) -> Vec<(RouteInfo<'_>, RouteInfo<'_>)> {
+        let lenient = trailing_slash == TrailingSlash::Lenient;
+        let mut conflicts = vec![];
+
+        for (i, route) in self.routes.iter().enumerate() {
+            let earlier = self.routes[..i].iter()
+                .find(|other| other.conflicts_with(route, lenient));
+
+            if let Some(earlier) = earlier {
+                conflicts.push((earlier.info(), route.info()));
+            }
+        }
+
+        conflicts
+    }
+
+    /// Returns the pairs of routes, handled by different methods, that were
+    /// explicitly given the same name.
+    ///
+    /// For each pair, the first route is the one that was inserted first.
+    pub(crate) fn duplicate_names(&self) -> Vec<(RouteInfo<'_>, RouteInfo<'_>)> {
+        let mut duplicates = vec![];
+
+        for (i, route) in self.routes.iter().enumerate() {
+            let name = match route.explicit_name() {
+                Some(name) => name,
+                None => continue,
+            };
+
+            let earlier = self.routes[..i].iter()
+                .find(|other| {
+                    other.explicit_name() == Some(name) &&
+                        (other.qualified_name() != route.qualified_name() ||
+                         route.qualified_name().is_none())
+                });
+
+            if let Some(earlier) = earlier {
+                duplicates.push((earlier.info(), route.info()));
+            }
+        }
+
+        duplicates
+    }
+
+    /// Select the trees matching the request host, most specific host first.
+    ///
+    /// The tree for routes not bound to any host is only selected when the
+    /// request host matches no host pattern, as every host tree already
+    /// includes these routes.
+    fn select(&self, request: &Request<()>) -> Vec<(&Tree, HostCaptures)> {
+        let trees = self.trees.as_ref()
+            .expect("route set must be built before matching requests");
+
+        let selected: Vec<_> = trees.hosts.iter()
+            .filter_map(|(host, tree)| {
+                host.test_request(request).map(|captures| (tree, captures))
+            })
+            .collect();
+
+        if selected.is_empty() {
+            vec![(&trees.any, vec![])]
+        } else {
+            selected
+        }
+    }
+
+    /// Returns `true` if any route matches the request with its path replaced
+    /// by `path`, regardless of the method.
+    pub(crate) fn matches_path(&self, request: &Request<()>, path: &str) -> bool {
+        self.select(request).iter().any(|(tree, _)| {
+            match tree.test(&self.routes, request, &Method::GET, path) {
+                Ok(_) | Err(Rejection::MediaType(_)) => true,
+                Err(Rejection::Method(allowed)) => !allowed.is_empty(),
+            }
+        })
     }
 }
 
@@ -41,10 +232,507 @@ where
     T: Clone,
 {
     /// Match a request against a route set
-    pub(crate) fn test(&self, request: &Request<()>) -> Option<(T, Captures)> {
-        self.routes
-            .iter()
-            .flat_map(|route| route.test(request))
-            .next()
+    ///
+    /// Routes bound to a host are only matched when the request targets that
+    /// host. When the request host matches several host patterns, exact hosts
+    /// are tried before hosts with captures, which are tried before wildcard
+    /// hosts. The next host pattern is only tried when no route bound to the
+    /// previous one matches the request path.
+    ///
+    /// When multiple routes match the request, the most specific one is
+    /// returned: literal segments rank above captures, which rank above globs.
+    /// Routes that are equally specific are returned in insertion order.
+    ///
+    /// `HEAD` requests that do not match a `HEAD` route are matched against
+    /// the `GET` routes instead. `OPTIONS` requests that do not match an
+    /// `OPTIONS` route result in `Lookup::Options`.
+    pub(crate) fn test(&self, request: &Request<()>) -> Lookup<T> {
+        for (tree, host) in self.select(request) {
+            match self.test_tree(tree, host, request) {
+                Lookup::NotFound => {}
+                lookup => return lookup,
+            }
+        }
+
+        Lookup::NotFound
+    }
+
+    fn test_tree(&self, tree: &Tree, host: HostCaptures, request: &Request<()>) -> Lookup<T> {
+        let method = request.method();
+        let path = request.uri().path();
+
+        let mut allowed = match tree.test(&self.routes, request, method, path) {
+            Ok((index, captures)) => return self.found(index, captures, host),
+            Err(Rejection::MediaType(consumed)) => return Lookup::UnsupportedMediaType(consumed),
+            Err(Rejection::Method(ref allowed)) if allowed.is_empty() => return Lookup::NotFound,
+            Err(Rejection::Method(allowed)) => allowed,
+        };
+
+        if *method == Method::HEAD && allowed.contains(&Method::GET) {
+            if let Ok((index, captures)) = tree.test(&self.routes, request, &Method::GET, path) {
+                return self.found(index, captures, host);
+            }
+        }
+
+        // `HEAD` and `OPTIONS` are implicitly handled.
+        if let Some(pos) = allowed.iter().position(|m| *m == Method::GET) {
+            if !allowed.contains(&Method::HEAD) {
+                allowed.insert(pos + 1, Method::HEAD);
+            }
+        }
+
+        if !allowed.contains(&Method::OPTIONS) {
+            allowed.push(Method::OPTIONS);
+        }
+
+        if *method == Method::OPTIONS {
+            Lookup::Options(allowed)
+        } else {
+            Lookup::MethodNotAllowed(allowed)
+        }
+    }
+
+    fn found(&self, index: usize, captures: Vec<(usize, usize)>, host: HostCaptures) -> Lookup<T> {
+        let route = &self.routes[index];
+
+        let mut captures = match route.pattern().prefix() {
+            Some(prefix) => Captures::mounted(captures, prefix.clone()),
+            None => Captures::new(captures),
+        };
+
+        if route.host().is_some() {
+            captures = captures.with_host(host);
+        }
+
+        Lookup::Found(route.destination().clone(), captures)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn route_set(routes: &[(Method, &str)]) -> RouteSet<usize> {
+        route_set_with(routes, TrailingSlash::Lenient)
+    }
+
+    fn route_set_with(routes: &[(Method, &str)], trailing_slash: TrailingSlash) -> RouteSet<usize> {
+        let mut set = RouteSet::new();
+
+        for (i, &(ref method, path)) in routes.iter().enumerate() {
+            set.insert(Route::new(i).method(method.clone()).path(path));
+        }
+
+        set.build_tree(trailing_slash);
+        set
+    }
+
+    fn lookup(set: &RouteSet<usize>, method: Method, path: &str) -> Lookup<usize> {
+        let request = Request::builder()
+            .method(method)
+            .uri(path)
+            .body(())
+            .unwrap();
+
+        set.test(&request)
+    }
+
+    fn test(set: &RouteSet<usize>, method: Method, path: &str) -> Option<(usize, Vec<String>)> {
+        match lookup(set, method, path) {
+            Lookup::Found(destination, captures) => {
+                let captures = (0..captures.len())
+                    .map(|i| captures.get(i, path).to_string())
+                    .collect();
+
+                Some((destination, captures))
+            }
+            _ => None,
+        }
+    }
+
+    fn allowed(set: &RouteSet<usize>, method: Method, path: &str) -> Option<Vec<Method>> {
+        match lookup(set, method, path) {
+            Lookup::MethodNotAllowed(allowed) => Some(allowed),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn most_specific_wins() {
+        let set = route_set(&[
+            (Method::GET, "/users/:id"),
+            (Method::GET, "/users/me"),
+            (Method::GET, "/*path"),
+        ]);
+
+        assert_eq!(test(&set, Method::GET, "/users/me"), Some((1, vec![])));
+        assert_eq!(test(&set, Method::GET, "/users/1/"), Some((0, vec!["1".into()])));
+        assert_eq!(test(&set, Method::GET, "/users"), Some((2, vec!["users".into()])));
+        assert_eq!(test(&set, Method::GET, "/users/1/a"), Some((2, vec!["users/1/a".into()])));
+
+        let set = route_set(&[
+            (Method::GET, "/*path"),
+            (Method::GET, "/users/:id"),
+            (Method::GET, "/:a/:b<u32>"),
+            (Method::GET, "/:a/:b<[a-z]+>"),
+            (Method::GET, "/:a/:b.json"),
+        ]);
+
+        assert_eq!(test(&set, Method::GET, "/users/me"), Some((1, vec!["me".into()])));
+        assert_eq!(test(&set, Method::GET, "/posts/1"), Some((2, vec!["posts".into(), "1".into()])));
+        assert_eq!(test(&set, Method::GET, "/posts/me"), Some((3, vec!["posts".into(), "me".into()])));
+        assert_eq!(test(&set, Method::GET, "/posts/me.json"), Some((4, vec!["posts".into(), "me".into()])));
+        assert_eq!(test(&set, Method::GET, "/posts/Me"), Some((0, vec!["posts/Me".into()])));
+
+        // Specificity is compared from left to right
+        let set = route_set(&[
+            (Method::GET, "/:a/b"),
+            (Method::GET, "/a/:b"),
+        ]);
+
+        assert_eq!(test(&set, Method::GET, "/a/b"), Some((1, vec!["b".into()])));
+    }
+
+    #[test]
+    fn equally_specific_first_inserted_wins() {
+        let set = route_set(&[
+            (Method::GET, "/users/:id"),
+            (Method::GET, "/users/:name"),
+            (Method::DELETE, "/users/me"),
+        ]);
+
+        assert_eq!(test(&set, Method::GET, "/users/me"), Some((0, vec!["me".into()])));
+    }
+
+    #[test]
+    fn conflicts() {
+        let routes = [
+            (Method::GET, "/users/:id"),
+            (Method::GET, "/users/:user_id/"),
+            (Method::POST, "/users/:id"),
+            (Method::GET, "/users/me"),
+        ];
+
+        let set = route_set_with(&routes, TrailingSlash::Lenient);
+        let conflicts = set.conflicts(TrailingSlash::Lenient);
+
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].0.path(), "/users/:id");
+        assert_eq!(conflicts[0].1.path(), "/users/:user_id/");
+
+        assert!(set.conflicts(TrailingSlash::Strict).is_empty());
+    }
+
+    #[test]
+    fn match_method() {
+        let set = route_set(&[
+            (Method::POST, "/"),
+            (Method::GET, "/:id"),
+            (Method::GET, "/"),
+        ]);
+
+        assert_eq!(test(&set, Method::GET, "/"), Some((2, vec![])));
+        assert_eq!(test(&set, Method::POST, "/"), Some((0, vec![])));
+        assert_eq!(test(&set, Method::DELETE, "/"), None);
+        assert_eq!(test(&set, Method::GET, "/foo"), Some((1, vec!["foo".into()])));
+        assert_eq!(test(&set, Method::POST, "/foo"), None);
+    }
+
+    #[test]
+    fn backtrack_to_capture() {
+        let set = route_set(&[
+            (Method::GET, "/:a/bar"),
+            (Method::GET, "/foo/baz"),
+        ]);
+
+        assert_eq!(test(&set, Method::GET, "/foo/bar"), Some((0, vec!["foo".into()])));
+        assert_eq!(test(&set, Method::GET, "/foo/baz"), Some((1, vec![])));
+        assert_eq!(test(&set, Method::GET, "/foo"), None);
+    }
+
+    #[test]
+    fn constrained_captures() {
+        let set = route_set(&[
+            (Method::GET, "/users/:id<u32>"),
+            (Method::GET, "/users/:name<[a-z]+>"),
+            (Method::GET, "/users/:other"),
+            (Method::DELETE, "/items/:id<u32>"),
+        ]);
+
+        assert_eq!(test(&set, Method::GET, "/users/42"), Some((0, vec!["42".into()])));
+        assert_eq!(test(&set, Method::GET, "/users/bob"), Some((1, vec!["bob".into()])));
+        assert_eq!(test(&set, Method::GET, "/users/Bob"), Some((2, vec!["Bob".into()])));
+
+        assert_eq!(allowed(&set, Method::GET, "/items/42"), Some(vec![Method::DELETE, Method::OPTIONS]));
+
+        match lookup(&set, Method::GET, "/items/foo") {
+            Lookup::NotFound => {}
+            actual => panic!("unexpected lookup result; {:?}", actual),
+        }
+    }
+
+    #[test]
+    fn pattern_captures() {
+        let set = route_set(&[
+            (Method::GET, "/files/:name.json"),
+            (Method::GET, "/files/:name.:ext"),
+            (Method::GET, "/files/:name"),
+        ]);
+
+        assert_eq!(test(&set, Method::GET, "/files/a.json"), Some((0, vec!["a".into()])));
+        assert_eq!(test(&set, Method::GET, "/files/a.b.txt"), Some((1, vec!["a.b".into(), "txt".into()])));
+        assert_eq!(test(&set, Method::GET, "/files/a"), Some((2, vec!["a".into()])));
+    }
+
+    #[test]
+    fn trailing_slash() {
+        let routes = [
+            (Method::GET, "/users"),
+            (Method::GET, "/posts/"),
+            (Method::GET, "/files/*path"),
+            (Method::GET, "/"),
+        ];
+
+        let set = route_set_with(&routes, TrailingSlash::Lenient);
+
+        assert_eq!(test(&set, Method::GET, "/users/"), Some((0, vec![])));
+        assert_eq!(test(&set, Method::GET, "/posts"), Some((1, vec![])));
+        assert_eq!(test(&set, Method::GET, "/files/a/"), Some((2, vec!["a".into()])));
+        assert_eq!(test(&set, Method::GET, "/files/"), None);
+        assert_eq!(test(&set, Method::GET, "/"), Some((3, vec![])));
+
+        let set = route_set_with(&routes, TrailingSlash::Strict);
+
+        assert_eq!(test(&set, Method::GET, "/users"), Some((0, vec![])));
+        assert_eq!(test(&set, Method::GET, "/users/"), None);
+        assert_eq!(test(&set, Method::GET, "/posts/"), Some((1, vec![])));
+        assert_eq!(test(&set, Method::GET, "/posts"), None);
+        assert_eq!(test(&set, Method::GET, "/files/a/"), Some((2, vec!["a/".into()])));
+        assert_eq!(test(&set, Method::GET, "/files/"), None);
+        assert_eq!(test(&set, Method::GET, "/"), Some((3, vec![])));
+
+        let request = Request::builder().uri("/").body(()).unwrap();
+        assert!(set.matches_path(&request, "/users"));
+        assert!(!set.matches_path(&request, "/users/"));
+    }
+
+    #[test]
+    fn guarded_routes() {
+        use crate::routing::Guard;
+
+        let mut set = RouteSet::new();
+        set.insert(Route::new(0).method(Method::GET).path("/users/:id").guard(Guard::header("x-admin")));
+        set.insert(Route::new(1).method(Method::POST).path("/users/:id").guard(Guard::header("x-admin")));
+        set.insert(Route::new(2).method(Method::GET).path("/users/:id"));
+        set.build_tree(TrailingSlash::Lenient);
+
+        let request = Request::builder()
+            .uri("/users/1")
+            .header("x-admin", "")
+            .body(())
+            .unwrap();
+
+        match set.test(&request) {
+            Lookup::Found(0, _) => {}
+            actual => panic!("unexpected lookup result; {:?}", actual),
+        }
+
+        assert_eq!(test(&set, Method::GET, "/users/1"), Some((2, vec!["1".into()])));
+
+        // Routes rejected by a guard do not contribute to the allowed methods
+        assert_eq!(allowed(&set, Method::POST, "/users/1"), Some(vec![
+            Method::GET, Method::HEAD, Method::OPTIONS]));
+    }
+
+    #[test]
+    fn host_routes() {
+        let mut api = RouteSet::new();
+        api.insert(Route::new(0).method(Method::GET).path("/users"));
+
+        let mut tenants = RouteSet::new();
+        tenants.insert(Route::new(1).method(Method::GET).path("/users/:id"));
+
+        let mut set = api.host("api.example.com");
+        set.insert_all(tenants.host(":tenant.example.com"));
+        set.insert(Route::new(2).method(Method::GET).path("/*path"));
+        set.build_tree(TrailingSlash::Lenient);
+
+        let lookup = |host: &str, path: &str| {
+            let request = Request::builder()
+                .uri(path)
+                .header("host", host)
+                .body(())
+                .unwrap();
+
+            match set.test(&request) {
+                Lookup::Found(destination, captures) => {
+                    let captures: Vec<_> = (0..captures.len())
+                        .map(|i| captures.get(i, path).to_string())
+                        .collect();
+
+                    Some((destination, captures))
+                }
+                _ => None,
+            }
+        };
+
+        assert_eq!(lookup("api.example.com", "/users"), Some((0, vec![])));
+        assert_eq!(lookup("acme.example.com", "/users/1"), Some((1, vec!["1".into(), "acme".into()])));
+        assert_eq!(lookup("acme.example.com", "/users"), Some((2, vec!["users".into()])));
+        assert_eq!(lookup("example.org", "/users/1"), Some((2, vec!["users/1".into()])));
+    }
+
+    #[test]
+    fn mounted_captures() {
+        let mut set = RouteSet::new();
+        set.insert(Route::new(0).method(Method::GET).path("/users/:id"));
+
+        let mut set = set.mount("/tenants/:tenant");
+        set.build_tree(TrailingSlash::Lenient);
+
+        let path = "/tenants/acme/users/1";
+
+        match lookup(&set, Method::GET, path) {
+            Lookup::Found(0, captures) => {
+                assert_eq!(captures.get(0, path), "1");
+                assert_eq!(captures.get(1, path), "acme");
+                assert_eq!(captures.named_index("tenant"), Some(1));
+                assert_eq!(captures.named_index("id"), None);
+            }
+            actual => panic!("unexpected lookup result; {:?}", actual),
+        }
+
+        assert_eq!(test(&set, Method::GET, "/users/1"), None);
+    }
+
+    #[test]
+    fn method_not_allowed() {
+        let set = route_set(&[
+            (Method::POST, "/users"),
+            (Method::GET, "/users/:id"),
+            (Method::GET, "/users"),
+            (Method::DELETE, "/users/:id"),
+            (Method::PUT, "/users/me"),
+            (Method::POST, "/users"),
+        ]);
+
+        assert_eq!(allowed(&set, Method::GET, "/users"), None);
+        assert_eq!(allowed(&set, Method::PATCH, "/users"), Some(vec![
+            Method::POST, Method::GET, Method::HEAD, Method::OPTIONS]));
+        assert_eq!(allowed(&set, Method::PATCH, "/users/me"), Some(vec![
+            Method::GET, Method::HEAD, Method::DELETE, Method::PUT, Method::OPTIONS]));
+        assert_eq!(allowed(&set, Method::PATCH, "/nope"), None);
+
+        match lookup(&set, Method::PATCH, "/nope") {
+            Lookup::NotFound => {}
+            actual => panic!("unexpected lookup result; {:?}", actual),
+        }
+    }
+
+    #[test]
+    fn implicit_head() {
+        let set = route_set(&[
+            (Method::GET, "/:id"),
+            (Method::HEAD, "/foo"),
+            (Method::POST, "/bar/baz"),
+        ]);
+
+        assert_eq!(test(&set, Method::HEAD, "/foo"), Some((1, vec![])));
+        assert_eq!(test(&set, Method::HEAD, "/baz"), Some((0, vec!["baz".into()])));
+        assert_eq!(allowed(&set, Method::HEAD, "/bar/baz"), Some(vec![Method::POST, Method::OPTIONS]));
+    }
+
+    #[test]
+    fn implicit_options() {
+        let set = route_set(&[
+            (Method::GET, "/foo"),
+            (Method::POST, "/foo"),
+            (Method::OPTIONS, "/bar"),
+            (Method::GET, "/bar"),
+        ]);
+
+        match lookup(&set, Method::OPTIONS, "/foo") {
+            Lookup::Options(allowed) => {
+                assert_eq!(allowed, vec![Method::GET, Method::HEAD, Method::POST, Method::OPTIONS]);
+            }
+            actual => panic!("unexpected lookup result; {:?}", actual),
+        }
+
+        assert_eq!(test(&set, Method::OPTIONS, "/bar"), Some((2, vec![])));
+
+        match lookup(&set, Method::OPTIONS, "/baz") {
+            Lookup::NotFound => {}
+            actual => panic!("unexpected lookup result; {:?}", actual),
+        }
+    }
+
+    #[test]
+    fn consumes() {
+        let mut set = RouteSet::new();
+        set.insert(Route::new(0).method(Method::POST).path("/json").consumes("application/json"));
+        set.insert(Route::new(1).method(Method::POST).path("/text").consumes("text/*"));
+        set.insert(Route::new(2).method(Method::POST).path("/docs").consumes("application/json"));
+        set.insert(Route::new(3).method(Method::POST).path("/docs").consumes("text/csv"));
+        set.insert(Route::new(4).method(Method::POST).path("/docs/*rest").consumes("text/csv"));
+        set.insert(Route::new(5).method(Method::POST).path("/docs/*rest"));
+        set.build_tree(TrailingSlash::Lenient);
+
+        assert!(set.conflicts(TrailingSlash::Lenient).is_empty());
+
+        let lookup = |path: &str, content_type: Option<&str>| {
+            let mut request = Request::builder();
+            request.method(Method::POST).uri(path);
+
+            if let Some(content_type) = content_type {
+                request.header("content-type", content_type);
+            }
+
+            set.test(&request.body(()).unwrap())
+        };
+
+        for &content_type in &["application/json", "Application/JSON; charset=utf-8", "application/merge-patch+json"] {
+            match lookup("/json", Some(content_type)) {
+                Lookup::Found(0, _) => {}
+                actual => panic!("unexpected lookup result; {:?}", actual),
+            }
+        }
+
+        match lookup("/text", Some("text/csv")) {
+            Lookup::Found(1, _) => {}
+            actual => panic!("unexpected lookup result; {:?}", actual),
+        }
+
+        for &content_type in &[None, Some("text/json"), Some("application/jsonx")] {
+            match lookup("/json", content_type) {
+                Lookup::UnsupportedMediaType(media_types) => {
+                    assert_eq!(media_types, vec!["application/json".to_string()]);
+                }
+                actual => panic!("unexpected lookup result; {:?}", actual),
+            }
+        }
+
+        // Routes sharing a method and path are selected by media type
+        match lookup("/docs", Some("application/json")) {
+            Lookup::Found(2, _) => {}
+            actual => panic!("unexpected lookup result; {:?}", actual),
+        }
+
+        match lookup("/docs", Some("text/csv")) {
+            Lookup::Found(3, _) => {}
+            actual => panic!("unexpected lookup result; {:?}", actual),
+        }
+
+        match lookup("/docs", Some("text/plain")) {
+            Lookup::UnsupportedMediaType(media_types) => {
+                assert_eq!(media_types, vec!["application/json".to_string(), "text/csv".to_string()]);
+            }
+            actual => panic!("unexpected lookup result; {:?}", actual),
+        }
+
+        // Less specific routes are tried when the media type is not consumed
+        match lookup("/docs/a", Some("text/plain")) {
+            Lookup::Found(5, _) => {}
+            actual => panic!("unexpected lookup result; {:?}", actual),
+        }
     }
 }
@@ -0,0 +1,38 @@
+/// How request paths that differ from a route only by a trailing slash are
+/// handled.
+///
+/// Regardless of the policy, request paths are normalized before being
+/// matched: empty segments are removed, `.` and `..` segments are resolved,
+/// and percent-encoded unreserved characters are decoded. `/a//b/` and
+/// `/a/%62/` are both matched as `/a/b/`.
+///
+/// The policy is set with [`ServiceBuilder::trailing_slash`].
+///
+/// [`ServiceBuilder::trailing_slash`]: ../struct.ServiceBuilder.html#method.trailing_slash
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrailingSlash {
+    /// A trailing slash is significant. `/users` and `/users/` are distinct
+    /// paths, each matching only routes declared the same way.
+    Strict,
+
+    /// A trailing slash is ignored. `/users` and `/users/` both match routes
+    /// declared with or without a trailing slash.
+    ///
+    /// This is the default.
+    Lenient,
+
+    /// Paths are matched strictly, but requests for a non canonical path are
+    /// redirected to the canonical one with a `308 Permanent Redirect`
+    /// response.
+    ///
+    /// A path is not canonical when normalizing it changes it, or when no
+    /// route matches it but a route matches the same path with the trailing
+    /// slash added or removed.
+    Redirect,
+}
+
+impl Default for TrailingSlash {
+    fn default() -> TrailingSlash {
+        TrailingSlash::Lenient
+    }
+}
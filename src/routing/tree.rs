@@ -0,0 +1,423 @@
+use super::Route;
+use super::constraint::Constraint;
+use super::path::{Pattern, Segment};
+
+use http::{Method, Request};
+
+use std::collections::HashMap;
+
+/// Prefix tree indexing a set of routes by path segment.
+///
+/// Every node represents a single path segment. Literal segments are looked up
+/// by value, so the cost of matching a request depends on the depth of the
+/// request path and not on the number of routes in the set.
+///
+/// When several routes match a request, the most specific one is selected.
+/// Path segments are compared from left to right. At each position, a literal
+/// segment ranks above a segment mixing literal text and captures, which
+/// ranks above a constrained capture, then an unconstrained capture and
+/// finally a glob. Routes that are equally specific are selected in insertion
+/// order.
+#[derive(Debug)]
+pub(crate) struct Tree {
+    root: Node,
+
+    /// When `true`, trailing slashes are ignored.
+    lenient: bool,
+}
+
+#[derive(Debug)]
+struct Node {
+    /// Routes whose path terminates at this node, in insertion order.
+    routes: Vec<usize>,
+
+    /// Routes with a glob segment at this position, in insertion order.
+    globs: Vec<usize>,
+
+    /// Children reached by matching a literal segment.
+    literals: HashMap<String, Node>,
+
+    /// Children reached by matching a capture segment, one per distinct
+    /// constraint. Constrained captures are stored first.
+    captures: Vec<Capture>,
+
+    /// Children reached by matching a segment mixing literal text and
+    /// captures, one per distinct pattern.
+    patterns: Vec<(Pattern, Node)>,
+}
+
+#[derive(Debug)]
+struct Capture {
+    /// Constraint the segment must satisfy. `None` matches any segment.
+    constraint: Option<Constraint>,
+
+    node: Node,
+}
+
+/// Index of a matched route along with the position of each path capture.
+type Match = (usize, Vec<(usize, usize)>);
+
+/// Reason why no route matched a request.
+#[derive(Debug)]
+pub(crate) enum Rejection {
+    /// Routes matched the path, but not the method. Contains the methods
+    /// accepted by these routes, and is empty when no route matched the path.
+    Method(Vec<Method>),
+
+    /// Routes matched the method and path, but none of them consume the media
+    /// type of the request body. Contains the media types they consume.
+    MediaType(Vec<String>),
+}
+
+/// State tracked while searching the tree for a match.
+struct Search<'a, T> {
+    routes: &'a [Route<T>],
+    request: &'a Request<()>,
+    method: &'a Method,
+    path: &'a str,
+    segments: Vec<(usize, &'a str)>,
+    captures: Vec<(usize, usize)>,
+    best: Option<Match>,
+
+    /// Routes that matched the path but not the method.
+    rejected: Vec<usize>,
+
+    /// Routes that matched the method and path but not the request media
+    /// type.
+    unsupported: Vec<usize>,
+
+    /// Number of nodes visited so far.
+    visited: usize,
+}
+
+// ===== impl Tree =====
+
+impl Tree {
+    /// Build a tree indexing the given routes for which `include` returns
+    /// `true`.
+    ///
+    /// When `lenient` is set, trailing slashes are ignored, both in the route
+    /// paths and in the request paths.
+    pub fn new<T, F>(routes: &[Route<T>], lenient: bool, include: F) -> Tree
+    where
+        F: Fn(&Route<T>) -> bool,
+    {
+        let mut root = Node::new();
+
+        for (index, route) in routes.iter().enumerate() {
+            if !include(route) {
+                continue;
+            }
+
+            let segments = if lenient {
+                route.pattern().lenient_segments()
+            } else {
+                route.pattern().segments()
+            };
+
+            root.insert(index, segments);
+        }
+
+        Tree { root, lenient }
+    }
+
+    /// Find the most specific route matching the method and path, and whose
+    /// guards and consumed media types accept the request.
+    ///
+    /// On success, returns the index of the route along with the position of
+    /// each path capture. Otherwise, returns why no route matched.
+    pub fn test<T>(&self, routes: &[Route<T>], request: &Request<()>, method: &Method, path: &str)
+        -> Result<Match, Rejection>
+    {
+        let mut search = Search::new(routes, request, method, path, self.lenient);
+        search.visit(&self.root, 0);
+
+        match search.best {
+            Some(best) => Ok(best),
+            None if !search.unsupported.is_empty() => Err(Rejection::MediaType(search.consumed())),
+            None => Err(Rejection::Method(search.allowed())),
+        }
+    }
+}
+
+// ===== impl Node =====
+
+impl Node {
+    fn new() -> Node {
+        Node {
+            routes: vec![],
+            globs: vec![],
+            literals: HashMap::new(),
+            captures: vec![],
+            patterns: vec![],
+        }
+    }
+
+    fn insert(&mut self, index: usize, segments: &[Segment]) {
+        let (segment, rest) = match segments.split_first() {
+            Some(v) => v,
+            None => {
+                self.routes.push(index);
+                return;
+            }
+        };
+
+        match *segment {
+            Segment::Literal(ref value) => {
+                self.literals.entry(value.clone())
+                    .or_insert_with(Node::new)
+                    .insert(index, rest);
+            }
+            Segment::Capture(ref constraint) => {
+                let source = constraint.as_ref().map(Constraint::source);
+
+                let pos = self.captures.iter()
+                    .position(|capture| capture.constraint.as_ref().map(Constraint::source) == source);
+
+                let pos = pos.unwrap_or_else(|| {
+                    // Constrained captures are tried before unconstrained ones.
+                    let pos = match *constraint {
+                        Some(_) => self.captures.iter()
+                            .position(|capture| capture.constraint.is_none())
+                            .unwrap_or(self.captures.len()),
+                        None => self.captures.len(),
+                    };
+
+                    self.captures.insert(pos, Capture {
+                        constraint: constraint.clone(),
+                        node: Node::new(),
+                    });
+
+                    pos
+                });
+
+                self.captures[pos].node.insert(index, rest);
+            }
+            Segment::Pattern(ref pattern) => {
+                let pos = self.patterns.iter()
+                    .position(|(p, _)| p.source() == pattern.source());
+
+                let pos = pos.unwrap_or_else(|| {
+                    self.patterns.push((pattern.clone(), Node::new()));
+                    self.patterns.len() - 1
+                });
+
+                self.patterns[pos].1.insert(index, rest);
+            }
+            Segment::Glob => {
+                // Segments following a glob can never be matched.
+                if rest.is_empty() {
+                    self.globs.push(index);
+                }
+            }
+        }
+    }
+}
+
+// ===== impl Search =====
+
+impl<'a, T> Search<'a, T> {
+    fn new(
+        routes: &'a [Route<T>],
+        request: &'a Request<()>,
+        method: &'a Method,
+        mut path: &'a str,
+        lenient: bool,
+    ) -> Self {
+        if lenient && path.ends_with('/') {
+            path = &path[0..path.len() - 1];
+        }
+
+        let mut segments = vec![];
+        let mut offset = 0;
+
+        for segment in path.split("/") {
+            segments.push((offset, segment));
+            offset += segment.len() + 1;
+        }
+
+        Search {
+            routes,
+            request,
+            method,
+            path,
+            segments,
+            captures: vec![],
+            best: None,
+            rejected: vec![],
+            unsupported: vec![],
+            visited: 0,
+        }
+    }
+
+    /// Visit the children of `node` from the most to the least specific,
+    /// stopping at the first match.
+    fn visit(&mut self, node: &Node, depth: usize) {
+        self.visited += 1;
+
+        if depth == self.segments.len() {
+            self.candidates(&node.routes);
+            return;
+        }
+
+        let (offset, segment) = self.segments[depth];
+
+        if let Some(child) = node.literals.get(segment) {
+            self.visit(child, depth + 1);
+
+            if self.best.is_some() {
+                return;
+            }
+        }
+
+        for (pattern, child) in &node.patterns {
+            let start = self.captures.len();
+
+            if !pattern.test(segment, &mut self.captures) {
+                continue;
+            }
+
+            for capture in &mut self.captures[start..] {
+                capture.0 += offset;
+            }
+
+            self.visit(child, depth + 1);
+            self.captures.truncate(start);
+
+            if self.best.is_some() {
+                return;
+            }
+        }
+
+        for capture in &node.captures {
+            // Captures never match an empty segment.
+            if segment.is_empty() {
+                break;
+            }
+
+            if let Some(ref constraint) = capture.constraint {
+                // Segments that do not satisfy the constraint fall through to
+                // the remaining routes.
+                if !constraint.test(segment) {
+                    continue;
+                }
+            }
+
+            self.captures.push((offset, segment.len()));
+            self.visit(&capture.node, depth + 1);
+            self.captures.pop();
+
+            if self.best.is_some() {
+                return;
+            }
+        }
+
+        // Globs match at least one character.
+        if !node.globs.is_empty() && offset < self.path.len() {
+            self.captures.push((offset, self.path.len() - offset));
+            self.candidates(&node.globs);
+            self.captures.pop();
+        }
+    }
+
+    /// Select the first route in `indices` that matches the request method
+    /// and whose guards and consumed media types accept the request.
+    ///
+    /// Routes rejected by a guard are ignored. The other routes that are
+    /// skipped are tracked in order to be able to respond with "405 Method Not
+    /// Allowed" or "415 Unsupported Media Type".
+    fn candidates(&mut self, indices: &[usize]) {
+        for &index in indices {
+            let route = &self.routes[index];
+
+            if !route.test_guards(self.request) {
+                continue;
+            }
+
+            if route.http_method() != self.method {
+                self.rejected.push(index);
+                continue;
+            }
+
+            if !route.test_content_type(self.request) {
+                self.unsupported.push(index);
+                continue;
+            }
+
+            self.best = Some((index, self.captures.clone()));
+            return;
+        }
+    }
+
+    /// Methods accepted by the routes that matched the path, in route
+    /// insertion order.
+    fn allowed(&mut self) -> Vec<Method> {
+        let mut allowed: Vec<Method> = vec![];
+
+        self.rejected.sort();
+
+        for &index in &self.rejected {
+            let method = self.routes[index].http_method();
+
+            if !allowed.contains(method) {
+                allowed.push(method.clone());
+            }
+        }
+
+        allowed
+    }
+
+    /// Media types consumed by the routes that matched the method and path,
+    /// in route insertion order.
+    fn consumed(&mut self) -> Vec<String> {
+        let mut consumed: Vec<String> = vec![];
+
+        self.unsupported.sort();
+
+        for &index in &self.unsupported {
+            for media_type in self.routes[index].consumed() {
+                if !consumed.contains(media_type) {
+                    consumed.push(media_type.clone());
+                }
+            }
+        }
+
+        consumed
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Number of nodes visited to match `path` against `n` routes of the form
+    /// `/resource{i}/items/:id`.
+    fn visited(n: usize, path: &str) -> usize {
+        let routes: Vec<_> = (0..n)
+            .map(|i| {
+                Route::new(i)
+                    .method(Method::GET)
+                    .path(&format!("/resource{}/items/:id", i))
+            })
+            .collect();
+
+        let tree = Tree::new(&routes, false, |_| true);
+        let request = Request::builder().uri(path).body(()).unwrap();
+
+        let mut search = Search::new(&routes, &request, &Method::GET, path, false);
+        search.visit(&tree.root, 0);
+
+        assert!(search.best.is_some());
+        search.visited
+    }
+
+    #[test]
+    fn lookup_cost_does_not_depend_on_route_count() {
+        let expected = visited(1, "/resource0/items/1");
+
+        for &n in &[10, 100, 1_000, 10_000] {
+            assert_eq!(visited(n, &format!("/resource{}/items/1", n - 1)), expected);
+            assert_eq!(visited(n, "/resource0/items/1"), expected);
+        }
+    }
+}
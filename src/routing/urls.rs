@@ -0,0 +1,212 @@
+use super::{Path, RouteSet};
+
+use http::StatusCode;
+
+use std::collections::HashMap;
+use std::error;
+use std::fmt;
+use std::sync::Arc;
+
+/// Builds URLs for named routes.
+///
+/// Every route defined with `impl_web!` is named after its handler method,
+/// unless a name is given with `#[web(name = "...")]`. It can also be named
+/// after its handler method qualified by the resource type, as in
+/// `Users::show_user`.
+///
+/// Names given with `#[web(name = "...")]` take priority over handler method
+/// names and must be unique: building the service fails if two methods are
+/// given the same name. When methods of different resources share a name,
+/// such as `index`, that name is ambiguous and the qualified names must be
+/// used instead. When a method handles multiple routes, the one that was
+/// added to the service first is used.
+///
+/// `Urls` is stored in the service's [`Config`](../config/struct.Config.html),
+/// so it is available from extractors through `Context::config`. It can also
+/// be used directly as a resource method argument.
+///
+/// ```rust
+/// # #[macro_use] extern crate tower_web;
+/// use tower_web::routing::Urls;
+///
+/// struct Users;
+///
+/// impl_web! {
+///     impl Users {
+///         #[get("/users/:id")]
+///         fn show_user(&self, id: u32) -> Result<String, ()> {
+///             Ok(format!("user {}", id))
+///         }
+///
+///         #[post("/users")]
+///         fn create_user(&self, urls: Urls) -> Result<String, ()> {
+///             let url = urls.url("show_user", &[("id", "1")]).unwrap();
+///             Ok(url)
+///         }
+///     }
+/// }
+/// # fn main() {}
+/// ```
+#[derive(Debug, Clone)]
+pub struct Urls {
+    routes: Arc<HashMap<String, Entry>>,
+}
+
+#[derive(Debug)]
+enum Entry {
+    /// A route that was explicitly named, or named after its qualified
+    /// handler method.
+    Route(Path),
+
+    /// The first route named after a handler method, along with the qualified
+    /// names of all methods sharing that name.
+    Default(Path, Vec<String>),
+}
+
+/// Error returned when building a URL fails.
+#[derive(Debug)]
+pub struct UrlError {
+    kind: Kind,
+}
+
+#[derive(Debug)]
+enum Kind {
+    UnknownRoute(String),
+    AmbiguousRoute(String, Vec<String>),
+    MissingCapture(String),
+    InvalidCapture(String, String),
+}
+
+// ===== impl Urls =====
+
+impl Urls {
+    pub(crate) fn new<T>(routes: &RouteSet<T>) -> Urls {
+        let mut map = HashMap::new();
+
+        for route in routes.iter() {
+            let names = route.explicit_name()
+                .map(str::to_string)
+                .into_iter()
+                .chain(route.qualified_name());
+
+            for name in names {
+                map.entry(name)
+                    .or_insert_with(|| Entry::Route(route.pattern().clone()));
+            }
+        }
+
+        // Routes named after their handler method
+        for route in routes.iter() {
+            if route.explicit_name().is_some() {
+                continue;
+            }
+
+            let (name, qualified) = match (route.route_name(), route.qualified_name()) {
+                (Some(name), Some(qualified)) => (name, qualified),
+                _ => continue,
+            };
+
+            let entry = map.entry(name.to_string())
+                .or_insert_with(|| Entry::Default(route.pattern().clone(), vec![]));
+
+            if let Entry::Default(_, ref mut handlers) = *entry {
+                if !handlers.contains(&qualified) {
+                    handlers.push(qualified);
+                }
+            }
+        }
+
+        Urls { routes: Arc::new(map) }
+    }
+
+    /// Build the path of the route named `name`.
+    ///
+    /// `captures` provides a value for each capture in the route path, by
+    /// capture name. Values are percent-encoded. An error is returned if no
+    /// route is named `name`, if the name is ambiguous, if a capture has no
+    /// value or if a value does not satisfy the capture's constraint.
+    pub fn url(&self, name: &str, captures: &[(&str, &str)]) -> Result<String, UrlError> {
+        let path = match self.routes.get(name) {
+            Some(Entry::Route(path)) => path,
+            Some(Entry::Default(path, handlers)) if handlers.len() == 1 => path,
+            Some(Entry::Default(_, handlers)) => {
+                let kind = Kind::AmbiguousRoute(name.to_string(), handlers.clone());
+                return Err(UrlError { kind });
+            }
+            None => return Err(UrlError { kind: Kind::UnknownRoute(name.to_string()) }),
+        };
+
+        path.url(captures)
+    }
+}
+
+// ===== impl UrlError =====
+
+impl UrlError {
+    pub(crate) fn missing_capture(name: &str) -> UrlError {
+        UrlError { kind: Kind::MissingCapture(name.to_string()) }
+    }
+
+    pub(crate) fn invalid_capture(name: &str, value: &str) -> UrlError {
+        UrlError { kind: Kind::InvalidCapture(name.to_string(), value.to_string()) }
+    }
+
+    /// Returns `true` if no route has the requested name.
+    pub fn is_unknown_route(&self) -> bool {
+        match self.kind {
+            Kind::UnknownRoute(..) => true,
+            _ => false,
+        }
+    }
+
+    /// Returns `true` if methods of different resources are named after the
+    /// requested name.
+    pub fn is_ambiguous_route(&self) -> bool {
+        match self.kind {
+            Kind::AmbiguousRoute(..) => true,
+            _ => false,
+        }
+    }
+
+    /// Returns `true` if no value was provided for a capture.
+    pub fn is_missing_capture(&self) -> bool {
+        match self.kind {
+            Kind::MissingCapture(..) => true,
+            _ => false,
+        }
+    }
+
+    /// Returns `true` if a capture value does not satisfy its constraint.
+    pub fn is_invalid_capture(&self) -> bool {
+        match self.kind {
+            Kind::InvalidCapture(..) => true,
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for UrlError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            Kind::UnknownRoute(ref name) => write!(fmt, "no route named `{}`", name),
+            Kind::AmbiguousRoute(ref name, ref handlers) => {
+                write!(fmt, "route name `{}` is ambiguous; use one of `{}`",
+                       name, handlers.join("`, `"))
+            }
+            Kind::MissingCapture(ref name) => write!(fmt, "missing value for capture `{}`", name),
+            Kind::InvalidCapture(ref name, ref value) => {
+                write!(fmt, "invalid value for capture `{}`: {:?}", name, value)
+            }
+        }
+    }
+}
+
+impl error::Error for UrlError {}
+
+impl From<UrlError> for crate::Error {
+    fn from(err: UrlError) -> Self {
+        let mut error = crate::Error::from(StatusCode::INTERNAL_SERVER_ERROR);
+        error.set_detail(&err.to_string());
+        error
+    }
+}
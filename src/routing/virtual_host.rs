@@ -0,0 +1,57 @@
+use crate::response::Serializer;
+use crate::routing::{IntoResource, RouteSet};
+use crate::util::{BufStream, Chain};
+
+/// A resource only served to requests targeting a given host.
+///
+/// The host is matched before the path, so the routes of the inner resource
+/// are only considered when the request host matches the pattern. Routes that
+/// are not bound to a host are considered for every host.
+///
+/// The pattern is a host name such as `api.example.com`. A leading `*` label
+/// matches any subdomain, as in `*.example.com`. Labels starting with `:`
+/// capture a label of the host, as in `:tenant.example.com`. Captured labels
+/// are available to the inner resource's handlers by argument name.
+///
+/// Created by `ServiceBuilder::host`.
+#[derive(Debug)]
+pub struct VirtualHost<T> {
+    host: String,
+    inner: T,
+}
+
+impl<T> VirtualHost<T> {
+    /// Serve `inner` to requests targeting hosts matching `host`.
+    pub fn new(host: &str, inner: T) -> VirtualHost<T> {
+        VirtualHost {
+            host: host.to_string(),
+            inner,
+        }
+    }
+}
+
+impl<T, S, B> IntoResource<S, B> for VirtualHost<T>
+where
+    T: IntoResource<S, B>,
+    S: Serializer,
+    B: BufStream,
+{
+    type Destination = T::Destination;
+    type Resource = T::Resource;
+
+    fn routes(&self) -> RouteSet<Self::Destination> {
+        self.inner.routes().host(&self.host)
+    }
+
+    fn into_resource(self, serializer: S) -> Self::Resource {
+        self.inner.into_resource(serializer)
+    }
+}
+
+impl<T, U> Chain<U> for VirtualHost<T> {
+    type Output = (Self, U);
+
+    fn chain(self, other: U) -> Self::Output {
+        (self, other)
+    }
+}
@@ -63,6 +63,10 @@ impl BufStream for LiftReqBody {
     fn poll(&mut self) -> Poll<Option<Self::Item>, crate::Error> {
         Stream::poll(&mut self.body).map_err(|_| crate::Error::from(StatusCode::INTERNAL_SERVER_ERROR))
     }
+
+    fn size_hint(&self) -> SizeHint {
+        BufStream::size_hint(&self.body)
+    }
 }
 
 impl<T> HyperService for Lift<T>
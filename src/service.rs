@@ -7,10 +7,12 @@
 //! [`ServiceBuilder`]: struct.ServiceBuilder.html
 
 mod builder;
+mod error;
 mod new_service;
 // TODO: Rename this `service`?
 mod web;
 
 pub use self::builder::ServiceBuilder;
+pub use self::error::BuildError;
 pub use self::new_service::NewWebService;
 pub use self::web::WebService;
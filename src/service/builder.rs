@@ -1,14 +1,17 @@
 use crate::config::ConfigBuilder;
 use crate::error::{IntoCatch, DefaultCatch};
+use crate::extract::BodyLimit;
 use futures::Future;
 use crate::middleware::Identity;
 use crate::net::ConnectionStream;
 use crate::response::{DefaultSerializer, Serializer};
-use crate::routing::{Resource, IntoResource, RoutedService};
-use crate::service::NewWebService;
+use crate::routing::{Handler, HandlerRoute, Resource, IntoResource, Mount, MountService, RoutedService, TrailingSlash, Urls, VirtualHost};
+use crate::service::{BuildError, NewWebService};
 use crate::util::{BufStream, Chain};
 use crate::util::http::{HttpService, HttpMiddleware};
 
+use http::Method;
+
 use std::io;
 use std::net::SocketAddr;
 
@@ -107,6 +110,21 @@ use std::net::SocketAddr;
 /// # }
 /// # }
 /// ```
+///
+/// # Conflicting routes
+///
+/// Building the service fails when two routes, possibly defined by different
+/// resources, match exactly the same requests, as the route added last could
+/// never be reached. Previous releases silently routed such requests to the
+/// route added first. The same goes for resource methods given the same
+/// name with `#[web(name = "...")]`. [`build_new_service`] and [`run`] panic
+/// in these cases, while [`try_build_new_service`] returns the offending
+/// routes as a [`BuildError`].
+///
+/// [`build_new_service`]: #method.build_new_service
+/// [`run`]: #method.run
+/// [`try_build_new_service`]: #method.try_build_new_service
+/// [`BuildError`]: service/struct.BuildError.html
 #[derive(Debug)]
 pub struct ServiceBuilder<T, Serializer, Catch, Middleware> {
     /// The inner resource
@@ -115,6 +133,7 @@ pub struct ServiceBuilder<T, Serializer, Catch, Middleware> {
     catch: Catch,
     middleware: Middleware,
     config: ConfigBuilder,
+    trailing_slash: TrailingSlash,
 }
 
 impl ServiceBuilder<(), DefaultSerializer, DefaultCatch, Identity> {
@@ -152,6 +171,7 @@ impl ServiceBuilder<(), DefaultSerializer, DefaultCatch, Identity> {
             catch: DefaultCatch::new(),
             middleware: Identity::new(),
             config: ConfigBuilder::new(),
+            trailing_slash: TrailingSlash::default(),
         }
     }
 }
@@ -159,9 +179,16 @@ impl ServiceBuilder<(), DefaultSerializer, DefaultCatch, Identity> {
 impl<T, S, C, M> ServiceBuilder<T, S, C, M> {
     /// Add a resource to the service.
     ///
-    /// Resources are prioritized based on the order they are added to the
-    /// `ServiceBuilder`. If two resources handle the same route, then the one
-    /// that was added first gets priority.
+    /// When the routes of several resources match a request, the most specific
+    /// route handles it, regardless of the order the resources are added in:
+    /// literal path segments rank above captures, which rank above globs. If
+    /// two resources handle exactly the same route, building the service
+    /// panics.
+    ///
+    /// A `Vec` of resources is also a resource. Resources chosen at runtime
+    /// are collected by erasing their types with [`BoxResource`].
+    ///
+    /// [`BoxResource`]: routing/struct.BoxResource.html
     ///
     /// # Examples
     ///
@@ -197,9 +224,217 @@ impl<T, S, C, M> ServiceBuilder<T, S, C, M> {
             catch: self.catch,
             middleware: self.middleware,
             config: self.config,
+            trailing_slash: self.trailing_slash,
         }
     }
 
+    /// Add a resource to the service, mounted under a path prefix.
+    ///
+    /// Every route of the resource is prefixed with `prefix`. This allows
+    /// reusing a resource under multiple paths, for example to serve several
+    /// API versions. Captures in the prefix are available to the resource's
+    /// methods by argument name, just like the captures in their own paths.
+    ///
+    /// Routes are prioritized the same way as with `resource`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[macro_use] extern crate tower_web;
+    /// use tower_web::ServiceBuilder;
+    ///
+    /// struct Users;
+    ///
+    /// impl_web! {
+    ///     impl Users {
+    ///         #[get("/users/:id")]
+    ///         fn user(&self, tenant: String, id: u32) -> Result<String, ()> {
+    ///             Ok(format!("{}: {}", tenant, id))
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// # fn main() {
+    /// # if false {
+    /// # let addr = "127.0.0.1:8080".parse().unwrap();
+    /// ServiceBuilder::new()
+    ///     .mount("/tenants/:tenant", Users)
+    ///     .run(&addr);
+    /// # }
+    /// # }
+    /// ```
+    pub fn mount<U>(self, prefix: &str, resource: U)
+        -> ServiceBuilder<<T as Chain<Mount<U>>>::Output, S, C, M>
+    where
+        T: Chain<Mount<U>>,
+    {
+        self.resource(Mount::new(prefix, resource))
+    }
+
+    /// Add an HTTP service to the service, mounted under a path prefix.
+    ///
+    /// Requests whose path starts with `prefix` are forwarded to `service`,
+    /// which may be any `tower_service::Service` of `http::Request` to
+    /// `http::Response`. The prefix is removed from the request URI and the
+    /// request body is forwarded unchanged. Errors returned by the service are
+    /// handled as internal server errors.
+    ///
+    /// Routes of other resources that are more specific than `prefix` take
+    /// precedence over the mounted service.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # extern crate futures;
+    /// # extern crate http;
+    /// # extern crate tower_service;
+    /// # extern crate tower_web;
+    /// use futures::future::{self, FutureResult};
+    /// use futures::Poll;
+    /// use tower_service::Service;
+    /// use tower_web::ServiceBuilder;
+    ///
+    /// use std::marker::PhantomData;
+    ///
+    /// // The service is generic over the request body type
+    /// struct Legacy<B>(PhantomData<fn(B)>);
+    ///
+    /// impl<B> Clone for Legacy<B> {
+    ///     fn clone(&self) -> Self {
+    ///         Legacy(PhantomData)
+    ///     }
+    /// }
+    ///
+    /// impl<B> Service for Legacy<B> {
+    ///     type Request = http::Request<B>;
+    ///     type Response = http::Response<&'static str>;
+    ///     type Error = ();
+    ///     type Future = FutureResult<Self::Response, ()>;
+    ///
+    ///     fn poll_ready(&mut self) -> Poll<(), ()> {
+    ///         Ok(().into())
+    ///     }
+    ///
+    ///     fn call(&mut self, _: Self::Request) -> Self::Future {
+    ///         future::ok(http::Response::new("legacy"))
+    ///     }
+    /// }
+    ///
+    /// # fn main() {
+    /// # if false {
+    /// # let addr = "127.0.0.1:8080".parse().unwrap();
+    /// ServiceBuilder::new()
+    ///     .mount_service("/legacy", Legacy(PhantomData))
+    ///     .run(&addr);
+    /// # }
+    /// # }
+    /// ```
+    pub fn mount_service<U>(self, prefix: &str, service: U)
+        -> ServiceBuilder<<T as Chain<MountService<U>>>::Output, S, C, M>
+    where
+        T: Chain<MountService<U>>,
+    {
+        self.resource(MountService::new(prefix, service))
+    }
+
+    /// Add a resource to the service, only served to requests targeting
+    /// `host`.
+    ///
+    /// The request host is matched before the path. `host` is either a host
+    /// name, such as `api.example.com`, or a pattern. A leading `*` label
+    /// matches any subdomain, as in `*.example.com`, and labels starting with
+    /// `:` capture a label of the host, as in `:tenant.example.com`. Captured
+    /// labels are available to the resource's methods by argument name.
+    ///
+    /// When several host patterns match the request, the most specific one is
+    /// tried first: host names rank above patterns with captures, which rank
+    /// above patterns starting with `*`. A less specific pattern is only tried
+    /// when no route bound to the previous one matches the request path.
+    /// Resources added with `resource` are served for every host.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[macro_use] extern crate tower_web;
+    /// use tower_web::ServiceBuilder;
+    ///
+    /// struct Api;
+    /// struct Tenants;
+    ///
+    /// impl_web! {
+    ///     impl Api {
+    ///         // ...
+    ///     }
+    ///
+    ///     impl Tenants {
+    ///         #[get("/")]
+    ///         fn index(&self, tenant: String) -> Result<String, ()> {
+    ///             Ok(format!("welcome to {}", tenant))
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// # fn main() {
+    /// # if false {
+    /// # let addr = "127.0.0.1:8080".parse().unwrap();
+    /// ServiceBuilder::new()
+    ///     .host("api.example.com", Api)
+    ///     .host(":tenant.example.com", Tenants)
+    ///     .run(&addr);
+    /// # }
+    /// # }
+    /// ```
+    pub fn host<U>(self, host: &str, resource: U)
+        -> ServiceBuilder<<T as Chain<VirtualHost<U>>>::Output, S, C, M>
+    where
+        T: Chain<VirtualHost<U>>,
+    {
+        self.resource(VirtualHost::new(host, resource))
+    }
+
+    /// Add a route handled by a function.
+    ///
+    /// This allows defining routes at runtime, for example from a
+    /// configuration file, without using `impl_web!`. The handler takes up to
+    /// eight arguments implementing `Extract` and returns a value implementing
+    /// `Response`, which goes through the service's serializers, catch and
+    /// middleware like the responses of any other resource.
+    ///
+    /// The first arguments of the handler are extracted from the path
+    /// captures, in the order the captures appear in `path`. The remaining
+    /// argument, if any, is extracted from the request body.
+    ///
+    /// Routes are prioritized the same way as with `resource`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # extern crate http;
+    /// # extern crate tower_web;
+    /// use http::Method;
+    /// use tower_web::ServiceBuilder;
+    ///
+    /// # fn main() {
+    /// # if false {
+    /// # let addr = "127.0.0.1:8080".parse().unwrap();
+    /// ServiceBuilder::new()
+    ///     .route(Method::GET, "/hello/:name", |name: String| {
+    ///         format!("hello {}", name)
+    ///     })
+    ///     .route(Method::POST, "/echo", |body: String| body)
+    ///     .run(&addr);
+    /// # }
+    /// # }
+    /// ```
+    pub fn route<F, Args>(self, method: Method, path: &str, handler: F)
+        -> ServiceBuilder<<T as Chain<HandlerRoute<F, Args>>>::Output, S, C, M>
+    where
+        T: Chain<HandlerRoute<F, Args>>,
+        F: Handler<Args>,
+    {
+        self.resource(HandlerRoute::new(method, path, handler))
+    }
+
     /// Add a serializer to the service.
     ///
     /// Serializers convert response structs to bytes. Each given serializer
@@ -261,6 +496,7 @@ impl<T, S, C, M> ServiceBuilder<T, S, C, M> {
             catch: self.catch,
             middleware: self.middleware,
             config: self.config,
+            trailing_slash: self.trailing_slash,
         }
     }
 
@@ -324,6 +560,7 @@ impl<T, S, C, M> ServiceBuilder<T, S, C, M> {
             catch: self.catch,
             middleware: self.middleware,
             config: self.config.insert(config),
+            trailing_slash: self.trailing_slash,
         }
     }
 
@@ -371,6 +608,7 @@ impl<T, S, C, M> ServiceBuilder<T, S, C, M> {
             catch: self.catch,
             middleware: self.middleware.chain(middleware),
             config: self.config,
+            trailing_slash: self.trailing_slash,
         }
     }
 
@@ -423,9 +661,114 @@ impl<T, S, C, M> ServiceBuilder<T, S, C, M> {
             catch,
             middleware: self.middleware,
             config: self.config,
+            trailing_slash: self.trailing_slash,
         }
     }
 
+    /// Set how trailing slashes in request paths are handled.
+    ///
+    /// Request paths are always normalized before being matched: empty
+    /// segments are removed, `.` and `..` segments are resolved, and
+    /// percent-encoded unreserved characters are decoded. The policy then
+    /// decides whether `/users` and `/users/` are the same path. See
+    /// [`TrailingSlash`] for the available policies.
+    ///
+    /// The default is `TrailingSlash::Lenient`.
+    ///
+    /// [`TrailingSlash`]: routing/enum.TrailingSlash.html
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[macro_use] extern crate tower_web;
+    /// use tower_web::ServiceBuilder;
+    /// use tower_web::routing::TrailingSlash;
+    ///
+    /// struct MyResource;
+    ///
+    /// impl_web! {
+    ///     impl MyResource {
+    ///         // ...
+    ///     }
+    /// }
+    ///
+    /// # fn main() {
+    /// # if false {
+    /// # let addr = "127.0.0.1:8080".parse().unwrap();
+    /// ServiceBuilder::new()
+    ///     .resource(MyResource)
+    ///     .trailing_slash(TrailingSlash::Redirect)
+    ///     .run(&addr);
+    /// # }
+    /// # }
+    /// ```
+    pub fn trailing_slash(self, trailing_slash: TrailingSlash) -> Self {
+        ServiceBuilder {
+            resource: self.resource,
+            serializer: self.serializer,
+            catch: self.catch,
+            middleware: self.middleware,
+            config: self.config,
+            trailing_slash,
+        }
+    }
+
+    /// Limit the size of request bodies to `limit` bytes.
+    ///
+    /// The limit applies to every request body that is buffered before being
+    /// passed to a resource method, for example when deserializing a JSON
+    /// body. Requests that announce a larger body with `Content-Length` are
+    /// rejected before reading any data, other requests once the limit is
+    /// reached. Either way, the request is rejected with `413 Payload Too
+    /// Large`, which is passed to the catch handler.
+    ///
+    /// Resource methods can override the limit with `#[web(body_limit =
+    /// "..")]`. Bodies streamed to the resource method with [`BodyStream`]
+    /// or [`Multipart`] are limited as well, but the resource method sees the
+    /// `413 Payload Too Large` error while reading them.
+    ///
+    /// By default, request bodies are not limited.
+    ///
+    /// [`BodyStream`]: extract/struct.BodyStream.html
+    /// [`Multipart`]: extract/multipart/struct.Multipart.html
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[macro_use] extern crate tower_web;
+    /// use tower_web::ServiceBuilder;
+    ///
+    /// struct MyResource;
+    ///
+    /// impl_web! {
+    ///     impl MyResource {
+    ///         #[post("/small")]
+    ///         fn small(&self, body: Vec<u8>) -> Result<String, ()> {
+    ///             Ok(format!("{} bytes", body.len()))
+    ///         }
+    ///
+    ///         #[post("/large")]
+    ///         #[web(body_limit = "10MB")]
+    ///         fn large(&self, body: Vec<u8>) -> Result<String, ()> {
+    ///             Ok(format!("{} bytes", body.len()))
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// # fn main() {
+    /// # if false {
+    /// # let addr = "127.0.0.1:8080".parse().unwrap();
+    /// ServiceBuilder::new()
+    ///     .resource(MyResource)
+    ///     .body_limit(64 * 1024)
+    ///     .run(&addr);
+    /// # }
+    /// # }
+    /// ```
+    pub fn body_limit(self, limit: u64) -> Self {
+        self.config(BodyLimit(limit))
+    }
+
     /// Build a `NewWebService` instance
     ///
     /// The returned value impements `tower_service::NewService` and is used to
@@ -433,6 +776,20 @@ impl<T, S, C, M> ServiceBuilder<T, S, C, M> {
     /// instance is used to generate one service per TCP connection established
     /// to the server.
     ///
+    /// # Panics
+    ///
+    /// Panics if two routes, possibly defined by different resources, match
+    /// exactly the same requests. Such routes have the same method, host,
+    /// guards and content type, and paths that only differ by capture names.
+    /// The route added last could never be reached.
+    ///
+    /// Also panics if two resource methods are given the same name with
+    /// `#[web(name = "...")]`, as that name could not be used to build URLs.
+    ///
+    /// Use [`try_build_new_service`] to get these routes as an error instead.
+    ///
+    /// [`try_build_new_service`]: #method.try_build_new_service
+    ///
     /// # Examples
     ///
     /// ```rust
@@ -473,6 +830,66 @@ impl<T, S, C, M> ServiceBuilder<T, S, C, M> {
     /// # }
     /// ```
     pub fn build_new_service<RequestBody>(self) -> NewWebService<T::Resource, C::Catch, M>
+    where T: IntoResource<S, RequestBody>,
+          S: Serializer,
+          C: IntoCatch<S>,
+          M: HttpMiddleware<RoutedService<T::Resource, C::Catch>>,
+          RequestBody: BufStream,
+    {
+        match self.try_build_new_service() {
+            Ok(new_service) => new_service,
+            Err(err) => panic!("{}", err),
+        }
+    }
+
+    /// Build a `NewService` instance, reporting routes that can never be
+    /// reached and duplicate route names.
+    ///
+    /// This behaves like [`build_new_service`] but returns an error instead of
+    /// panicking when two routes match exactly the same requests, or when two
+    /// resource methods are given the same name.
+    ///
+    /// [`build_new_service`]: #method.build_new_service
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[macro_use] extern crate tower_web;
+    /// use tower_web::ServiceBuilder;
+    ///
+    /// #[derive(Clone, Debug)]
+    /// struct Users;
+    ///
+    /// #[derive(Clone, Debug)]
+    /// struct Accounts;
+    ///
+    /// impl_web! {
+    ///     impl Users {
+    ///         #[get("/users/:id")]
+    ///         fn user(&self, id: u32) -> Result<String, ()> {
+    ///             Ok(format!("user {}", id))
+    ///         }
+    ///     }
+    ///
+    ///     impl Accounts {
+    ///         #[get("/users/:account_id")]
+    ///         fn account(&self, account_id: u32) -> Result<String, ()> {
+    ///             Ok(format!("account {}", account_id))
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// # fn main() {
+    /// let err = ServiceBuilder::new()
+    ///     .resource(Users)
+    ///     .resource(Accounts)
+    ///     .try_build_new_service::<String>()
+    ///     .err().unwrap();
+    ///
+    /// assert_eq!(err.conflicts().len(), 1);
+    /// # }
+    /// ```
+    pub fn try_build_new_service<RequestBody>(self) -> Result<NewWebService<T::Resource, C::Catch, M>, BuildError>
     where T: IntoResource<S, RequestBody>,
           S: Serializer,
           C: IntoCatch<S>,
@@ -483,16 +900,37 @@ impl<T, S, C, M> ServiceBuilder<T, S, C, M> {
         let routes = self.resource.routes();
         let serializer = self.serializer;
 
+        // Report routes that can never be reached, and route names that
+        // cannot be used to build URLs
+        let mut error = BuildError::new();
+
+        for (first, second) in routes.conflicts(self.trailing_slash) {
+            error.conflict(first.to_string(), second.to_string());
+        }
+
+        for (first, second) in routes.duplicate_names() {
+            let name = first.name().unwrap_or("").to_string();
+            error.duplicate_name(name, first.to_string(), second.to_string());
+        }
+
+        if !error.is_empty() {
+            return Err(error);
+        }
+
+        // Named routes are available to resources through the config
+        let config = self.config.insert(Urls::new(&routes));
+
         // Create the routed service
         let routed = RoutedService::new(
             self.resource.into_resource(serializer),
             self.catch.into_catch(),
-            self.config.into_config(),
-            routes);
+            config.into_config(),
+            routes,
+            self.trailing_slash);
 
-        NewWebService::new(
+        Ok(NewWebService::new(
             routed,
-            self.middleware)
+            self.middleware))
     }
 
     /// Run the service
@@ -0,0 +1,80 @@
+use std::error;
+use std::fmt;
+
+/// Error returned when `ServiceBuilder` cannot build a service.
+///
+/// See [`ServiceBuilder::try_build_new_service`].
+///
+/// [`ServiceBuilder::try_build_new_service`]: struct.ServiceBuilder.html#method.try_build_new_service
+#[derive(Debug)]
+pub struct BuildError {
+    conflicts: Vec<(String, String)>,
+    duplicate_names: Vec<(String, String, String)>,
+}
+
+// ===== impl BuildError =====
+
+impl BuildError {
+    pub(crate) fn new() -> BuildError {
+        BuildError {
+            conflicts: vec![],
+            duplicate_names: vec![],
+        }
+    }
+
+    pub(crate) fn conflict(&mut self, first: String, second: String) {
+        self.conflicts.push((first, second));
+    }
+
+    pub(crate) fn duplicate_name(&mut self, name: String, first: String, second: String) {
+        self.duplicate_names.push((name, first, second));
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.conflicts.is_empty() && self.duplicate_names.is_empty()
+    }
+
+    /// Returns the pairs of routes that match exactly the same requests.
+    ///
+    /// The first route of each pair takes priority. The second one can never
+    /// be reached.
+    pub fn conflicts(&self) -> &[(String, String)] {
+        &self.conflicts
+    }
+
+    /// Returns the route names given to more than one resource method with
+    /// `#[web(name = "...")]`.
+    pub fn duplicate_names(&self) -> Vec<&str> {
+        self.duplicate_names.iter()
+            .map(|(name, _, _)| &name[..])
+            .collect()
+    }
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.conflicts.is_empty() {
+            write!(fmt, "conflicting routes:")?;
+
+            for (first, second) in &self.conflicts {
+                write!(fmt, "\n  `{}` conflicts with `{}`", second, first)?;
+            }
+        }
+
+        if !self.duplicate_names.is_empty() {
+            if !self.conflicts.is_empty() {
+                writeln!(fmt)?;
+            }
+
+            write!(fmt, "duplicate route names:")?;
+
+            for (name, first, second) in &self.duplicate_names {
+                write!(fmt, "\n  `{}` names both `{}` and `{}`", name, first, second)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl error::Error for BuildError {}
@@ -1,5 +1,5 @@
 use crate::error::Catch;
-use crate::routing::{Resource, RoutedService};
+use crate::routing::{Resource, RouteInfo, RoutedService};
 use crate::service::WebService;
 use crate::util::Never;
 use crate::util::http::{HttpMiddleware};
@@ -38,6 +38,43 @@ where
             middleware,
         }
     }
+
+    /// Describe the routes served by the service, in the order they were
+    /// added.
+    ///
+    /// This is not the order in which requests are matched against them:
+    /// more specific paths are preferred regardless of declaration order.
+    ///
+    /// ```rust
+    /// # #[macro_use] extern crate tower_web;
+    /// use tower_web::ServiceBuilder;
+    ///
+    /// struct Users;
+    ///
+    /// impl_web! {
+    ///     impl Users {
+    ///         #[get("/users/:id")]
+    ///         #[content_type("json")]
+    ///         fn show_user(&self, id: u32) -> Result<String, ()> {
+    ///             // implementation
+    /// #           unimplemented!()
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// # fn main() {
+    /// let service = ServiceBuilder::new()
+    ///     .mount("/api", Users)
+    ///     .build_new_service::<String>();
+    ///
+    /// for route in service.routes() {
+    ///     assert_eq!(route.to_string(), "GET /api/users/:id -> Users::show_user (json)");
+    /// }
+    /// # }
+    /// ```
+    pub fn routes(&self) -> Vec<RouteInfo<'_>> {
+        self.service.routes()
+    }
 }
 
 impl<T, U, M> NewService for NewWebService<T, U, M>
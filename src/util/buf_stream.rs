@@ -19,6 +19,8 @@ mod either;
 mod empty;
 mod file;
 mod from;
+mod into_bytes;
+pub mod limit;
 pub mod size_hint;
 mod std;
 mod str;
@@ -28,5 +30,7 @@ pub use self::chain::Chain;
 pub use self::collect::Collect;
 pub use self::empty::{empty, Empty};
 pub use self::from::FromBufStream;
+pub use self::into_bytes::IntoBytes;
+pub use self::limit::Limit;
 pub use self::size_hint::SizeHint;
 pub use self::std::StdStream;
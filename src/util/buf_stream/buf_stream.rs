@@ -1,4 +1,4 @@
-use super::{Chain, Collect, FromBufStream, SizeHint};
+use super::{Chain, Collect, FromBufStream, Limit, SizeHint};
 
 use bytes::Buf;
 use futures::{Async, Poll};
@@ -74,6 +74,18 @@ pub trait BufStream {
         Chain::new(self, other)
     }
 
+    /// Limits the amount of data yielded by `self` to `max` bytes.
+    ///
+    /// The returned buf stream fails once `self` yields more than `max` bytes,
+    /// or before yielding any data if `size_hint()` announces more than `max`
+    /// bytes.
+    fn limit(self, max: u64) -> Limit<Self>
+    where
+        Self: Sized,
+    {
+        Limit::new(self, max)
+    }
+
     /// Consumes all data from `self`, storing it in byte storage of type `T`.
     fn collect<T>(self) -> Collect<Self, T>
     where
@@ -1,4 +1,5 @@
 use super::BufStream;
+use super::size_hint::{Builder, SizeHint};
 
 use bytes::Bytes;
 use futures::Poll;
@@ -21,4 +22,10 @@ impl BufStream for Bytes {
 
         Ok(Some(buf).into())
     }
+
+    fn size_hint(&self) -> SizeHint {
+        let mut builder = Builder::new();
+        builder.available(self.len()).upper(self.len());
+        builder.build()
+    }
 }
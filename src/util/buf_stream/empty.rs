@@ -1,7 +1,7 @@
 use bytes::Buf;
 use futures::{Async, Poll};
 use std::marker::PhantomData;
-use crate::util::buf_stream::BufStream;
+use crate::util::buf_stream::{size_hint, BufStream, SizeHint};
 
 /// A `BufStream` that contains no data.
 #[derive(Debug, Copy, Clone)]
@@ -35,4 +35,8 @@ where
     fn poll(&mut self) -> Poll<Option<Self::Item>, Self::Error> {
         Ok(Async::Ready(None))
     }
+
+    fn size_hint(&self) -> SizeHint {
+        size_hint::Builder::new().upper(0).build()
+    }
 }
@@ -0,0 +1,38 @@
+use bytes::{Buf, Bytes};
+use hyper::Chunk;
+
+use std::io::Cursor;
+
+/// Convert a buffer into `Bytes`, without copying the data when the buffer
+/// already is backed by a `Bytes` or a `Vec<u8>`.
+pub trait IntoBytes: Buf {
+    /// Convert the remaining data of the buffer into `Bytes`.
+    fn into_bytes(self) -> Bytes;
+}
+
+impl IntoBytes for Chunk {
+    fn into_bytes(self) -> Bytes {
+        self.into()
+    }
+}
+
+impl IntoBytes for Cursor<Bytes> {
+    fn into_bytes(self) -> Bytes {
+        let pos = self.position() as usize;
+        self.into_inner().slice_from(pos)
+    }
+}
+
+impl IntoBytes for Cursor<Vec<u8>> {
+    fn into_bytes(self) -> Bytes {
+        let pos = self.position() as usize;
+        Bytes::from(self.into_inner()).slice_from(pos)
+    }
+}
+
+impl IntoBytes for Cursor<&'static [u8]> {
+    fn into_bytes(self) -> Bytes {
+        let pos = self.position() as usize;
+        Bytes::from_static(&self.into_inner()[pos..])
+    }
+}
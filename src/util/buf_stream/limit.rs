@@ -0,0 +1,157 @@
+//! Limit the amount of data yielded by a buf stream.
+
+use super::{BufStream, SizeHint};
+
+use bytes::Buf;
+use futures::{Poll, try_ready};
+
+use std::error;
+use std::fmt;
+
+/// A buf stream that fails once the inner stream yields more than a maximum
+/// number of bytes.
+///
+/// `Limit` values are produced by the `limit` function on `BufStream`.
+#[derive(Debug)]
+pub struct Limit<T> {
+    stream: T,
+    remaining: u64,
+
+    /// Set once the size hint of the inner stream has been checked
+    checked_hint: bool,
+}
+
+/// Errors returned by `Limit`.
+#[derive(Debug)]
+pub struct Error<T> {
+    /// `None` represents the limit being exceeded
+    inner: Option<T>,
+}
+
+impl<T> Limit<T> {
+    pub(crate) fn new(stream: T, max: u64) -> Limit<T> {
+        Limit {
+            stream,
+            remaining: max,
+            checked_hint: false,
+        }
+    }
+}
+
+impl<T> BufStream for Limit<T>
+where
+    T: BufStream,
+{
+    type Item = T::Item;
+    type Error = Error<T::Error>;
+
+    fn poll(&mut self) -> Poll<Option<Self::Item>, Self::Error> {
+        // Fail without reading any data if the stream announces more data than
+        // is allowed, e.g. through the `Content-Length` header. This is only
+        // done before the first read, as streams are not required to shrink
+        // their hint as data is yielded.
+        if !self.checked_hint {
+            if self.stream.size_hint().lower() as u64 > self.remaining {
+                return Err(Error { inner: None });
+            }
+
+            self.checked_hint = true;
+        }
+
+        let buf = try_ready!(self.stream.poll().map_err(|e| Error { inner: Some(e) }));
+
+        if let Some(ref buf) = buf {
+            let len = buf.remaining() as u64;
+
+            if len > self.remaining {
+                return Err(Error { inner: None });
+            }
+
+            self.remaining -= len;
+        }
+
+        Ok(buf.into())
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.stream.size_hint()
+    }
+}
+
+// ===== impl Error =====
+
+impl<T> Error<T> {
+    /// Returns `true` if the error was caused by the limit being exceeded.
+    pub fn is_limit_exceeded(&self) -> bool {
+        self.inner.is_none()
+    }
+
+    /// Consumes `self`, returning the error of the inner stream, if any.
+    pub fn into_inner(self) -> Option<T> {
+        self.inner
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Error<T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.inner {
+            Some(ref e) => e.fmt(fmt),
+            None => fmt.write_str("buf stream limit exceeded"),
+        }
+    }
+}
+
+impl<T: error::Error> error::Error for Error<T> {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn limit_size_hint() {
+        assert!("hello".limit(5).poll().is_ok());
+        assert!("hello".limit(4).poll().unwrap_err().is_limit_exceeded());
+    }
+
+    #[test]
+    fn limit_while_streaming() {
+        // `Chain` does not provide a size hint
+        let mut stream = "hello".chain(" world").limit(8);
+
+        assert!(stream.poll().is_ok());
+        assert!(stream.poll().unwrap_err().is_limit_exceeded());
+    }
+
+    #[test]
+    fn limit_constant_size_hint() {
+        use super::super::size_hint;
+        use bytes::Bytes;
+        use futures::Async;
+        use std::collections::VecDeque;
+        use std::io;
+
+        /// Yields its chunks while always hinting at the full length, as
+        /// some HTTP/2 bodies do.
+        struct Chunks(VecDeque<Bytes>, usize);
+
+        impl BufStream for Chunks {
+            type Item = io::Cursor<Bytes>;
+            type Error = ();
+
+            fn poll(&mut self) -> Poll<Option<Self::Item>, ()> {
+                Ok(Async::Ready(self.0.pop_front().map(io::Cursor::new)))
+            }
+
+            fn size_hint(&self) -> SizeHint {
+                size_hint::Builder::new().lower(self.1).build()
+            }
+        }
+
+        let chunks = vec![Bytes::from(vec![0; 50]), Bytes::from(vec![0; 10])];
+        let mut stream = Chunks(chunks.into_iter().collect(), 60).limit(100);
+
+        assert!(stream.poll().unwrap().is_ready());
+        assert!(stream.poll().unwrap().is_ready());
+        assert!(stream.poll().unwrap().is_ready());
+    }
+}
@@ -1,5 +1,6 @@
 use crate::error::Never;
 use super::BufStream;
+use super::size_hint::{Builder, SizeHint};
 
 use futures::Poll;
 
@@ -20,6 +21,10 @@ impl BufStream for String {
 
         Ok(Some(buf).into())
     }
+
+    fn size_hint(&self) -> SizeHint {
+        exact(self.len())
+    }
 }
 
 impl BufStream for &'static str {
@@ -36,4 +41,14 @@ impl BufStream for &'static str {
 
         Ok(Some(buf).into())
     }
+
+    fn size_hint(&self) -> SizeHint {
+        exact(self.len())
+    }
+}
+
+fn exact(len: usize) -> SizeHint {
+    let mut builder = Builder::new();
+    builder.available(len).upper(len);
+    builder.build()
 }
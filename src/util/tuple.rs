@@ -7,6 +7,7 @@ use crate::extract::{self, ExtractFuture};
 use crate::response::{Context, Response, Serializer};
 use crate::routing::{self, Resource, ResourceFuture, IntoResource, RouteSet, RouteMatch};
 use crate::util::{BufStream, Chain};
+use crate::util::buf_stream::SizeHint;
 use crate::util::http::{HttpFuture, SealedFuture};
 
 use bytes::Buf;
@@ -154,6 +155,14 @@ where
             A(ref mut f) => Ok(try_ready!(f.poll()).map(A).into()),
         }
     }
+
+    fn size_hint(&self) -> SizeHint {
+        use self::Either1::*;
+
+        match *self {
+            A(ref f) => f.size_hint(),
+        }
+    }
 }
 
 impl<A> Buf for Either1<A>
@@ -346,6 +355,15 @@ where
             B(ref mut f) => Ok(try_ready!(f.poll()).map(B).into()),
         }
     }
+
+    fn size_hint(&self) -> SizeHint {
+        use self::Either2::*;
+
+        match *self {
+            A(ref f) => f.size_hint(),
+            B(ref f) => f.size_hint(),
+        }
+    }
 }
 
 impl<A, B> Buf for Either2<A, B>
@@ -562,6 +580,16 @@ where
             C(ref mut f) => Ok(try_ready!(f.poll()).map(C).into()),
         }
     }
+
+    fn size_hint(&self) -> SizeHint {
+        use self::Either3::*;
+
+        match *self {
+            A(ref f) => f.size_hint(),
+            B(ref f) => f.size_hint(),
+            C(ref f) => f.size_hint(),
+        }
+    }
 }
 
 impl<A, B, C> Buf for Either3<A, B, C>
@@ -802,6 +830,17 @@ where
             D(ref mut f) => Ok(try_ready!(f.poll()).map(D).into()),
         }
     }
+
+    fn size_hint(&self) -> SizeHint {
+        use self::Either4::*;
+
+        match *self {
+            A(ref f) => f.size_hint(),
+            B(ref f) => f.size_hint(),
+            C(ref f) => f.size_hint(),
+            D(ref f) => f.size_hint(),
+        }
+    }
 }
 
 impl<A, B, C, D> Buf for Either4<A, B, C, D>
@@ -1066,6 +1105,18 @@ where
             E(ref mut f) => Ok(try_ready!(f.poll()).map(E).into()),
         }
     }
+
+    fn size_hint(&self) -> SizeHint {
+        use self::Either5::*;
+
+        match *self {
+            A(ref f) => f.size_hint(),
+            B(ref f) => f.size_hint(),
+            C(ref f) => f.size_hint(),
+            D(ref f) => f.size_hint(),
+            E(ref f) => f.size_hint(),
+        }
+    }
 }
 
 impl<A, B, C, D, E> Buf for Either5<A, B, C, D, E>
@@ -1354,6 +1405,19 @@ where
             F(ref mut f) => Ok(try_ready!(f.poll()).map(F).into()),
         }
     }
+
+    fn size_hint(&self) -> SizeHint {
+        use self::Either6::*;
+
+        match *self {
+            A(ref f) => f.size_hint(),
+            B(ref f) => f.size_hint(),
+            C(ref f) => f.size_hint(),
+            D(ref f) => f.size_hint(),
+            E(ref f) => f.size_hint(),
+            F(ref f) => f.size_hint(),
+        }
+    }
 }
 
 impl<A, B, C, D, E, F> Buf for Either6<A, B, C, D, E, F>
@@ -1666,6 +1730,20 @@ where
             G(ref mut f) => Ok(try_ready!(f.poll()).map(G).into()),
         }
     }
+
+    fn size_hint(&self) -> SizeHint {
+        use self::Either7::*;
+
+        match *self {
+            A(ref f) => f.size_hint(),
+            B(ref f) => f.size_hint(),
+            C(ref f) => f.size_hint(),
+            D(ref f) => f.size_hint(),
+            E(ref f) => f.size_hint(),
+            F(ref f) => f.size_hint(),
+            G(ref f) => f.size_hint(),
+        }
+    }
 }
 
 impl<A, B, C, D, E, F, G> Buf for Either7<A, B, C, D, E, F, G>
@@ -2002,6 +2080,21 @@ where
             H(ref mut f) => Ok(try_ready!(f.poll()).map(H).into()),
         }
     }
+
+    fn size_hint(&self) -> SizeHint {
+        use self::Either8::*;
+
+        match *self {
+            A(ref f) => f.size_hint(),
+            B(ref f) => f.size_hint(),
+            C(ref f) => f.size_hint(),
+            D(ref f) => f.size_hint(),
+            E(ref f) => f.size_hint(),
+            F(ref f) => f.size_hint(),
+            G(ref f) => f.size_hint(),
+            H(ref f) => f.size_hint(),
+        }
+    }
 }
 
 impl<A, B, C, D, E, F, G, H> Buf for Either8<A, B, C, D, E, F, G, H>
@@ -2362,6 +2455,22 @@ where
             I(ref mut f) => Ok(try_ready!(f.poll()).map(I).into()),
         }
     }
+
+    fn size_hint(&self) -> SizeHint {
+        use self::Either9::*;
+
+        match *self {
+            A(ref f) => f.size_hint(),
+            B(ref f) => f.size_hint(),
+            C(ref f) => f.size_hint(),
+            D(ref f) => f.size_hint(),
+            E(ref f) => f.size_hint(),
+            F(ref f) => f.size_hint(),
+            G(ref f) => f.size_hint(),
+            H(ref f) => f.size_hint(),
+            I(ref f) => f.size_hint(),
+        }
+    }
 }
 
 impl<A, B, C, D, E, F, G, H, I> Buf for Either9<A, B, C, D, E, F, G, H, I>
@@ -2746,6 +2855,23 @@ where
             J(ref mut f) => Ok(try_ready!(f.poll()).map(J).into()),
         }
     }
+
+    fn size_hint(&self) -> SizeHint {
+        use self::Either10::*;
+
+        match *self {
+            A(ref f) => f.size_hint(),
+            B(ref f) => f.size_hint(),
+            C(ref f) => f.size_hint(),
+            D(ref f) => f.size_hint(),
+            E(ref f) => f.size_hint(),
+            F(ref f) => f.size_hint(),
+            G(ref f) => f.size_hint(),
+            H(ref f) => f.size_hint(),
+            I(ref f) => f.size_hint(),
+            J(ref f) => f.size_hint(),
+        }
+    }
 }
 
 impl<A, B, C, D, E, F, G, H, I, J> Buf for Either10<A, B, C, D, E, F, G, H, I, J>
@@ -3154,6 +3280,24 @@ where
             K(ref mut f) => Ok(try_ready!(f.poll()).map(K).into()),
         }
     }
+
+    fn size_hint(&self) -> SizeHint {
+        use self::Either11::*;
+
+        match *self {
+            A(ref f) => f.size_hint(),
+            B(ref f) => f.size_hint(),
+            C(ref f) => f.size_hint(),
+            D(ref f) => f.size_hint(),
+            E(ref f) => f.size_hint(),
+            F(ref f) => f.size_hint(),
+            G(ref f) => f.size_hint(),
+            H(ref f) => f.size_hint(),
+            I(ref f) => f.size_hint(),
+            J(ref f) => f.size_hint(),
+            K(ref f) => f.size_hint(),
+        }
+    }
 }
 
 impl<A, B, C, D, E, F, G, H, I, J, K> Buf for Either11<A, B, C, D, E, F, G, H, I, J, K>
@@ -3586,6 +3730,25 @@ where
             L(ref mut f) => Ok(try_ready!(f.poll()).map(L).into()),
         }
     }
+
+    fn size_hint(&self) -> SizeHint {
+        use self::Either12::*;
+
+        match *self {
+            A(ref f) => f.size_hint(),
+            B(ref f) => f.size_hint(),
+            C(ref f) => f.size_hint(),
+            D(ref f) => f.size_hint(),
+            E(ref f) => f.size_hint(),
+            F(ref f) => f.size_hint(),
+            G(ref f) => f.size_hint(),
+            H(ref f) => f.size_hint(),
+            I(ref f) => f.size_hint(),
+            J(ref f) => f.size_hint(),
+            K(ref f) => f.size_hint(),
+            L(ref f) => f.size_hint(),
+        }
+    }
 }
 
 impl<A, B, C, D, E, F, G, H, I, J, K, L> Buf for Either12<A, B, C, D, E, F, G, H, I, J, K, L>
@@ -0,0 +1,97 @@
+use tower_web::{Deserialize, Extract, impl_web};
+
+mod support;
+use crate::support::*;
+
+#[derive(Clone, Debug)]
+struct Users;
+
+#[derive(Debug, Extract)]
+struct NewUser {
+    name: String,
+    age: u32,
+    emails: Vec<Email>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Email {
+    address: String,
+    primary: bool,
+}
+
+impl_web! {
+    impl Users {
+        #[post("/users")]
+        fn create(&self, body: NewUser) -> Result<String, ()> {
+            let primary = body.emails.iter()
+                .find(|email| email.primary)
+                .map(|email| &email.address[..]);
+
+            Ok(format!("{}; {}; {:?}", body.name, body.age, primary))
+        }
+    }
+}
+
+#[test]
+fn json_success() {
+    let mut web = service(Users);
+
+    let body = r#"{"name":"alice","age":30,"emails":[{"address":"a@b.c","primary":true}]}"#;
+    let response = web.call_unwrap(post!("/users", body, "content-type": "application/json"));
+    assert_ok!(response);
+    assert_body!(response, r#"alice; 30; Some("a@b.c")"#);
+}
+
+#[test]
+fn json_syntax_error() {
+    let mut web = service(Users);
+
+    let body = r#"{"name":"alice","#;
+    let response = web.call_unwrap(post!("/users", body, "content-type": "application/json"));
+    assert_bad_request!(response);
+    assert_body!(response, r#"{"type":"about:blank","title":"Bad Request","detail":"EOF while parsing a value at line 1 column 16"}"#);
+
+    let body = r#"{"name":"alice","age":30,"emails":[]} trailing"#;
+    let response = web.call_unwrap(post!("/users", body, "content-type": "application/json"));
+    assert_bad_request!(response);
+}
+
+#[test]
+fn json_invalid_type() {
+    let mut web = service(Users);
+
+    let body = r#"{"name":"alice","age":"thirty","emails":[]}"#;
+    let response = web.call_unwrap(post!("/users", body, "content-type": "application/json"));
+    assert_eq!(response.status(), http::StatusCode::UNPROCESSABLE_ENTITY);
+    assert_body!(response, r#"{"type":"about:blank","title":"Unprocessable Entity","detail":"age: invalid type: string \"thirty\", expected u32 at line 1 column 30"}"#);
+
+    let body = r#"{"name":"alice","age":30,"emails":[{"address":"a@b.c","primary":true},{"address":"d@e.f","primary":"no"}]}"#;
+    let response = web.call_unwrap(post!("/users", body, "content-type": "application/json"));
+    assert_eq!(response.status(), http::StatusCode::UNPROCESSABLE_ENTITY);
+    assert_body!(response, r#"{"type":"about:blank","title":"Unprocessable Entity","detail":"emails[1].primary: invalid type: string \"no\", expected a boolean at line 1 column 103"}"#);
+}
+
+#[test]
+fn json_missing_field() {
+    let mut web = service(Users);
+
+    let body = r#"{"name":"alice","emails":[]}"#;
+    let response = web.call_unwrap(post!("/users", body, "content-type": "application/json"));
+    assert_eq!(response.status(), http::StatusCode::UNPROCESSABLE_ENTITY);
+    assert_body!(response, r#"{"type":"about:blank","title":"Unprocessable Entity","detail":"missing field `age` at line 1 column 28"}"#);
+
+    let body = r#"{"name":"alice","age":30,"emails":[{"primary":true}]}"#;
+    let response = web.call_unwrap(post!("/users", body, "content-type": "application/json"));
+    assert_eq!(response.status(), http::StatusCode::UNPROCESSABLE_ENTITY);
+    assert_body!(response, r#"{"type":"about:blank","title":"Unprocessable Entity","detail":"emails[0]: missing field `address` at line 1 column 51"}"#);
+}
+
+#[test]
+fn form_invalid_value() {
+    let mut web = service(Users);
+
+    let body = "name=alice&age=thirty";
+    let response = web.call_unwrap(post!("/users", body, "content-type": "application/x-www-form-urlencoded"));
+    assert_eq!(response.status(), http::StatusCode::UNPROCESSABLE_ENTITY);
+    assert_body!(response, r#"{"type":"about:blank","title":"Unprocessable Entity","detail":"age: invalid digit found in string"}"#);
+}
@@ -0,0 +1,87 @@
+use tower_web::{Deserialize, Extract, impl_web};
+
+mod support;
+use crate::support::*;
+
+#[derive(Clone, Debug)]
+struct Uploads;
+
+#[derive(Debug, Extract)]
+struct Message {
+    text: String,
+}
+
+impl_web! {
+    impl Uploads {
+        #[post("/bytes")]
+        fn bytes(&self, body: Vec<u8>) -> Result<String, ()> {
+            Ok(format!("{} bytes", body.len()))
+        }
+
+        #[post("/message")]
+        fn message(&self, body: Message) -> Result<String, ()> {
+            Ok(body.text)
+        }
+
+        #[post("/large")]
+        #[web(body_limit = "1KB")]
+        fn large(&self, body: Vec<u8>) -> Result<String, ()> {
+            Ok(format!("{} bytes", body.len()))
+        }
+    }
+}
+
+fn limited_service(limit: u64) -> impl TestHttpService<RequestBody = String> {
+    use tower_service::NewService;
+
+    ::tower_web::ServiceBuilder::new()
+        .resource(Uploads)
+        .body_limit(limit)
+        .build_new_service()
+        .new_service()
+        .wait().unwrap()
+}
+
+#[test]
+fn unlimited_by_default() {
+    let mut web = service(Uploads);
+
+    let response = web.call_unwrap(post!("/bytes", "x".repeat(64 * 1024)));
+    assert_ok!(response);
+    assert_body!(response, "65536 bytes");
+}
+
+#[test]
+fn global_limit() {
+    let mut web = limited_service(16);
+
+    let response = web.call_unwrap(post!("/bytes", "x".repeat(16)));
+    assert_ok!(response);
+    assert_body!(response, "16 bytes");
+
+    let response = web.call_unwrap(post!("/bytes", "x".repeat(17)));
+    assert_eq!(response.status(), ::http::StatusCode::PAYLOAD_TOO_LARGE);
+    assert_body!(response, r#"{"type":"about:blank","title":"Payload Too Large"}"#);
+
+    let body = r#"{"text":"a long message"}"#;
+    let response = web.call_unwrap(post!("/message", body, "content-type": "application/json"));
+    assert_eq!(response.status(), ::http::StatusCode::PAYLOAD_TOO_LARGE);
+}
+
+#[test]
+fn handler_limit() {
+    let mut web = limited_service(16);
+
+    let response = web.call_unwrap(post!("/large", "x".repeat(1024)));
+    assert_ok!(response);
+    assert_body!(response, "1024 bytes");
+
+    let response = web.call_unwrap(post!("/large", "x".repeat(1025)));
+    assert_eq!(response.status(), ::http::StatusCode::PAYLOAD_TOO_LARGE);
+
+    // The handler limit also applies without a global limit
+    let mut web = service(Uploads);
+
+    let response = web.call_unwrap(post!("/large", "x".repeat(1025)));
+    assert_eq!(response.status(), ::http::StatusCode::PAYLOAD_TOO_LARGE);
+}
@@ -0,0 +1,94 @@
+use futures::{Future, Stream};
+use tower_web::extract::{BodyStream, Error};
+use tower_web::impl_web;
+
+mod support;
+use crate::support::*;
+
+#[derive(Clone, Debug)]
+struct Uploads;
+
+impl_web! {
+    impl Uploads {
+        #[post("/upload")]
+        fn upload(&self, body: BodyStream) -> impl Future<Item = String, Error = ()> {
+            body.fold(vec![], |mut data, chunk| {
+                data.extend_from_slice(&chunk);
+                Ok::<_, Error>(data)
+            })
+            .map(|data| String::from_utf8(data).unwrap())
+            .map_err(|_| ())
+        }
+
+        #[post("/users/:id/avatar")]
+        fn avatar(&self, id: u32, body: BodyStream) -> impl Future<Item = String, Error = ()> {
+            body.concat2()
+                .map(move |data| format!("user {}: {} bytes", id, data.len()))
+                .map_err(|_| ())
+        }
+
+        #[post("/limited")]
+        #[web(body_limit = "8")]
+        fn limited(&self, body: BodyStream) -> impl Future<Item = String, Error = ()> {
+            body.concat2().then(|res| {
+                Ok(match res {
+                    Ok(data) => format!("{} bytes", data.len()),
+                    Err(err) => format!("error {}", tower_web::Error::from(err).status_code()),
+                })
+            })
+        }
+
+        #[post("/misnamed")]
+        fn misnamed(&self, data: BodyStream) -> impl Future<Item = String, Error = ()> {
+            data.concat2()
+                .map(|data| format!("{} bytes", data.len()))
+                .map_err(|_| ())
+        }
+    }
+}
+
+#[test]
+fn stream_body() {
+    let mut web = service(Uploads);
+
+    let response = web.call_unwrap(post!("/upload", "hello world"));
+    assert_ok!(response);
+    assert_body!(response, "hello world");
+
+    let response = web.call_unwrap(post!("/upload", ""));
+    assert_ok!(response);
+    assert_body!(response, "");
+}
+
+#[test]
+fn stream_body_with_captures() {
+    let mut web = service(Uploads);
+
+    let response = web.call_unwrap(post!("/users/7/avatar", "abcdef"));
+    assert_ok!(response);
+    assert_body!(response, "user 7: 6 bytes");
+
+    let response = web.call_unwrap(post!("/users/nope/avatar", "abcdef"));
+    assert_bad_request!(response);
+}
+
+#[test]
+fn stream_body_limit() {
+    let mut web = service(Uploads);
+
+    let response = web.call_unwrap(post!("/limited", "12345678"));
+    assert_ok!(response);
+    assert_body!(response, "8 bytes");
+
+    let response = web.call_unwrap(post!("/limited", "123456789"));
+    assert_ok!(response);
+    assert_body!(response, "error 413 Payload Too Large");
+}
+
+#[test]
+fn stream_body_not_named_body() {
+    let mut web = service(Uploads);
+
+    let response = web.call_unwrap(post!("/misnamed", "hello"));
+    assert_internal_error!(response);
+}
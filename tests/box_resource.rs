@@ -0,0 +1,77 @@
+use http::Method;
+use tower_web::impl_web;
+use tower_web::routing::{BoxResource, HandlerRoute};
+
+mod support;
+use crate::support::*;
+
+#[derive(Clone, Debug)]
+struct Users;
+
+#[derive(Clone, Debug)]
+struct Admin;
+
+impl_web! {
+    impl Users {
+        #[get("/users/:id")]
+        fn user(&self, id: u32) -> Result<String, ()> {
+            Ok(format!("user {}", id))
+        }
+    }
+
+    #[web(prefix = "/admin")]
+    impl Admin {
+        #[get("/")]
+        fn index(&self) -> Result<&'static str, ()> {
+            Ok("admin")
+        }
+    }
+}
+
+fn web(admin: bool) -> impl TestHttpService<RequestBody = String> {
+    use tower_service::NewService;
+
+    let mut resources = vec![
+        BoxResource::new(Users),
+        BoxResource::new(HandlerRoute::new(Method::GET, "/", || "index")),
+    ];
+
+    if admin {
+        resources.push(BoxResource::new(Admin));
+    }
+
+    ::tower_web::ServiceBuilder::new()
+        .resource(resources)
+        .build_new_service()
+        .new_service()
+        .wait().unwrap()
+}
+
+#[test]
+fn dispatch() {
+    let mut web = web(true);
+
+    let response = web.call_unwrap(get!("/users/12"));
+    assert_ok!(response);
+    assert_body!(response, "user 12");
+
+    let response = web.call_unwrap(get!("/"));
+    assert_ok!(response);
+    assert_body!(response, "index");
+
+    let response = web.call_unwrap(get!("/admin"));
+    assert_ok!(response);
+    assert_body!(response, "admin");
+}
+
+#[test]
+fn chosen_at_runtime() {
+    let mut web = web(false);
+
+    let response = web.call_unwrap(get!("/users/12"));
+    assert_ok!(response);
+    assert_body!(response, "user 12");
+
+    let response = web.call_unwrap(get!("/admin"));
+    assert_not_found!(response);
+}
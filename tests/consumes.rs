@@ -0,0 +1,142 @@
+use tower_web::{Deserialize, Extract, impl_web};
+
+mod support;
+use crate::support::*;
+
+#[derive(Clone, Debug)]
+struct Documents;
+
+#[derive(Debug, Extract)]
+struct Document {
+    title: String,
+}
+
+impl_web! {
+    impl Documents {
+        #[post("/documents")]
+        #[web(consumes = "application/json")]
+        fn create(&self, body: Document) -> Result<String, ()> {
+            Ok(format!("created {}", body.title))
+        }
+
+        #[patch("/documents")]
+        #[web(consumes = "application/json, application/x-www-form-urlencoded")]
+        fn update(&self, body: Document) -> Result<String, ()> {
+            Ok(format!("updated {}", body.title))
+        }
+
+        #[put("/documents")]
+        #[web(consumes = "application/json")]
+        fn replace_json(&self, body: Document) -> Result<String, ()> {
+            Ok(format!("json {}", body.title))
+        }
+
+        #[put("/documents")]
+        #[web(consumes = "text/plain")]
+        fn replace_text(&self, body: String) -> Result<String, ()> {
+            Ok(format!("text {}", body))
+        }
+
+        #[post("/any")]
+        fn any(&self, body: Document) -> Result<String, ()> {
+            Ok(body.title)
+        }
+    }
+}
+
+#[test]
+fn consumes_json() {
+    let mut web = service(Documents);
+
+    let body = r#"{"title":"hello"}"#;
+    let response = web.call_unwrap(post!("/documents", body, "content-type": "application/json; charset=utf-8"));
+    assert_ok!(response);
+    assert_body!(response, "created hello");
+
+    let response = web.call_unwrap(post!("/documents", body, "content-type": "application/vnd.docs+json"));
+    assert_ok!(response);
+    assert_body!(response, "created hello");
+}
+
+#[test]
+fn unsupported_media_type() {
+    let mut web = service(Documents);
+
+    let response = web.call_unwrap(post!("/documents", "title=hello", "content-type": "application/x-www-form-urlencoded"));
+    assert_eq!(response.status(), http::StatusCode::UNSUPPORTED_MEDIA_TYPE);
+    assert_header!(response, "accept-post", "application/json");
+
+    let response = web.call_unwrap(post!("/documents", r#"{"title":"hello"}"#));
+    assert_eq!(response.status(), http::StatusCode::UNSUPPORTED_MEDIA_TYPE);
+    assert_header!(response, "accept-post", "application/json");
+}
+
+#[test]
+fn patch_media_types() {
+    let mut web = service(Documents);
+
+    let request = http::Request::builder()
+        .method("PATCH")
+        .uri("/documents")
+        .header("content-type", "application/merge-patch+json")
+        .body(r#"{"title":"hello"}"#.to_string())
+        .unwrap();
+
+    let response = web.call_unwrap(request);
+    assert_ok!(response);
+    assert_body!(response, "updated hello");
+
+    let request = http::Request::builder()
+        .method("PATCH")
+        .uri("/documents")
+        .header("content-type", "text/plain")
+        .body("hello".to_string())
+        .unwrap();
+
+    let response = web.call_unwrap(request);
+    assert_eq!(response.status(), http::StatusCode::UNSUPPORTED_MEDIA_TYPE);
+    assert_header!(response, "accept-patch", "application/json, application/x-www-form-urlencoded");
+}
+
+#[test]
+fn undeclared_media_types() {
+    let mut web = service(Documents);
+
+    let response = web.call_unwrap(post!("/any", r#"{"title":"hello"}"#, "content-type": "application/problem+json"));
+    assert_ok!(response);
+    assert_body!(response, "hello");
+
+    let response = web.call_unwrap(post!("/any", "hello", "content-type": "text/plain"));
+    assert_eq!(response.status(), http::StatusCode::UNSUPPORTED_MEDIA_TYPE);
+    assert_header!(response, "accept-post", "application/json, application/x-www-form-urlencoded, multipart/form-data");
+
+    // Missing content type
+    let response = web.call_unwrap(post!("/any", r#"{"title":"hello"}"#));
+    assert_eq!(response.status(), http::StatusCode::UNSUPPORTED_MEDIA_TYPE);
+    assert_header!(response, "accept-post", "application/json, application/x-www-form-urlencoded, multipart/form-data");
+}
+
+#[test]
+fn select_by_media_type() {
+    let mut web = service(Documents);
+
+    let put = |body: &str, content_type: &str| {
+        http::Request::builder()
+            .method("PUT")
+            .uri("/documents")
+            .header("content-type", content_type)
+            .body(body.to_string())
+            .unwrap()
+    };
+
+    let response = web.call_unwrap(put(r#"{"title":"hello"}"#, "application/json"));
+    assert_ok!(response);
+    assert_body!(response, "json hello");
+
+    let response = web.call_unwrap(put("hello", "text/plain"));
+    assert_ok!(response);
+    assert_body!(response, "text hello");
+
+    let response = web.call_unwrap(put("hello", "text/csv"));
+    assert_eq!(response.status(), http::StatusCode::UNSUPPORTED_MEDIA_TYPE);
+}
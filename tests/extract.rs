@@ -208,7 +208,7 @@ fn extract_body_wrap_json_no_content_type_header() {
     let body = "";
 
     let response = web.call_unwrap(post!("/extract_body", body));
-    assert_bad_request!(response);
+    assert_eq!(response.status(), http::StatusCode::UNSUPPORTED_MEDIA_TYPE);
 }
 
 #[test]
@@ -0,0 +1,116 @@
+use tower_web::{Deserialize, Extract, impl_web};
+
+mod support;
+use crate::support::*;
+
+#[derive(Clone, Debug)]
+struct Issues;
+
+#[derive(Debug, Extract, PartialEq)]
+#[serde(rename_all = "lowercase")]
+enum Status {
+    Open,
+    Closed,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum State {
+    Open,
+    Closed,
+}
+
+#[derive(Debug, Extract)]
+struct IssueId(u32);
+
+#[derive(Debug, Extract)]
+struct IssuePath {
+    project: String,
+    id: u32,
+    state: State,
+}
+
+#[derive(Debug, Extract)]
+struct TenantPath {
+    tenant: String,
+    id: u32,
+}
+
+impl_web! {
+    impl Issues {
+        #[get("/issues/:status")]
+        fn by_status(&self, status: Status) -> Result<String, ()> {
+            Ok(format!("{:?}", status))
+        }
+
+        #[get("/issue/:id")]
+        fn by_id(&self, id: IssueId) -> Result<String, ()> {
+            Ok(format!("issue {}", id.0))
+        }
+
+        #[get("/projects/:project/issues/:id/:state")]
+        fn issue(&self, captures: IssuePath) -> Result<String, ()> {
+            Ok(format!("{}; {}; {:?}", captures.project, captures.id, captures.state))
+        }
+
+        #[get("/mounted/:id")]
+        fn mounted(&self, captures: TenantPath) -> Result<String, ()> {
+            Ok(format!("{}; {}", captures.tenant, captures.id))
+        }
+    }
+}
+
+#[test]
+fn enum_capture() {
+    let mut web = service(Issues);
+
+    let response = web.call_unwrap(get!("/issues/open"));
+    assert_ok!(response);
+    assert_body!(response, "Open");
+
+    let response = web.call_unwrap(get!("/issues/closed"));
+    assert_ok!(response);
+    assert_body!(response, "Closed");
+
+    let response = web.call_unwrap(get!("/issues/pending"));
+    assert_bad_request!(response);
+}
+
+#[test]
+fn newtype_capture() {
+    let mut web = service(Issues);
+
+    let response = web.call_unwrap(get!("/issue/12"));
+    assert_ok!(response);
+    assert_body!(response, "issue 12");
+
+    let response = web.call_unwrap(get!("/issue/twelve"));
+    assert_bad_request!(response);
+}
+
+#[test]
+fn struct_from_all_captures() {
+    let mut web = service(Issues);
+
+    let response = web.call_unwrap(get!("/projects/tower%20web/issues/12/closed"));
+    assert_ok!(response);
+    assert_body!(response, "tower web; 12; Closed");
+
+    let response = web.call_unwrap(get!("/projects/tower/issues/twelve/closed"));
+    assert_bad_request!(response);
+}
+
+#[test]
+fn struct_from_mount_captures() {
+    use tower_service::NewService;
+
+    let mut web = ::tower_web::ServiceBuilder::new()
+        .mount("/tenants/:tenant", Issues)
+        .build_new_service()
+        .new_service()
+        .wait().unwrap();
+
+    let response = web.call_unwrap(get!("/tenants/acme/mounted/12"));
+    assert_ok!(response);
+    assert_body!(response, "acme; 12");
+}
@@ -0,0 +1,83 @@
+use tower_web::{Deserialize, Extract, impl_web};
+
+mod support;
+use crate::support::*;
+
+#[derive(Clone, Debug)]
+struct Clients;
+
+#[derive(Debug, Extract)]
+struct ClientInfo {
+    user_agent: String,
+    accept_language: Option<String>,
+    #[web(header(name = "x-request-id"))]
+    id: Option<u64>,
+}
+
+#[derive(Debug, Extract)]
+#[serde(rename_all = "lowercase")]
+enum Mode {
+    Fast,
+    Slow,
+}
+
+impl_web! {
+    impl Clients {
+        #[get("/")]
+        fn client(&self, client: ClientInfo) -> Result<String, ()> {
+            Ok(format!("{}; {:?}; {:?}", client.user_agent, client.accept_language, client.id))
+        }
+
+        #[get("/mode")]
+        fn mode(&self, x_mode: Mode) -> Result<String, ()> {
+            Ok(format!("{:?}", x_mode))
+        }
+    }
+}
+
+#[test]
+fn fields_from_headers() {
+    let mut web = service(Clients);
+
+    let response = web.call_unwrap(get!("/", "user-agent": "curl", "accept-language": "en"));
+    assert_ok!(response);
+    assert_body!(response, "curl; Some(\"en\"); None");
+
+    let response = web.call_unwrap(get!("/", "user-agent": "curl", "x-request-id": "42"));
+    assert_ok!(response);
+    assert_body!(response, "curl; None; Some(42)");
+}
+
+#[test]
+fn missing_header() {
+    let mut web = service(Clients);
+
+    let response = web.call_unwrap(get!("/"));
+    assert_bad_request!(response);
+
+    let response = web.call_unwrap(get!("/", "accept-language": "en"));
+    assert_bad_request!(response);
+}
+
+#[test]
+fn invalid_header() {
+    let mut web = service(Clients);
+
+    let response = web.call_unwrap(get!("/", "user-agent": "curl", "x-request-id": "nope"));
+    assert_bad_request!(response);
+}
+
+#[test]
+fn single_header() {
+    let mut web = service(Clients);
+
+    let response = web.call_unwrap(get!("/mode", "x-mode": "fast"));
+    assert_ok!(response);
+    assert_body!(response, "Fast");
+
+    let response = web.call_unwrap(get!("/mode", "x-mode": "other"));
+    assert_bad_request!(response);
+
+    let response = web.call_unwrap(get!("/mode"));
+    assert_bad_request!(response);
+}
@@ -0,0 +1,92 @@
+use tower_web::impl_web;
+
+mod support;
+use crate::support::*;
+
+#[derive(Clone, Debug)]
+struct TestGuards;
+
+impl_web! {
+    impl TestGuards {
+        #[get("/users")]
+        #[web(guard(header = "x-api-version", value = "2"))]
+        fn users_v2_header(&self) -> Result<&'static str, ()> {
+            Ok("users v2")
+        }
+
+        #[get("/users")]
+        #[web(guard(accept = "application/vnd.acme.v2+json"))]
+        fn users_v2_accept(&self) -> Result<&'static str, ()> {
+            Ok("users v2 accept")
+        }
+
+        #[get("/users")]
+        #[web(guard(host = "admin.example.com"))]
+        #[web(guard(query = "all"))]
+        fn users_admin(&self) -> Result<&'static str, ()> {
+            Ok("all users")
+        }
+
+        #[get("/users")]
+        fn users(&self) -> Result<&'static str, ()> {
+            Ok("users")
+        }
+
+        #[post("/reports")]
+        #[web(guard(header = "x-api-version"))]
+        fn reports(&self) -> Result<&'static str, ()> {
+            Ok("reports")
+        }
+    }
+}
+
+#[test]
+fn header_guard() {
+    let mut web = service(TestGuards);
+
+    let response = web.call_unwrap(get!("/users", "x-api-version": "2"));
+    assert_ok!(response);
+    assert_body!(response, "users v2");
+
+    // Rejected guards fall through to the next route
+    let response = web.call_unwrap(get!("/users", "x-api-version": "1"));
+    assert_ok!(response);
+    assert_body!(response, "users");
+
+    let response = web.call_unwrap(post!("/reports", "", "x-api-version": "1"));
+    assert_ok!(response);
+    assert_body!(response, "reports");
+
+    let response = web.call_unwrap(post!("/reports", ""));
+    assert_not_found!(response);
+}
+
+#[test]
+fn accept_guard() {
+    let mut web = service(TestGuards);
+
+    let response = web.call_unwrap(get!("/users", "accept": "application/vnd.acme.v2+json"));
+    assert_ok!(response);
+    assert_body!(response, "users v2 accept");
+
+    let response = web.call_unwrap(get!("/users", "accept": "application/json"));
+    assert_ok!(response);
+    assert_body!(response, "users");
+}
+
+#[test]
+fn host_and_query_guards() {
+    let mut web = service(TestGuards);
+
+    let response = web.call_unwrap(get!("/users?all", "host": "admin.example.com"));
+    assert_ok!(response);
+    assert_body!(response, "all users");
+
+    let response = web.call_unwrap(get!("/users", "host": "admin.example.com"));
+    assert_ok!(response);
+    assert_body!(response, "users");
+
+    let response = web.call_unwrap(get!("/users?all", "host": "www.example.com"));
+    assert_ok!(response);
+    assert_body!(response, "users");
+}
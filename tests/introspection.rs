@@ -0,0 +1,62 @@
+use tower_web::impl_web;
+
+#[derive(Clone, Debug)]
+struct Users;
+
+#[derive(Clone, Debug)]
+struct Health;
+
+impl_web! {
+    impl Users {
+        #[get("/users")]
+        #[content_type("json")]
+        fn list_users(&self) -> Result<String, ()> {
+            Ok("[]".to_string())
+        }
+
+        #[post("/users/:id")]
+        #[web(name = "update")]
+        fn update_user(&self, id: u32) -> Result<String, ()> {
+            Ok(id.to_string())
+        }
+    }
+
+    impl Health {
+        #[get("/health")]
+        fn health(&self) -> Result<&'static str, ()> {
+            Ok("ok")
+        }
+    }
+}
+
+#[test]
+fn route_table() {
+    let service = ::tower_web::ServiceBuilder::new()
+        .mount("/api", Users)
+        .resource(Health)
+        .build_new_service::<String>();
+
+    let routes = service.routes();
+    assert_eq!(routes.len(), 3);
+
+    assert_eq!(routes[0].method(), &http::Method::GET);
+    assert_eq!(routes[0].path(), "/api/users");
+    assert_eq!(routes[0].name(), Some("list_users"));
+    assert_eq!(routes[0].resource(), Some("Users"));
+    assert_eq!(routes[0].handler(), Some("list_users"));
+    assert_eq!(routes[0].content_type(), Some("json"));
+
+    assert_eq!(routes[1].method(), &http::Method::POST);
+    assert_eq!(routes[1].path(), "/api/users/:id");
+    assert_eq!(routes[1].name(), Some("update"));
+    assert_eq!(routes[1].handler(), Some("update_user"));
+    assert_eq!(routes[1].content_type(), None);
+
+    let table: Vec<_> = routes.iter().map(|route| route.to_string()).collect();
+
+    assert_eq!(table, [
+        "GET /api/users -> Users::list_users (json)",
+        "POST /api/users/:id -> Users::update_user",
+        "GET /health -> Health::health",
+    ]);
+}
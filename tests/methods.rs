@@ -1,5 +1,10 @@
+use futures::Poll;
 use http;
 use tower_web::impl_web;
+use tower_web::util::BufStream;
+use tower_web::util::buf_stream::{size_hint, SizeHint};
+
+use std::io;
 
 mod support;
 use crate::support::*;
@@ -68,3 +73,266 @@ fn sync_method_str() {
         assert_body!(response, format!("{}: hello world", method));
     }
 }
+
+#[derive(Clone, Debug)]
+struct TestUsers;
+
+#[derive(Clone, Debug)]
+struct TestUsersAdmin;
+
+impl_web! {
+    impl TestUsers {
+        #[get("/users/:id")]
+        fn show(&self, id: u32) -> Result<String, ()> {
+            Ok(format!("user {}", id))
+        }
+    }
+
+    impl TestUsersAdmin {
+        #[delete("/users/:id")]
+        fn destroy(&self, id: u32) -> Result<String, ()> {
+            Ok(format!("deleted {}", id))
+        }
+    }
+}
+
+#[test]
+fn method_not_allowed() {
+    use tower_service::NewService;
+
+    let mut web = ::tower_web::ServiceBuilder::new()
+        .resource(TestUsers)
+        .resource(TestUsersAdmin)
+        .build_new_service()
+        .new_service()
+        .wait().unwrap();
+
+    let request = request::Builder::new()
+        .method("DELETE")
+        .uri("/users/1")
+        .body("".to_string())
+        .unwrap();
+
+    let response = web.call_unwrap(request);
+    assert_ok!(response);
+    assert_body!(response, "deleted 1");
+
+    let request = request::Builder::new()
+        .method("POST")
+        .uri("/users/1")
+        .body("".to_string())
+        .unwrap();
+
+    let response = web.call_unwrap(request);
+    assert_eq!(response.status(), http::StatusCode::METHOD_NOT_ALLOWED);
+    assert_header!(response, "allow", "GET, HEAD, DELETE, OPTIONS");
+
+    let request = request::Builder::new()
+        .method("POST")
+        .uri("/accounts/1")
+        .body("".to_string())
+        .unwrap();
+
+    let response = web.call_unwrap(request);
+    assert_not_found!(response);
+    assert!(response.headers().get("allow").is_none());
+}
+
+#[test]
+fn implicit_head() {
+    let mut web = service(TestMethods);
+
+    let request = request::Builder::new()
+        .method("HEAD")
+        .uri("/")
+        .body("".to_string())
+        .unwrap();
+
+    let response = web.call_unwrap(request);
+    assert_ok!(response);
+    assert_header!(response, "content-type", "text/plain");
+    assert_header!(response, "content-length", "16");
+    assert_body!(response, "");
+}
+
+/// A response body that must not be read.
+#[derive(Debug)]
+struct Unread {
+    len: Option<usize>,
+}
+
+impl BufStream for Unread {
+    type Item = io::Cursor<Vec<u8>>;
+    type Error = ();
+
+    fn poll(&mut self) -> Poll<Option<Self::Item>, ()> {
+        panic!("the body of a HEAD response must not be polled");
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        let mut builder = size_hint::Builder::new();
+
+        if let Some(len) = self.len {
+            builder.lower(len).upper(len);
+        }
+
+        builder.build()
+    }
+}
+
+#[derive(Clone, Debug)]
+struct TestStreams;
+
+impl_web! {
+    impl TestStreams {
+        #[get("/sized")]
+        fn sized(&self) -> Result<http::Response<Unread>, ()> {
+            Ok(http::Response::new(Unread { len: Some(1024) }))
+        }
+
+        #[get("/unsized")]
+        fn unsized_body(&self) -> Result<http::Response<Unread>, ()> {
+            Ok(http::Response::new(Unread { len: None }))
+        }
+    }
+}
+
+#[test]
+fn implicit_head_does_not_read_body() {
+    let mut web = service(TestStreams);
+
+    let request = request::Builder::new()
+        .method("HEAD")
+        .uri("/sized")
+        .body("".to_string())
+        .unwrap();
+
+    let response = web.call_unwrap(request);
+    assert_ok!(response);
+    assert_header!(response, "content-length", "1024");
+    assert_body!(response, "");
+
+    let request = request::Builder::new()
+        .method("HEAD")
+        .uri("/unsized")
+        .body("".to_string())
+        .unwrap();
+
+    let response = web.call_unwrap(request);
+    assert_ok!(response);
+    assert!(response.headers().get("content-length").is_none());
+    assert_body!(response, "");
+}
+
+#[test]
+fn implicit_options() {
+    let mut web = service(TestMethods);
+
+    let request = request::Builder::new()
+        .method("OPTIONS")
+        .uri("/")
+        .body("".to_string())
+        .unwrap();
+
+    let response = web.call_unwrap(request);
+    assert_ok!(response);
+    assert_header!(response, "allow", "GET, HEAD, POST, PUT, PATCH, DELETE, OPTIONS");
+    assert_header!(response, "content-length", "0");
+    assert_body!(response, "");
+
+    let request = request::Builder::new()
+        .method("OPTIONS")
+        .uri("/nope")
+        .body("".to_string())
+        .unwrap();
+
+    let response = web.call_unwrap(request);
+    assert_not_found!(response);
+}
+
+#[derive(Clone, Debug)]
+struct TestExtensionMethods;
+
+impl_web! {
+    impl TestExtensionMethods {
+        #[head("/")]
+        fn head(&self) -> Result<http::Response<&'static str>, ()> {
+            Ok(http::Response::builder()
+                .header("x-head", "explicit")
+                .body("")
+                .unwrap())
+        }
+
+        #[options("/")]
+        #[content_type("plain")]
+        fn options(&self) -> Result<&'static str, ()> {
+            Ok("OPTIONS: hello world")
+        }
+
+        #[trace("/")]
+        #[content_type("plain")]
+        fn trace(&self) -> Result<&'static str, ()> {
+            Ok("TRACE: hello world")
+        }
+
+        #[route(method = "PROPFIND", path = "/dav/*path")]
+        #[content_type("plain")]
+        fn propfind(&self, path: String) -> Result<String, ()> {
+            Ok(format!("PROPFIND: {}", path))
+        }
+
+        /// @route(method = "MKCOL", path = "/dav/*path")
+        fn mkcol(&self, path: String) -> Result<String, ()> {
+            Ok(format!("MKCOL: {}", path))
+        }
+    }
+}
+
+#[test]
+fn extension_methods() {
+    let mut web = service(TestExtensionMethods);
+
+    let request = request::Builder::new()
+        .method("HEAD")
+        .uri("/")
+        .body("".to_string())
+        .unwrap();
+
+    let response = web.call_unwrap(request);
+    assert_ok!(response);
+    assert_header!(response, "x-head", "explicit");
+
+    for &method in &["OPTIONS", "TRACE"] {
+        let request = request::Builder::new()
+            .method(method)
+            .uri("/")
+            .body("".to_string())
+            .unwrap();
+
+        let response = web.call_unwrap(request);
+        assert_ok!(response);
+        assert_body!(response, format!("{}: hello world", method));
+    }
+
+    for &method in &["PROPFIND", "MKCOL"] {
+        let request = request::Builder::new()
+            .method(method)
+            .uri("/dav/a/b")
+            .body("".to_string())
+            .unwrap();
+
+        let response = web.call_unwrap(request);
+        assert_ok!(response);
+        assert_body!(response, format!("{}: a/b", method));
+    }
+
+    let request = request::Builder::new()
+        .method("COPY")
+        .uri("/dav/a/b")
+        .body("".to_string())
+        .unwrap();
+
+    let response = web.call_unwrap(request);
+    assert_eq!(response.status(), http::StatusCode::METHOD_NOT_ALLOWED);
+    assert_header!(response, "allow", "PROPFIND, MKCOL, OPTIONS");
+}
@@ -0,0 +1,141 @@
+use tower_web::impl_web;
+
+mod support;
+use crate::support::*;
+
+#[derive(Clone, Debug)]
+struct Versioned;
+
+#[derive(Clone, Debug)]
+struct Users;
+
+#[derive(Clone, Debug)]
+struct Admin;
+
+#[derive(Clone, Debug)]
+struct Orgs;
+
+impl_web! {
+    impl Versioned {
+        #[get("/")]
+        fn index(&self) -> Result<&'static str, ()> {
+            Ok("index")
+        }
+
+        #[get("/items/:id")]
+        fn item(&self, id: u32) -> Result<String, ()> {
+            Ok(format!("item {}", id))
+        }
+    }
+
+    impl Users {
+        #[get("/users/:id")]
+        fn user(&self, tenant: String, id: u32) -> Result<String, ()> {
+            Ok(format!("tenant {}; user {}", tenant, id))
+        }
+    }
+
+    #[web(prefix = "/admin/")]
+    impl Admin {
+        #[get("/")]
+        fn index(&self) -> Result<&'static str, ()> {
+            Ok("admin index")
+        }
+
+        #[get("/users/:id")]
+        fn user(&self, id: u32) -> Result<String, ()> {
+            Ok(format!("admin user {}", id))
+        }
+    }
+
+    /// Organizations
+    /// @web(prefix = "/orgs/:org")
+    impl Orgs {
+        #[get("/members")]
+        fn members(&self, org: String) -> Result<String, ()> {
+            Ok(format!("members of {}", org))
+        }
+    }
+}
+
+#[test]
+fn mount_prefix() {
+    use tower_service::NewService;
+
+    let mut web = ::tower_web::ServiceBuilder::new()
+        .mount("/api/v1", Versioned)
+        .mount("/api/v2/", Versioned)
+        .build_new_service()
+        .new_service()
+        .wait().unwrap();
+
+    for prefix in &["/api/v1", "/api/v2"] {
+        let response = web.call_unwrap(get!(*prefix));
+        assert_ok!(response);
+        assert_body!(response, "index");
+
+        let response = web.call_unwrap(get!(&format!("{}/items/12", prefix)[..]));
+        assert_ok!(response);
+        assert_body!(response, "item 12");
+    }
+
+    let response = web.call_unwrap(get!("/"));
+    assert_not_found!(response);
+
+    let response = web.call_unwrap(get!("/items/12"));
+    assert_not_found!(response);
+}
+
+#[test]
+fn mount_prefix_captures() {
+    use tower_service::NewService;
+
+    let mut web = ::tower_web::ServiceBuilder::new()
+        .mount("/tenants/:tenant", Users)
+        .build_new_service()
+        .new_service()
+        .wait().unwrap();
+
+    let response = web.call_unwrap(get!("/tenants/acme/users/12"));
+    assert_ok!(response);
+    assert_body!(response, "tenant acme; user 12");
+
+    // Prefix captures are percent-decoded like any other capture
+    let response = web.call_unwrap(get!("/tenants/acme%20corp/users/12"));
+    assert_ok!(response);
+    assert_body!(response, "tenant acme corp; user 12");
+}
+
+#[test]
+fn unmounted_falls_back_to_header() {
+    let mut web = service(Users);
+
+    let response = web.call_unwrap(get!("/users/12", "tenant": "acme"));
+    assert_ok!(response);
+    assert_body!(response, "tenant acme; user 12");
+}
+
+#[test]
+fn impl_prefix() {
+    let mut web = service(Admin);
+
+    let response = web.call_unwrap(get!("/admin"));
+    assert_ok!(response);
+    assert_body!(response, "admin index");
+
+    let response = web.call_unwrap(get!("/admin/users/12"));
+    assert_ok!(response);
+    assert_body!(response, "admin user 12");
+
+    let response = web.call_unwrap(get!("/users/12"));
+    assert_not_found!(response);
+}
+
+#[test]
+fn impl_prefix_doc_rule() {
+    let mut web = service(Orgs);
+
+    let response = web.call_unwrap(get!("/orgs/tokio/members"));
+    assert_ok!(response);
+    assert_body!(response, "members of tokio");
+}
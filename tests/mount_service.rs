@@ -0,0 +1,118 @@
+use futures::future::{self, FutureResult};
+use futures::Poll;
+use tower_service::Service;
+use tower_web::impl_web;
+
+mod support;
+use crate::support::*;
+
+#[derive(Clone, Debug)]
+struct Legacy;
+
+impl Service for Legacy {
+    type Request = http::Request<String>;
+    type Response = http::Response<String>;
+    type Error = ();
+    type Future = FutureResult<Self::Response, ()>;
+
+    fn poll_ready(&mut self) -> Poll<(), ()> {
+        Ok(().into())
+    }
+
+    fn call(&mut self, request: Self::Request) -> Self::Future {
+        if request.uri().path() == "/fail" {
+            return future::err(());
+        }
+
+        let body = format!("{} {} {}",
+                           request.method(),
+                           request.uri(),
+                           request.body());
+
+        future::ok(http::Response::new(body))
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Users;
+
+impl_web! {
+    impl Users {
+        #[get("/legacy/users")]
+        fn users(&self) -> Result<&'static str, ()> {
+            Ok("users")
+        }
+    }
+}
+
+fn web() -> impl TestHttpService<RequestBody = String> {
+    use tower_service::NewService;
+
+    ::tower_web::ServiceBuilder::new()
+        .mount_service("/legacy", Legacy)
+        .resource(Users)
+        .build_new_service()
+        .new_service()
+        .wait().unwrap()
+}
+
+#[test]
+fn strip_prefix() {
+    let mut web = web();
+
+    let response = web.call_unwrap(get!("/legacy/a/b?c=d"));
+    assert_ok!(response);
+    assert_body!(response, "GET /a/b?c=d ");
+
+    let response = web.call_unwrap(get!("/legacy"));
+    assert_ok!(response);
+    assert_body!(response, "GET / ");
+}
+
+#[test]
+fn prefix_captures() {
+    use tower_service::NewService;
+
+    let mut web = ::tower_web::ServiceBuilder::new()
+        .mount_service("/api/:version", Legacy)
+        .build_new_service()
+        .new_service()
+        .wait().unwrap();
+
+    let response = web.call_unwrap(get!("/api/v1/a/b"));
+    assert_ok!(response);
+    assert_body!(response, "GET /a/b ");
+
+    let response = web.call_unwrap(get!("/api/v1"));
+    assert_ok!(response);
+    assert_body!(response, "GET / ");
+}
+
+#[test]
+fn forward_body() {
+    let mut web = web();
+
+    let response = web.call_unwrap(post!("/legacy/submit", "hello world"));
+    assert_ok!(response);
+    assert_body!(response, "POST /submit hello world");
+}
+
+#[test]
+fn resources_take_precedence() {
+    let mut web = web();
+
+    let response = web.call_unwrap(get!("/legacy/users"));
+    assert_ok!(response);
+    assert_body!(response, "users");
+
+    let response = web.call_unwrap(get!("/other"));
+    assert_not_found!(response);
+}
+
+#[test]
+fn service_error() {
+    let mut web = web();
+
+    let response = web.call_unwrap(get!("/legacy/fail"));
+    assert_internal_error!(response);
+}
@@ -0,0 +1,229 @@
+use futures::future::poll_fn;
+use futures::{Future, Stream, try_ready};
+use tower_web::extract::multipart::{Multipart, TempFile};
+use tower_web::{Deserialize, Extract, impl_web};
+
+use std::io::Read;
+
+mod support;
+use crate::support::*;
+
+#[derive(Clone, Debug)]
+struct Uploads;
+
+#[derive(Debug, Extract)]
+struct Upload {
+    title: String,
+    count: u32,
+    file: TempFile,
+    attachment: Option<TempFile>,
+}
+
+impl_web! {
+    impl Uploads {
+        #[post("/upload")]
+        fn upload(&self, body: Upload) -> Result<String, ()> {
+            let mut file = body.file;
+            let mut contents = String::new();
+            file.read_to_string(&mut contents).unwrap();
+
+            Ok(format!("{}; {}; {:?}; {:?}; {}; {}; {}",
+                       body.title,
+                       body.count,
+                       file.filename(),
+                       file.content_type(),
+                       file.path().is_some(),
+                       contents.len(),
+                       body.attachment.is_some()))
+        }
+
+        #[post("/parts")]
+        fn parts(&self, body: Multipart) -> impl Future<Item = String, Error = ()> {
+            let mut body = body;
+            let mut parts = vec![];
+            let mut current: Option<(String, String)> = None;
+
+            poll_fn(move || loop {
+                if current.is_some() {
+                    match try_ready!(body.poll_data()) {
+                        Some(data) => {
+                            let contents = &mut current.as_mut().unwrap().1;
+                            contents.push_str(std::str::from_utf8(&data).unwrap());
+                        }
+                        None => {
+                            let (name, contents) = current.take().unwrap();
+                            parts.push(format!("{}={}", name, contents));
+                        }
+                    }
+                } else if let Some(part) = try_ready!(body.poll()) {
+                    let name = format!("{}:{}",
+                                       part.name().unwrap_or(""),
+                                       part.filename().unwrap_or(""));
+                    current = Some((name, String::new()));
+                } else {
+                    return Ok(parts.join("&").into());
+                }
+            })
+            .map_err(|_: tower_web::extract::Error| ())
+        }
+
+        #[post("/limited")]
+        #[web(body_limit = "256")]
+        fn limited(&self, body: Multipart) -> impl Future<Item = String, Error = ()> {
+            body.for_each(|_| Ok(())).then(|res| {
+                Ok(match res {
+                    Ok(()) => "ok".to_string(),
+                    Err(err) => format!("error {}", tower_web::Error::from(err).status_code()),
+                })
+            })
+        }
+
+        #[post("/misnamed")]
+        fn misnamed(&self, form: Multipart) -> impl Future<Item = String, Error = ()> {
+            form.for_each(|_| Ok(()))
+                .map(|_| "ok".to_string())
+                .map_err(|_| ())
+        }
+    }
+}
+
+const CONTENT_TYPE: &str = "multipart/form-data; boundary=XyZ";
+
+fn form(parts: &[(&str, Option<&str>, &str)]) -> String {
+    let mut body = "preamble\r\n".to_string();
+
+    for &(name, filename, contents) in parts {
+        body.push_str("--XyZ\r\n");
+
+        match filename {
+            Some(filename) => {
+                body.push_str(&format!(
+                    "Content-Disposition: form-data; name=\"{}\"; filename=\"{}\"\r\n\
+                     Content-Type: text/plain\r\n", name, filename));
+            }
+            None => {
+                body.push_str(&format!(
+                    "Content-Disposition: form-data; name=\"{}\"\r\n", name));
+            }
+        }
+
+        body.push_str("\r\n");
+        body.push_str(contents);
+        body.push_str("\r\n");
+    }
+
+    body.push_str("--XyZ--\r\n");
+    body
+}
+
+#[test]
+fn stream_parts() {
+    let mut web = service(Uploads);
+
+    let body = form(&[
+        ("title", None, "hello"),
+        ("file", Some("a.txt"), "line one\r\nline two"),
+        ("empty", None, ""),
+    ]);
+
+    let response = web.call_unwrap(post!("/parts", body, "content-type": CONTENT_TYPE));
+    assert_ok!(response);
+    assert_body!(response, "title:=hello&file:a.txt=line one\r\nline two&empty:=");
+}
+
+#[test]
+fn derive_extract() {
+    let mut web = service(Uploads);
+
+    let body = form(&[
+        ("title", None, "hello"),
+        ("count", None, "3"),
+        ("file", Some("a.txt"), "line one\r\nline two"),
+    ]);
+
+    let response = web.call_unwrap(post!("/upload", body, "content-type": CONTENT_TYPE));
+    assert_ok!(response);
+    assert_body!(response, "hello; 3; Some(\"a.txt\"); Some(\"text/plain\"); false; 18; false");
+}
+
+#[test]
+fn spool_large_file() {
+    let mut web = service(Uploads);
+
+    let contents = "x".repeat(512 * 1024);
+    let body = form(&[
+        ("title", None, "big"),
+        ("count", None, "1"),
+        ("file", Some("big.txt"), &contents),
+        ("attachment", Some("small.txt"), "small"),
+    ]);
+
+    let response = web.call_unwrap(post!("/upload", body, "content-type": CONTENT_TYPE));
+    assert_ok!(response);
+    assert_body!(response, "big; 1; Some(\"big.txt\"); Some(\"text/plain\"); true; 524288; true");
+}
+
+#[test]
+fn invalid_form() {
+    let mut web = service(Uploads);
+
+    // Missing field
+    let body = form(&[("title", None, "hello"), ("count", None, "3")]);
+    let response = web.call_unwrap(post!("/upload", body, "content-type": CONTENT_TYPE));
+    assert_eq!(response.status(), http::StatusCode::UNPROCESSABLE_ENTITY);
+    assert_body!(response, r#"{"type":"about:blank","title":"Unprocessable Entity","detail":"missing field `file`"}"#);
+
+    // File given for a text field
+    let body = form(&[
+        ("title", Some("a.txt"), "hello"),
+        ("count", None, "3"),
+        ("file", Some("a.txt"), "data"),
+    ]);
+    let response = web.call_unwrap(post!("/upload", body, "content-type": CONTENT_TYPE));
+    assert_eq!(response.status(), http::StatusCode::UNPROCESSABLE_ENTITY);
+    assert_body!(response, r#"{"type":"about:blank","title":"Unprocessable Entity","detail":"title: expected text, found a file"}"#);
+
+    // Value that does not match the field type
+    let body = form(&[
+        ("title", None, "hello"),
+        ("count", None, "three"),
+        ("file", Some("a.txt"), "data"),
+    ]);
+    let response = web.call_unwrap(post!("/upload", body, "content-type": CONTENT_TYPE));
+    assert_eq!(response.status(), http::StatusCode::UNPROCESSABLE_ENTITY);
+    assert_body!(response, r#"{"type":"about:blank","title":"Unprocessable Entity","detail":"count: cannot parse u32: invalid digit found in string"}"#);
+
+    // Truncated body
+    let body = "--XyZ\r\nContent-Disposition: form-data; name=\"title\"\r\n\r\nhel";
+    let response = web.call_unwrap(post!("/upload", body, "content-type": CONTENT_TYPE));
+    assert_bad_request!(response);
+
+    // Not multipart
+    let response = web.call_unwrap(post!("/parts", "hello", "content-type": "text/plain"));
+    assert_eq!(response.status(), http::StatusCode::UNSUPPORTED_MEDIA_TYPE);
+    assert_header!(response, "accept-post", "multipart/form-data");
+}
+
+#[test]
+fn stream_parts_limit() {
+    let mut web = service(Uploads);
+
+    let body = form(&[("title", None, "hello")]);
+    let response = web.call_unwrap(post!("/limited", body, "content-type": CONTENT_TYPE));
+    assert_ok!(response);
+    assert_body!(response, "ok");
+
+    let body = form(&[("title", None, &"x".repeat(300))]);
+    let response = web.call_unwrap(post!("/limited", body, "content-type": CONTENT_TYPE));
+    assert_ok!(response);
+    assert_body!(response, "error 413 Payload Too Large");
+}
+
+#[test]
+fn stream_parts_not_named_body() {
+    let mut web = service(Uploads);
+
+    let body = form(&[("title", None, "hello")]);
+    let response = web.call_unwrap(post!("/misnamed", body, "content-type": CONTENT_TYPE));
+    assert_internal_error!(response);
+}
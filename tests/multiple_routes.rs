@@ -0,0 +1,58 @@
+use tower_web::impl_web;
+
+mod support;
+use crate::support::*;
+
+#[derive(Clone, Debug)]
+struct TestMultipleRoutes;
+
+impl_web! {
+    impl TestMultipleRoutes {
+        #[get("/")]
+        #[get("/index.html")]
+        fn index(&self) -> Result<&'static str, ()> {
+            Ok("index")
+        }
+
+        #[get("/users/:id")]
+        #[post("/users/:id")]
+        #[route(method = "PROPFIND", path = "/u/:id")]
+        fn user(&self, id: u32) -> Result<String, ()> {
+            Ok(format!("user {}", id))
+        }
+    }
+}
+
+#[test]
+fn multiple_paths() {
+    let mut web = service(TestMultipleRoutes);
+
+    for path in &["/", "/index.html"] {
+        let response = web.call_unwrap(get!(*path));
+        assert_ok!(response);
+        assert_body!(response, "index");
+    }
+}
+
+#[test]
+fn multiple_methods() {
+    let mut web = service(TestMultipleRoutes);
+
+    let response = web.call_unwrap(get!("/users/1"));
+    assert_ok!(response);
+    assert_body!(response, "user 1");
+
+    let response = web.call_unwrap(post!("/users/2", ""));
+    assert_ok!(response);
+    assert_body!(response, "user 2");
+
+    let request = ::http::Request::builder()
+        .method("PROPFIND")
+        .uri("/u/3")
+        .body("".to_string())
+        .unwrap();
+
+    let response = web.call_unwrap(request);
+    assert_ok!(response);
+    assert_body!(response, "user 3");
+}
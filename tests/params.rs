@@ -29,6 +29,36 @@ impl_web! {
             Ok("one_u32_param")
         }
 
+        #[get("/items/:id<u32>")]
+        #[content_type("plain")]
+        fn constrained_u32_param(&self, id: u32) -> Result<String, ()> {
+            Ok(format!("constrained_u32_param - {}", id))
+        }
+
+        #[get("/items/:slug<[a-z-]+>")]
+        #[content_type("plain")]
+        fn constrained_regex_param(&self, slug: String) -> Result<String, ()> {
+            Ok(format!("constrained_regex_param - {}", slug))
+        }
+
+        #[get("/items/*rest")]
+        #[content_type("plain")]
+        fn constrained_fallback(&self) -> Result<&'static str, ()> {
+            Ok("constrained_fallback")
+        }
+
+        #[get("/files/:name.:ext")]
+        #[content_type("plain")]
+        fn pattern_params(&self, name: String, ext: String) -> Result<String, ()> {
+            Ok(format!("pattern_params - {} {}", name, ext))
+        }
+
+        #[get("/v:version<u32>/users/@:user")]
+        #[content_type("plain")]
+        fn prefixed_params(&self, version: u32, user: String) -> Result<String, ()> {
+            Ok(format!("prefixed_params - {} {}", version, user))
+        }
+
         #[post("/content_length")]
         #[content_type("plain")]
         fn one_u32_header(&self, content_length: u32) -> Result<&'static str, ()> {
@@ -71,6 +101,47 @@ fn one_str_header() {
     assert_body!(response, "one_str_header");
 }
 
+#[test]
+fn constrained_params() {
+    let mut web = service(TestParams);
+
+    let response = web.call_unwrap(get!("/items/123"));
+    assert_ok!(response);
+    assert_body!(response, "constrained_u32_param - 123");
+
+    let response = web.call_unwrap(get!("/items/hello-world"));
+    assert_ok!(response);
+    assert_body!(response, "constrained_regex_param - hello-world");
+
+    // Does not satisfy either constraint, falls through to the glob
+    let response = web.call_unwrap(get!("/items/99999999999"));
+    assert_ok!(response);
+    assert_body!(response, "constrained_fallback");
+
+    let response = web.call_unwrap(get!("/items/Hello"));
+    assert_ok!(response);
+    assert_body!(response, "constrained_fallback");
+}
+
+#[test]
+fn pattern_params() {
+    let mut web = service(TestParams);
+
+    let response = web.call_unwrap(get!("/files/archive.tar.gz"));
+    assert_ok!(response);
+    assert_body!(response, "pattern_params - archive.tar gz");
+
+    let response = web.call_unwrap(get!("/files/archive"));
+    assert_not_found!(response);
+
+    let response = web.call_unwrap(get!("/v2/users/@carllerche"));
+    assert_ok!(response);
+    assert_body!(response, "prefixed_params - 2 carllerche");
+
+    let response = web.call_unwrap(get!("/v2/users/carllerche"));
+    assert_not_found!(response);
+}
+
 #[test]
 fn one_u32_param() {
     let mut web = service(TestParams);
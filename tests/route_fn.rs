@@ -0,0 +1,114 @@
+use http::Method;
+use tower_web::impl_web;
+
+mod support;
+use crate::support::*;
+
+#[derive(Clone, Debug)]
+struct Users;
+
+impl_web! {
+    impl Users {
+        #[get("/users")]
+        fn index(&self) -> Result<&'static str, ()> {
+            Ok("users")
+        }
+    }
+}
+
+fn web() -> impl TestHttpService<RequestBody = String> {
+    use tower_service::NewService;
+
+    ::tower_web::ServiceBuilder::new()
+        .resource(Users)
+        .route(Method::GET, "/", || "index")
+        .route(Method::GET, "/users/:id", |id: u32| format!("user {}", id))
+        .route(Method::GET, "/users/:id/posts/:post", |id: u32, post: String| {
+            format!("user {}; post {}", id, post)
+        })
+        .route(Method::POST, "/users/:id/posts", |id: u32, body: String| {
+            format!("user {}; body {}", id, body)
+        })
+        .route(Method::GET, "/buggy", || -> Result<String, http::StatusCode> {
+            Err(http::StatusCode::INTERNAL_SERVER_ERROR)
+        })
+        .build_new_service()
+        .new_service()
+        .wait().unwrap()
+}
+
+#[test]
+fn no_arguments() {
+    let mut web = web();
+
+    let response = web.call_unwrap(get!("/"));
+    assert_ok!(response);
+    assert_body!(response, "index");
+}
+
+#[test]
+fn captures() {
+    let mut web = web();
+
+    let response = web.call_unwrap(get!("/users/12"));
+    assert_ok!(response);
+    assert_header!(response, "content-type", "text/plain");
+    assert_body!(response, "user 12");
+
+    let response = web.call_unwrap(get!("/users/12/posts/hello"));
+    assert_ok!(response);
+    assert_body!(response, "user 12; post hello");
+}
+
+#[test]
+fn body() {
+    let mut web = web();
+
+    let response = web.call_unwrap(post!("/users/12/posts", "hello world"));
+    assert_ok!(response);
+    assert_body!(response, "user 12; body hello world");
+}
+
+#[test]
+fn with_resources() {
+    let mut web = web();
+
+    let response = web.call_unwrap(get!("/users"));
+    assert_ok!(response);
+    assert_body!(response, "users");
+
+    let response = web.call_unwrap(get!("/nope"));
+    assert_not_found!(response);
+}
+
+#[test]
+fn catch() {
+    use tower_service::NewService;
+
+    let mut web = ::tower_web::ServiceBuilder::new()
+        .route(Method::GET, "/buggy", || -> Result<String, http::StatusCode> {
+            Err(http::StatusCode::INTERNAL_SERVER_ERROR)
+        })
+        .catch(|_: &http::Request<()>, error: tower_web::Error| {
+            assert_eq!(error.status_code(), http::StatusCode::INTERNAL_SERVER_ERROR);
+
+            let response = http::response::Builder::new()
+                .status(200)
+                .body("caught")
+                .unwrap();
+
+            Ok(response)
+        })
+        .build_new_service()
+        .new_service()
+        .wait().unwrap();
+
+    let response = web.call_unwrap(get!("/buggy"));
+    assert_ok!(response);
+    assert_body!(response, "caught");
+
+    let mut web = self::web();
+
+    let response = web.call_unwrap(get!("/buggy"));
+    assert_internal_error!(response);
+}
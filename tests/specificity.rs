@@ -0,0 +1,97 @@
+use tower_web::impl_web;
+
+mod support;
+use crate::support::*;
+
+#[derive(Clone, Debug)]
+struct Users;
+
+#[derive(Clone, Debug)]
+struct Me;
+
+#[derive(Clone, Debug)]
+struct OtherUsers;
+
+impl_web! {
+    impl Users {
+        #[get("/*path")]
+        fn fallback(&self, path: String) -> Result<String, ()> {
+            Ok(format!("fallback {}", path))
+        }
+
+        #[get("/users/:id")]
+        fn user(&self, id: String) -> Result<String, ()> {
+            Ok(format!("user {}", id))
+        }
+    }
+
+    impl Me {
+        #[get("/users/me")]
+        fn me(&self) -> Result<&'static str, ()> {
+            Ok("me")
+        }
+    }
+
+    impl OtherUsers {
+        #[get("/users/:user_id")]
+        fn user(&self, user_id: String) -> Result<String, ()> {
+            Ok(format!("other user {}", user_id))
+        }
+    }
+}
+
+#[test]
+fn literal_beats_capture_beats_glob() {
+    use tower_service::NewService;
+
+    let mut web = ::tower_web::ServiceBuilder::new()
+        .resource(Users)
+        .resource(Me)
+        .build_new_service()
+        .new_service()
+        .wait().unwrap();
+
+    let response = web.call_unwrap(get!("/users/me"));
+    assert_ok!(response);
+    assert_body!(response, "me");
+
+    let response = web.call_unwrap(get!("/users/12"));
+    assert_ok!(response);
+    assert_body!(response, "user 12");
+
+    let response = web.call_unwrap(get!("/users/12/posts"));
+    assert_ok!(response);
+    assert_body!(response, "fallback users/12/posts");
+}
+
+#[test]
+#[should_panic(expected = "`GET /users/:user_id -> OtherUsers::user` conflicts with `GET /users/:id -> Users::user`")]
+fn conflicting_resources() {
+    ::tower_web::ServiceBuilder::new()
+        .resource(Users)
+        .resource(OtherUsers)
+        .build_new_service::<String>();
+}
+
+#[test]
+fn conflicting_resources_error() {
+    let err = ::tower_web::ServiceBuilder::new()
+        .resource(Users)
+        .resource(OtherUsers)
+        .try_build_new_service::<String>()
+        .err().unwrap();
+
+    assert_eq!(err.conflicts(), &[(
+        "GET /users/:id -> Users::user".to_string(),
+        "GET /users/:user_id -> OtherUsers::user".to_string(),
+    )][..]);
+}
+
+#[test]
+fn no_conflicts() {
+    assert!(::tower_web::ServiceBuilder::new()
+        .resource(Users)
+        .resource(Me)
+        .try_build_new_service::<String>()
+        .is_ok());
+}
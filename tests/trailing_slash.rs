@@ -0,0 +1,106 @@
+use tower_web::impl_web;
+use tower_web::routing::TrailingSlash;
+
+mod support;
+use crate::support::*;
+
+#[derive(Clone, Debug)]
+struct TestTrailingSlash;
+
+impl_web! {
+    impl TestTrailingSlash {
+        #[get("/a/b")]
+        fn a_b(&self) -> Result<&'static str, ()> {
+            Ok("a b")
+        }
+
+        #[get("/dir/")]
+        fn dir(&self) -> Result<&'static str, ()> {
+            Ok("dir")
+        }
+
+        #[get("/files/:name")]
+        fn file(&self, name: String) -> Result<String, ()> {
+            Ok(format!("file {}", name))
+        }
+    }
+}
+
+fn service_with(trailing_slash: TrailingSlash) -> impl TestHttpService<RequestBody = String> {
+    use tower_service::NewService;
+
+    ::tower_web::ServiceBuilder::new()
+        .resource(TestTrailingSlash)
+        .trailing_slash(trailing_slash)
+        .build_new_service()
+        .new_service()
+        .wait().unwrap()
+}
+
+#[test]
+fn lenient() {
+    let mut web = service(TestTrailingSlash);
+
+    for path in &["/a/b", "/a/b/", "/a//b/", "/a/%62", "/a/./c/../b"] {
+        let response = web.call_unwrap(get!(*path));
+        assert_ok!(response);
+        assert_body!(response, "a b");
+    }
+
+    let response = web.call_unwrap(get!("/dir"));
+    assert_ok!(response);
+    assert_body!(response, "dir");
+
+    // Reserved characters stay encoded
+    let response = web.call_unwrap(get!("/files/x%2Fy"));
+    assert_ok!(response);
+    assert_body!(response, "file x/y");
+
+    let response = web.call_unwrap(get!("/files/"));
+    assert_not_found!(response);
+}
+
+#[test]
+fn strict() {
+    let mut web = service_with(TrailingSlash::Strict);
+
+    for path in &["/a/b", "/a//b", "/a/%62"] {
+        let response = web.call_unwrap(get!(*path));
+        assert_ok!(response);
+        assert_body!(response, "a b");
+    }
+
+    let response = web.call_unwrap(get!("/a/b/"));
+    assert_not_found!(response);
+
+    let response = web.call_unwrap(get!("/dir/"));
+    assert_ok!(response);
+    assert_body!(response, "dir");
+
+    let response = web.call_unwrap(get!("/dir"));
+    assert_not_found!(response);
+}
+
+#[test]
+fn redirect() {
+    let mut web = service_with(TrailingSlash::Redirect);
+
+    let response = web.call_unwrap(get!("/a/b"));
+    assert_ok!(response);
+    assert_body!(response, "a b");
+
+    let response = web.call_unwrap(get!("/a/b/"));
+    assert_eq!(response.status(), ::http::StatusCode::PERMANENT_REDIRECT);
+    assert_header!(response, "location", "/a/b");
+
+    let response = web.call_unwrap(get!("/dir?q=1"));
+    assert_eq!(response.status(), ::http::StatusCode::PERMANENT_REDIRECT);
+    assert_header!(response, "location", "/dir/?q=1");
+
+    let response = web.call_unwrap(get!("/a//%62"));
+    assert_eq!(response.status(), ::http::StatusCode::PERMANENT_REDIRECT);
+    assert_header!(response, "location", "/a/b");
+
+    let response = web.call_unwrap(get!("/nope/"));
+    assert_not_found!(response);
+}
@@ -0,0 +1,150 @@
+use tower_web::impl_web;
+use tower_web::routing::Urls;
+
+mod support;
+use crate::support::*;
+
+#[derive(Clone, Debug)]
+struct Users;
+
+#[derive(Clone, Debug)]
+struct Files;
+
+impl_web! {
+    impl Users {
+        #[get("/users/:id")]
+        fn show_user(&self, id: u32) -> Result<String, ()> {
+            Ok(format!("user {}", id))
+        }
+
+        #[post("/users")]
+        fn create_user(&self, urls: Urls) -> Result<http::Response<&'static str>, tower_web::Error> {
+            let location = urls.url("show_user", &[("id", "42")])?;
+
+            Ok(http::Response::builder()
+                .status(201)
+                .header("location", &location[..])
+                .body("")
+                .unwrap())
+        }
+
+        #[get("/broken")]
+        fn broken(&self, urls: Urls) -> Result<String, tower_web::Error> {
+            Ok(urls.url("show_user", &[])?)
+        }
+    }
+
+    impl Files {
+        #[get("/files/*path")]
+        #[web(name = "file")]
+        fn show_file(&self, urls: Urls) -> Result<String, ()> {
+            let url = urls.url("file", &[("path", "docs/hello world.txt")]).unwrap();
+            Ok(url)
+        }
+    }
+}
+
+#[test]
+fn location_from_route_name() {
+    let mut web = service(Users);
+
+    let response = web.call_unwrap(post!("/users", ""));
+    assert_eq!(response.status(), 201);
+    assert_header!(response, "location", "/users/42");
+}
+
+#[test]
+fn missing_capture() {
+    let mut web = service(Users);
+
+    let response = web.call_unwrap(get!("/broken"));
+    assert_eq!(response.status(), 500);
+}
+
+#[test]
+fn custom_name_and_mount() {
+    use tower_service::NewService;
+
+    let mut web = ::tower_web::ServiceBuilder::new()
+        .mount("/static", Files)
+        .build_new_service()
+        .new_service()
+        .wait().unwrap();
+
+    let response = web.call_unwrap(get!("/static/files/a"));
+    assert_ok!(response);
+    assert_body!(response, "/static/files/docs/hello%20world.txt");
+}
+
+#[derive(Clone, Debug)]
+struct Posts;
+
+#[derive(Clone, Debug)]
+struct Comments;
+
+#[derive(Clone, Debug)]
+struct Articles;
+
+impl_web! {
+    impl Posts {
+        #[get("/posts")]
+        fn index(&self, urls: Urls) -> Result<String, tower_web::Error> {
+            Ok(urls.url("index", &[])?)
+        }
+
+        #[get("/posts/:id")]
+        #[web(name = "post")]
+        fn show_post(&self, urls: Urls) -> Result<String, tower_web::Error> {
+            Ok(urls.url("Comments::index", &[])?)
+        }
+    }
+
+    impl Comments {
+        #[get("/comments")]
+        fn index(&self) -> Result<&'static str, ()> {
+            Ok("comments")
+        }
+    }
+
+    impl Articles {
+        #[get("/articles/:id")]
+        #[web(name = "post")]
+        fn show_article(&self, id: u32) -> Result<String, ()> {
+            Ok(format!("article {}", id))
+        }
+    }
+}
+
+#[test]
+fn ambiguous_default_name() {
+    use tower_service::NewService;
+
+    let mut web = ::tower_web::ServiceBuilder::new()
+        .resource(Posts)
+        .resource(Comments)
+        .build_new_service()
+        .new_service()
+        .wait().unwrap();
+
+    let response = web.call_unwrap(get!("/posts"));
+    assert_internal_error!(response);
+
+    let response = web.call_unwrap(get!("/posts/1"));
+    assert_ok!(response);
+    assert_body!(response, "/comments");
+}
+
+#[test]
+fn duplicate_names() {
+    let err = ::tower_web::ServiceBuilder::new()
+        .resource(Posts)
+        .resource(Articles)
+        .try_build_new_service::<String>()
+        .err().unwrap();
+
+    assert!(err.conflicts().is_empty());
+    assert_eq!(err.duplicate_names(), ["post"]);
+    assert_eq!(err.to_string(), "duplicate route names:\n  \
+        `post` names both `GET /posts/:id -> Posts::show_post` and \
+        `GET /articles/:id -> Articles::show_article`");
+}
@@ -0,0 +1,156 @@
+use tower_web::impl_web;
+
+mod support;
+use crate::support::*;
+
+#[derive(Clone, Debug)]
+struct Api;
+
+#[derive(Clone, Debug)]
+struct Tenants;
+
+#[derive(Clone, Debug)]
+struct Fallback;
+
+impl_web! {
+    impl Api {
+        #[get("/")]
+        fn index(&self) -> Result<&'static str, ()> {
+            Ok("api")
+        }
+    }
+
+    impl Tenants {
+        #[get("/")]
+        fn index(&self, tenant: String) -> Result<String, ()> {
+            Ok(format!("tenant {}", tenant))
+        }
+
+        #[get("/users/:id")]
+        fn user(&self, tenant: String, id: u32) -> Result<String, ()> {
+            Ok(format!("tenant {}; user {}", tenant, id))
+        }
+    }
+
+    impl Fallback {
+        #[get("/")]
+        fn index(&self) -> Result<&'static str, ()> {
+            Ok("fallback")
+        }
+
+        #[get("/health")]
+        fn health(&self) -> Result<&'static str, ()> {
+            Ok("ok")
+        }
+    }
+}
+
+fn web() -> impl TestHttpService<RequestBody = String> {
+    use tower_service::NewService;
+
+    ::tower_web::ServiceBuilder::new()
+        .host("api.example.com", Api)
+        .host(":tenant.example.com", Tenants)
+        .resource(Fallback)
+        .build_new_service()
+        .new_service()
+        .wait().unwrap()
+}
+
+#[test]
+fn select_by_host() {
+    let mut web = web();
+
+    let response = web.call_unwrap(get!("/", "host": "api.example.com"));
+    assert_ok!(response);
+    assert_body!(response, "api");
+
+    let response = web.call_unwrap(get!("/", "host": "API.example.com:8080"));
+    assert_ok!(response);
+    assert_body!(response, "api");
+
+    let response = web.call_unwrap(get!("/", "host": "www.example.org"));
+    assert_ok!(response);
+    assert_body!(response, "fallback");
+
+    let response = web.call_unwrap(get!("/"));
+    assert_ok!(response);
+    assert_body!(response, "fallback");
+
+    // Routes not bound to a host are served for every host
+    let response = web.call_unwrap(get!("/health", "host": "api.example.com"));
+    assert_ok!(response);
+    assert_body!(response, "ok");
+}
+
+#[test]
+fn host_captures() {
+    let mut web = web();
+
+    let response = web.call_unwrap(get!("/", "host": "acme.example.com"));
+    assert_ok!(response);
+    assert_body!(response, "tenant acme");
+
+    let response = web.call_unwrap(get!("/users/12", "host": "acme.example.com"));
+    assert_ok!(response);
+    assert_body!(response, "tenant acme; user 12");
+
+    let response = web.call_unwrap(get!("/users/12", "host": "www.example.org"));
+    assert_not_found!(response);
+}
+
+#[test]
+fn wildcard_host() {
+    use tower_service::NewService;
+
+    let mut web = ::tower_web::ServiceBuilder::new()
+        .host("*.example.com", Api)
+        .build_new_service()
+        .new_service()
+        .wait().unwrap();
+
+    let response = web.call_unwrap(get!("/", "host": "v1.api.example.com"));
+    assert_ok!(response);
+    assert_body!(response, "api");
+
+    let response = web.call_unwrap(get!("/", "host": "example.com"));
+    assert_not_found!(response);
+}
+
+#[test]
+fn most_specific_host_first() {
+    use tower_service::NewService;
+
+    let mut web = ::tower_web::ServiceBuilder::new()
+        .host("*.example.com", Fallback)
+        .host(":tenant.example.com", Tenants)
+        .host("api.example.com", Api)
+        .build_new_service()
+        .new_service()
+        .wait().unwrap();
+
+    // Exact hosts win over captures and wildcards added before them
+    let response = web.call_unwrap(get!("/", "host": "api.example.com"));
+    assert_ok!(response);
+    assert_body!(response, "api");
+
+    let response = web.call_unwrap(get!("/", "host": "acme.example.com"));
+    assert_ok!(response);
+    assert_body!(response, "tenant acme");
+
+    let response = web.call_unwrap(get!("/", "host": "v1.api.example.com"));
+    assert_ok!(response);
+    assert_body!(response, "fallback");
+
+    // Less specific hosts are tried when the path is not found
+    let response = web.call_unwrap(get!("/users/12", "host": "api.example.com"));
+    assert_ok!(response);
+    assert_body!(response, "tenant api; user 12");
+
+    let response = web.call_unwrap(get!("/health", "host": "api.example.com"));
+    assert_ok!(response);
+    assert_body!(response, "ok");
+
+    let response = web.call_unwrap(get!("/users/12", "host": "v1.api.example.com"));
+    assert_not_found!(response);
+}
@@ -1,4 +1,4 @@
-use super::Attribute;
+use super::{attr, Attribute};
 
 use syn::{self, DeriveInput};
 use proc_macro2::{TokenStream, Span};
@@ -136,6 +136,9 @@ impl Extract {
             Data::Struct(ref data_struct) => {
                 from_shadow_struct(&self.ty, data_struct)
             }
+            Data::Enum(ref data_enum) => {
+                from_shadow_enum(&self.ty, &self.shadow_ty.ident, data_enum)
+            }
             _ => unimplemented!(),
         }
     }
@@ -178,6 +181,57 @@ fn from_shadow_struct(
     }
 }
 
+fn from_shadow_enum(
+    ty: &syn::Ident,
+    shadow_ty: &syn::Ident,
+    data_enum: &syn::DataEnum) -> TokenStream
+{
+    use syn::Fields;
+
+    let arms = data_enum.variants.iter()
+        .map(|variant| {
+            let ident = &variant.ident;
+
+            match variant.fields {
+                Fields::Named(ref fields) => {
+                    let idents: Vec<_> = fields.named.iter()
+                        .map(|f| f.ident.as_ref().unwrap())
+                        .collect();
+
+                    let fields_1 = &idents;
+                    let fields_2 = &idents;
+
+                    quote! {
+                        #shadow_ty::#ident { #(#fields_1,)* } => #ty::#ident { #(#fields_2,)* },
+                    }
+                }
+                Fields::Unnamed(ref fields) => {
+                    let idents: Vec<_> = (0..fields.unnamed.len())
+                        .map(|i| syn::Ident::new(&format!("f{}", i), Span::call_site()))
+                        .collect();
+
+                    let fields_1 = &idents;
+                    let fields_2 = &idents;
+
+                    quote! {
+                        #shadow_ty::#ident(#(#fields_1,)*) => #ty::#ident(#(#fields_2,)*),
+                    }
+                }
+                Fields::Unit => {
+                    quote! {
+                        #shadow_ty::#ident => #ty::#ident,
+                    }
+                }
+            }
+        });
+
+    quote! {
+        match shadow {
+            #(#arms)*
+        }
+    }
+}
+
 struct FoldShadowTy {
     /// Any error encountered
     err: Option<String>,
@@ -211,13 +265,42 @@ impl syn::fold::Fold for FoldShadowTy {
             assert!(field.ident.is_some(), "unimplemented: named fields with no name");
 
             let attrs = r#try!(Attribute::from_ast(&field.attrs));
+            let ident = field.ident.as_ref().unwrap().to_string();
+
+            // The name of the header the field is extracted from, when the
+            // value is extracted from the request headers.
+            let mut header = crate::header::arg_to_header_name(&ident);
+
+            for attr in attrs {
+                match attr.kind {
+                    attr::Kind::Header { name, value: None } => {
+                        if let Some(name) = name {
+                            header = name;
+                        }
+                    }
+                    _ => {
+                        let source = &attr.source;
+                        let actual = quote!(#source);
 
-            if attrs.is_empty() {
-                field.attrs.retain(is_serde_attr);
-                fields.named.push(field);
-            } else {
-                unimplemented!();
+                        self.err = Some(format!("invalid field level annotation. The attribute must be in one of \
+                                                 the following formats:\n\n\
+                                                 `#[web(header)]`\n\
+                                                 `#[web(header(name = \"x-foo\"))]`\n\n\
+                                                 Actual: {}", actual.to_string()));
+
+                        return fields;
+                    }
+                }
             }
+
+            field.attrs.retain(is_serde_attr);
+
+            if header.as_str() != ident {
+                let header = header.as_str();
+                field.attrs.push(syn::parse_quote!(#[serde(alias = #header)]));
+            }
+
+            fields.named.push(field);
         }
 
         fields
@@ -12,17 +12,27 @@ pub(crate) struct Arg {
     /// The index of the path binding the identifier matches.
     pub capture: Option<usize>,
 
+    /// Names of the route's path captures.
+    pub captures: Vec<String>,
+
     /// The argument type
     pub ty: Box<syn::Type>,
 }
 
 impl Arg {
     /// Create a new, regular, argument.
-    pub fn new(index: usize, ident: String, capture: Option<usize>, ty: Box<syn::Type>) -> Arg {
+    ///
+    /// `captures` are the names of the route's path captures.
+    pub fn new(index: usize, ident: String, captures: &[String], ty: Box<syn::Type>) -> Arg {
+        // Check if the identifier matches any captures
+        let capture = captures.iter()
+            .position(|capture| capture == &ident);
+
         Arg {
             index,
             ident: Some(ident),
             capture,
+            captures: captures.to_vec(),
             ty,
         }
     }
@@ -34,6 +44,7 @@ impl Arg {
             ty,
             ident: None,
             capture: None,
+            captures: vec![],
         }
     }
 
@@ -45,11 +56,15 @@ impl Arg {
             match &ident[..] {
                 "query_string" => quote! { __tw::codegen::CallSite::new_query_string() },
                 "body" => quote! { __tw::codegen::CallSite::new_body() },
-                header => {
-                    let header = crate::header::arg_to_header_name(header);
+                "captures" => {
+                    let names = &self.captures;
+                    quote! { __tw::codegen::CallSite::new_captures(&[#(#names),*]) }
+                }
+                param => {
+                    let header = crate::header::arg_to_header_name(param);
                     let header = header.as_str();
 
-                    quote! { __tw::codegen::CallSite::new_header(#header) }
+                    quote! { __tw::codegen::CallSite::new_param(#param, #header) }
                 }
             }
         } else {
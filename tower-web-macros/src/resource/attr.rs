@@ -5,15 +5,10 @@ use quote::ToTokens;
 
 #[derive(Debug)]
 pub(crate) struct Attributes {
-    pub method: Option<Method>,
+    /// Methods and paths matched by the handler, in declaration order
+    pub routes: Vec<RoutePath>,
 
-    /// HTTP path
-    pub path: Option<String>,
-
-    /// String literal version of the path
-    pub path_lit: Option<syn::LitStr>,
-
-    /// Path captures
+    /// Path captures, shared by all of the handler's paths
     pub path_captures: Vec<String>,
 
     /// Produced content-type
@@ -24,6 +19,33 @@ pub(crate) struct Attributes {
 
     /// Template
     template: Option<String>,
+
+    /// Path prefix applied to every route in an `impl` block
+    pub prefix: Option<String>,
+
+    /// Route name, used to build URLs
+    pub name: Option<String>,
+
+    /// Additional conditions the request must satisfy to match the route
+    pub guards: Vec<Guard>,
+
+    /// Maximum size of the request body, in bytes
+    pub body_limit: Option<u64>,
+
+    /// Media types accepted in the request body
+    pub consumes: Vec<String>,
+}
+
+/// A method and path matched by a route handler
+#[derive(Debug)]
+pub(crate) struct RoutePath {
+    pub method: Method,
+
+    /// HTTP path
+    pub path: String,
+
+    /// String literal version of the path
+    pub path_lit: syn::LitStr,
 }
 
 #[derive(Debug, PartialEq)]
@@ -33,6 +55,11 @@ pub(crate) enum Method {
     Put,
     Patch,
     Delete,
+    Head,
+    Options,
+    Trace,
+    /// An extension method, such as WebDAV's `PROPFIND`.
+    Other(String),
 }
 
 #[derive(Debug)]
@@ -40,16 +67,36 @@ enum Catch {
     All,
 }
 
+/// A route guard, declared with `#[web(guard(...))]`.
+#[derive(Debug, PartialEq)]
+pub(crate) struct Guard {
+    kind: GuardKind,
+
+    /// Value the header or query parameter must be set to
+    value: Option<String>,
+}
+
+#[derive(Debug, PartialEq)]
+enum GuardKind {
+    Header(String),
+    Accept(String),
+    Host(String),
+    Query(String),
+}
+
 impl Attributes {
     pub fn new() -> Attributes {
         Attributes {
-            method: None,
-            path: None,
-            path_lit: None,
+            routes: vec![],
             path_captures: vec![],
             content_type: None,
             catch: None,
             template: None,
+            prefix: None,
+            name: None,
+            guards: vec![],
+            body_limit: None,
+            consumes: vec![],
         }
     }
 
@@ -59,7 +106,7 @@ impl Attributes {
 
     /// Returns true if the method is a route handler
     pub fn is_route(&self) -> bool {
-        self.method.is_some()
+        !self.routes.is_empty()
     }
 
     /// Returns true if the method is a catch handler
@@ -67,17 +114,37 @@ impl Attributes {
         self.catch.is_some()
     }
 
-    fn set_method(&mut self, value: Method) {
-        assert!(self.method.is_none(), "unimplemented: dup method");
-        self.method = Some(value);
+    /// Returns true if only resource level attributes are set
+    pub fn is_resource_level(&self) -> bool {
+        self.is_empty() && self.content_type.is_none() && self.template.is_none() &&
+            self.name.is_none() && self.guards.is_empty() && self.body_limit.is_none() &&
+            self.consumes.is_empty()
     }
 
-    pub fn method_expr(&self) -> TokenStream {
-        self.method.as_ref().unwrap().to_tokens()
+    /// Returns true if both methods consume the same media types, in any
+    /// order.
+    pub fn same_consumes(&self, other: &Attributes) -> bool {
+        self.consumes.iter().all(|media_type| other.consumes.contains(media_type)) &&
+            other.consumes.iter().all(|media_type| self.consumes.contains(media_type))
     }
 
-    pub fn path_expr(&self) -> TokenStream {
-        self.path_lit.as_ref().unwrap().into_token_stream()
+    /// Prefix the route paths with `prefix`, as set on the `impl` block.
+    pub fn apply_prefix(&mut self, prefix: &str) {
+        let prefix = prefix.trim_end_matches('/');
+        let routes = std::mem::replace(&mut self.routes, vec![]);
+
+        for route in routes {
+            let lit = route.path_lit;
+
+            // The root path of a prefixed resource is the prefix itself.
+            let path = if lit.value() == "/" && !prefix.is_empty() {
+                prefix.to_string()
+            } else {
+                format!("{}{}", prefix, lit.value())
+            };
+
+            self.add_route(route.method, &syn::LitStr::new(&path, lit.span()));
+        }
     }
 
     pub fn template(&self) -> Option<&str> {
@@ -111,13 +178,14 @@ impl Attributes {
 
                 self.process_doc_rule(&raw);
             }
-            "get" | "post" | "put" | "patch" | "delete" | "content_type" | "catch" | "web" => {
+            "get" | "post" | "put" | "patch" | "delete" | "head" | "options" | "trace" | "route" |
+            "content_type" | "catch" | "web" => {
                 self.process_attr2(attr);
             }
             _ => return false,
         }
 
-        if self.method.is_some() && self.catch.is_some() {
+        if self.is_route() && self.catch.is_some() {
             panic!("catch handlers can not be routable");
         }
 
@@ -147,26 +215,32 @@ impl Attributes {
 
         match attr.parse_meta() {
             Ok(Meta::List(list)) => {
-                assert!(list.nested.len() == 1, "unimplemented: invalid route rule; list.nested.len() == 1");
-
                 // TODO: Should the identifier be lower cased?
                 let ident = list.path.get_ident().expect("invalid route rule");
 
+                if ident == "route" {
+                    self.process_route(&list);
+                    return;
+                }
+
+                assert!(list.nested.len() == 1, "unimplemented: invalid route rule; list.nested.len() == 1");
+
                 if ident == "get" {
-                    self.set_method(Method::Get);
-                    self.process_path(&list);
+                    self.process_path(Method::Get, &list);
                 } else if ident == "post" {
-                    self.set_method(Method::Post);
-                    self.process_path(&list);
+                    self.process_path(Method::Post, &list);
                 } else if ident == "put" {
-                    self.set_method(Method::Put);
-                    self.process_path(&list);
+                    self.process_path(Method::Put, &list);
                 } else if ident == "patch" {
-                    self.set_method(Method::Patch);
-                    self.process_path(&list);
+                    self.process_path(Method::Patch, &list);
                 } else if ident == "delete" {
-                    self.set_method(Method::Delete);
-                    self.process_path(&list);
+                    self.process_path(Method::Delete, &list);
+                } else if ident == "head" {
+                    self.process_path(Method::Head, &list);
+                } else if ident == "options" {
+                    self.process_path(Method::Options, &list);
+                } else if ident == "trace" {
+                    self.process_path(Method::Trace, &list);
                 } else if ident == "content_type" {
                     self.process_content_type(&list);
                 } else if ident == "catch" {
@@ -191,35 +265,79 @@ impl Attributes {
         }
     }
 
-    fn process_path(&mut self, list: &syn::MetaList) {
+    fn process_path(&mut self, method: Method, list: &syn::MetaList) {
         use syn::{Lit, NestedMeta};
 
         assert!(list.nested.len() == 1, "unimplemeneted: invalid route rule");
-        assert!(self.path.is_none(), "unimplemented: dup path");
 
         match list.nested.first().unwrap() {
-            NestedMeta::Lit(Lit::Str(lit)) => {
-                // Convert the path literal to a String
-                let path = lit.value();
-
-                // Figure out capture indices
-                //
-                // TODO: Validate capture format
-                self.path_captures = path.split("/")
-                    .filter(|segment| {
-                        let c = segment.chars().next();
-                        c == Some(':') || c == Some('*')
-                    })
-                    .map(|segment| segment[1..].to_string())
-                    .collect();
-
-                self.path = Some(path);
-                self.path_lit = Some(lit.clone());
-            }
+            NestedMeta::Lit(Lit::Str(lit)) => self.add_route(method, lit),
             _ => unimplemented!("unimplemented: invalid route rule"),
         }
     }
 
+    fn add_route(&mut self, method: Method, lit: &syn::LitStr) {
+        // Convert the path literal to a String
+        let path = lit.value();
+
+        // Figure out capture indices
+        let captures: Vec<_> = path.split("/")
+            .flat_map(|segment| parse_segment(segment).1)
+            .collect();
+
+        // Arguments are bound to captures by index, so every path must declare
+        // the same captures.
+        if self.routes.is_empty() {
+            self.path_captures = captures;
+        } else if self.path_captures != captures {
+            panic!("all paths of a handler must declare the same captures, in the same order; \
+                    path={:?}, captures={:?}, expected={:?}",
+                   path, captures, self.path_captures);
+        }
+
+        self.routes.push(RoutePath {
+            method,
+            path,
+            path_lit: lit.clone(),
+        });
+    }
+
+    /// Process a `#[route(method = "PROPFIND", path = "/foo")]` attribute.
+    fn process_route(&mut self, list: &syn::MetaList) {
+        use syn::{Lit, Meta, NestedMeta};
+
+        let mut method = None;
+        let mut path = None;
+
+        for meta in &list.nested {
+            let name_value = match *meta {
+                NestedMeta::Meta(Meta::NameValue(ref name_value)) => name_value,
+                _ => panic!("invalid `route` attribute; expected `#[route(method = \"..\", path = \"..\")]`"),
+            };
+
+            let lit = match name_value.lit {
+                Lit::Str(ref lit) => lit,
+                _ => panic!("invalid `route` attribute; values must be string literals"),
+            };
+
+            if name_value.path.is_ident("method") {
+                assert!(method.is_none(), "`route` method already set");
+                method = Some(Method::parse(&lit.value()));
+            } else if name_value.path.is_ident("path") {
+                assert!(path.is_none(), "`route` path already set");
+                path = Some(lit.clone());
+            } else {
+                panic!("invalid `route` attribute; unknown key `{}`",
+                       name_value.path.clone().into_token_stream());
+            }
+        }
+
+        let method = method.expect("`route` attribute requires a `method`");
+        let path = path.expect("`route` attribute requires a `path`");
+
+        self.add_route(method, &path);
+    }
+
     fn process_content_type(&mut self, list: &syn::MetaList) {
         use syn::{Lit, NestedMeta};
 
@@ -249,7 +367,31 @@ impl Attributes {
         for meta in &list.nested {
             match *meta {
                 NestedMeta::Meta(Meta::NameValue(ref name_value)) => {
-                    if name_value.path.is_ident("template") {
+                    if name_value.path.is_ident("prefix") {
+                        assert!(self.prefix.is_none(), "prefix already set");
+
+                        match name_value.lit {
+                            Lit::Str(ref lit_str) => {
+                                let prefix = lit_str.value();
+
+                                if !prefix.starts_with("/") {
+                                    panic!("prefix must start with `/`; prefix={:?}", prefix);
+                                }
+
+                                self.prefix = Some(prefix);
+                            }
+                            ref meta => unimplemented!("unsupported meta: {:?}", meta),
+                        }
+                    } else if name_value.path.is_ident("name") {
+                        assert!(self.name.is_none(), "name already set");
+
+                        match name_value.lit {
+                            Lit::Str(ref lit_str) => {
+                                self.name = Some(lit_str.value());
+                            }
+                            ref meta => unimplemented!("unsupported meta: {:?}", meta),
+                        }
+                    } else if name_value.path.is_ident("template") {
                         assert!(self.template.is_none(), "template already set");
 
                         match name_value.lit {
@@ -259,16 +401,120 @@ impl Attributes {
                             }
                             ref meta => unimplemented!("unsupported meta: {:?}", meta),
                         }
+                    } else if name_value.path.is_ident("consumes") {
+                        match name_value.lit {
+                            Lit::Str(ref lit_str) => {
+                                for media_type in lit_str.value().split(',') {
+                                    let media_type = media_type.trim();
+
+                                    if !media_type.contains('/') {
+                                        panic!("invalid `consumes` media type; media_type={:?}", media_type);
+                                    }
+
+                                    self.consumes.push(media_type.to_ascii_lowercase());
+                                }
+                            }
+                            ref meta => unimplemented!("unsupported meta: {:?}", meta),
+                        }
+                    } else if name_value.path.is_ident("body_limit") {
+                        assert!(self.body_limit.is_none(), "body_limit already set");
+
+                        let limit = match name_value.lit {
+                            Lit::Str(ref lit_str) => parse_size(&lit_str.value()),
+                            Lit::Int(ref lit_int) => lit_int.base10_parse().ok(),
+                            ref meta => unimplemented!("unsupported meta: {:?}", meta),
+                        };
+
+                        match limit {
+                            Some(limit) => self.body_limit = Some(limit),
+                            None => panic!("invalid `body_limit`; expected a size such as \"512KB\" or \"1MB\""),
+                        }
                     } else {
                         unimplemented!("unimplemented: invalid route rule");
                     }
                 }
+                NestedMeta::Meta(Meta::List(ref list)) if list.path.is_ident("guard") => {
+                    self.guards.push(Guard::parse(list));
+                }
                 _ => unimplemented!("unimplemented: invalid route rule"),
             }
         }
     }
 }
 
+/// Parse a size such as `"512"`, `"64KB"` or `"1 MB"` into a number of bytes.
+///
+/// Units are powers of 1024 and case insensitive.
+fn parse_size(s: &str) -> Option<u64> {
+    let s = s.trim();
+    let pos = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let (num, unit) = s.split_at(pos);
+
+    let num: u64 = num.parse().ok()?;
+
+    let shift = match &unit.trim().to_ascii_lowercase()[..] {
+        "" | "b" => 0,
+        "kb" | "kib" => 10,
+        "mb" | "mib" => 20,
+        "gb" | "gib" => 30,
+        _ => return None,
+    };
+
+    num.checked_mul(1 << shift)
+}
+
+/// Parse a path segment, returning the segment with capture names removed
+/// along with the capture names.
+///
+/// The segment syntax is defined by `tower_web_path::parse_segment`, which
+/// `tower-web` also uses to build the routes.
+fn parse_segment(segment: &str) -> (String, Vec<String>) {
+    use tower_web_path::{Part, Segment};
+
+    let parts = match tower_web_path::parse_segment(segment) {
+        Segment::Glob(name) => return ("*".to_string(), vec![name.to_string()]),
+        Segment::Parts(parts) => parts,
+    };
+
+    let mut pattern = String::new();
+    let mut names = vec![];
+
+    for part in parts {
+        match part {
+            Part::Literal(value) => pattern.push_str(value),
+            Part::Capture { name, constraint } => {
+                pattern.push(':');
+                names.push(name.to_string());
+
+                if let Some(constraint) = constraint {
+                    validate_constraint(constraint, segment);
+
+                    pattern.push('<');
+                    pattern.push_str(constraint);
+                    pattern.push('>');
+                }
+            }
+        }
+    }
+
+    (pattern, names)
+}
+
+/// Check that a capture constraint compiles, so that an invalid one is
+/// reported against the `route` attribute instead of when the service is
+/// built.
+///
+/// Primitive type names such as `u32` are valid regular expressions, so every
+/// constraint can be checked the same way.
+fn validate_constraint(constraint: &str, segment: &str) {
+    let anchored = format!("^(?:{})$", constraint);
+
+    if let Err(e) = regex::Regex::new(&anchored) {
+        panic!("invalid capture constraint `{}` in `route` attribute; segment={:?}; {}",
+               constraint, segment, e);
+    }
+}
+
 fn trim_at_prefix(s: &str) -> Option<&str> {
     for (i, b) in s.as_bytes().into_iter().enumerate() {
         match b {
@@ -283,9 +529,128 @@ fn trim_at_prefix(s: &str) -> Option<&str> {
     None
 }
 
+// ===== impl RoutePath =====
+
+impl RoutePath {
+    pub fn method_expr(&self) -> TokenStream {
+        self.method.to_tokens()
+    }
+
+    pub fn path_expr(&self) -> TokenStream {
+        self.path_lit.clone().into_token_stream()
+    }
+
+    /// The route path with capture names removed.
+    ///
+    /// Two routes with the same pattern match exactly the same requests, for
+    /// example `/users/:id<u32>` and `/users/:user_id<u32>`.
+    pub fn path_pattern(&self) -> String {
+        let mut path = &self.path[..];
+
+        if path.ends_with("/") {
+            path = &path[..path.len() - 1];
+        }
+
+        let segments: Vec<_> = path.split("/")
+            .map(|segment| parse_segment(segment).0)
+            .collect();
+
+        segments.join("/")
+    }
+}
+
+// ===== impl Guard =====
+
+impl Guard {
+    fn parse(list: &syn::MetaList) -> Guard {
+        use syn::{Lit, Meta, NestedMeta};
+
+        let mut kind = None;
+        let mut value = None;
+
+        for meta in &list.nested {
+            let name_value = match *meta {
+                NestedMeta::Meta(Meta::NameValue(ref name_value)) => name_value,
+                _ => panic!("guards must be of the form `guard(header = \"name\", value = \"value\")`"),
+            };
+
+            let lit = match name_value.lit {
+                Lit::Str(ref lit_str) => lit_str.value(),
+                ref meta => unimplemented!("unsupported meta: {:?}", meta),
+            };
+
+            let path = &name_value.path;
+            let key = quote!(#path).to_string();
+
+            if key == "value" {
+                assert!(value.is_none(), "guard value already set");
+                value = Some(lit);
+                continue;
+            }
+
+            assert!(kind.is_none(), "a guard must match a single header, accept, host or query");
+
+            kind = Some(match key.as_str() {
+                "header" => GuardKind::Header(lit),
+                "accept" => GuardKind::Accept(lit),
+                "host" => GuardKind::Host(lit),
+                "query" => GuardKind::Query(lit),
+                _ => panic!("unknown guard `{}`; expected header, accept, host or query", key),
+            });
+        }
+
+        let kind = kind.expect("a guard must match a header, accept, host or query");
+
+        match kind {
+            GuardKind::Accept(_) | GuardKind::Host(_) if value.is_some() => {
+                panic!("`value` is only supported on header and query guards");
+            }
+            _ => {}
+        }
+
+        Guard { kind, value }
+    }
+
+    pub fn to_tokens(&self) -> TokenStream {
+        use self::GuardKind::*;
+
+        match (&self.kind, &self.value) {
+            (Header(name), Some(value)) => quote!(__tw::routing::Guard::header_value(#name, #value)),
+            (Header(name), None) => quote!(__tw::routing::Guard::header(#name)),
+            (Accept(media_type), _) => quote!(__tw::routing::Guard::accept(#media_type)),
+            (Host(host), _) => quote!(__tw::routing::Guard::host(#host)),
+            (Query(name), Some(value)) => quote!(__tw::routing::Guard::query_value(#name, #value)),
+            (Query(name), None) => quote!(__tw::routing::Guard::query(#name)),
+        }
+    }
+}
+
 // ===== impl Method =====
 
 impl Method {
+    /// Parse a method name as used in the `route` attribute.
+    fn parse(value: &str) -> Method {
+        use self::Method::*;
+
+        match value {
+            "GET" => Get,
+            "POST" => Post,
+            "PUT" => Put,
+            "PATCH" => Patch,
+            "DELETE" => Delete,
+            "HEAD" => Head,
+            "OPTIONS" => Options,
+            "TRACE" => Trace,
+            _ => {
+                if http::Method::from_bytes(value.as_bytes()).is_err() {
+                    panic!("invalid HTTP method `{}` in `route` attribute", value);
+                }
+
+                Other(value.to_string())
+            }
+        }
+    }
+
     pub fn to_tokens(&self) -> TokenStream {
         use self::Method::*;
 
@@ -295,6 +660,12 @@ impl Method {
             Put => quote! { ::tower_web::codegen::http::Method::PUT },
             Patch => quote! { ::tower_web::codegen::http::Method::PATCH },
             Delete => quote! { ::tower_web::codegen::http::Method::DELETE },
+            Head => quote! { ::tower_web::codegen::http::Method::HEAD },
+            Options => quote! { ::tower_web::codegen::http::Method::OPTIONS },
+            Trace => quote! { ::tower_web::codegen::http::Method::TRACE },
+            Other(ref value) => quote! {
+                ::tower_web::codegen::http::Method::from_bytes(#value.as_bytes()).unwrap()
+            },
         }
     }
 }
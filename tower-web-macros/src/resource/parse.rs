@@ -60,7 +60,7 @@ impl ImplWeb {
 }
 
 impl syn::fold::Fold for ImplWeb {
-    fn fold_item_impl(&mut self, item: syn::ItemImpl) -> syn::ItemImpl {
+    fn fold_item_impl(&mut self, mut item: syn::ItemImpl) -> syn::ItemImpl {
         assert!(
             item.trait_.is_none(),
             "trait impls must not be in impl_web! block"
@@ -78,8 +78,23 @@ impl syn::fold::Fold for ImplWeb {
             }
         }
 
+        let mut attributes = Attributes::new();
+
+        // Every attribute is processed so misplaced route attributes are
+        // reported, but only the `#[web(...)]` attributes are consumed.
+        item.attrs.retain(|attr| {
+            let processed = attributes.process(attr);
+            !(processed && attr.path.is_ident("web"))
+        });
+
+        assert!(attributes.is_resource_level(),
+                "only `#[web(prefix = \"...\")]` is supported on impl blocks");
+
         let index = self.resources.len();
-        self.resources.push(Resource::new(index, &item));
+        let mut resource = Resource::new(index, &item);
+        resource.prefix = attributes.prefix;
+
+        self.resources.push(resource);
 
         syn::fold::fold_item_impl(self, item)
     }
@@ -91,11 +106,19 @@ impl syn::fold::Fold for ImplWeb {
 
         item.attrs.retain(|attr| !attributes.process(attr));
 
+        if attributes.prefix.is_some() {
+            panic!("`prefix` is only supported on impl blocks; {}", item.sig.ident);
+        }
+
         if attributes.is_empty() {
             // Not a web route, do no further processing.
             return item;
         }
 
+        if let Some(ref prefix) = self.resource().prefix {
+            attributes.apply_prefix(prefix);
+        }
+
         // Get the method name
         let ident = item.sig.ident.clone();
 
@@ -122,11 +145,7 @@ impl syn::fold::Fold for ImplWeb {
                             // Convert the identifier to a string
                             let ident = ident.ident.to_string();
 
-                            // Check if the identifier matches any captures
-                            let capture = attributes.path_captures.iter()
-                                .position(|capture| capture == &ident);
-
-                            args.push(Arg::new(index, ident, capture, arg.ty.clone()));
+                            args.push(Arg::new(index, ident, &attributes.path_captures, arg.ty.clone()));
                         }
                         _ => {
                             // In this case, we should proceed without
@@ -147,13 +166,24 @@ impl syn::fold::Fold for ImplWeb {
         if attributes.is_route() {
             // Prevent duplicate routes
             // It is possible path captures can conflict, such as '/:id' & '/12'
-            if let Some(_) = resource.routes.iter().map(|r| &r.attributes)
-                .position(|a| a.path == attributes.path &&
-                            a.method == attributes.method &&
-                            a.content_type == attributes.content_type) {
-                    // TODO: improve panic message via spans
-                    panic!("duplicate routes with method {:?}, path {:?}, content type {:?}:\n{:?}",
-                           attributes.method, attributes.path, attributes.content_type, item.sig.ident);
+            for (i, route) in attributes.routes.iter().enumerate() {
+                let pattern = route.path_pattern();
+
+                let others = resource.routes.iter()
+                    .map(|r| &r.attributes)
+                    .filter(|a| a.content_type == attributes.content_type &&
+                                a.guards == attributes.guards &&
+                                a.same_consumes(&attributes))
+                    .flat_map(|a| &a.routes)
+                    .chain(&attributes.routes[..i]);
+
+                for other in others {
+                    if other.method == route.method && other.path_pattern() == pattern {
+                        // TODO: improve panic message via spans
+                        panic!("duplicate routes with method {:?}, path {:?}, content type {:?}:\n{:?}",
+                               route.method, route.path, attributes.content_type, item.sig.ident);
+                    }
+                }
             }
 
             let route = Route::new(index, sig, attributes);
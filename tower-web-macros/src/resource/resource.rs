@@ -16,6 +16,9 @@ pub(crate) struct Resource {
     /// Resource generics
     generics: syn::Generics,
 
+    /// Path prefix applied to every route, set with `#[web(prefix = "...")]`
+    pub prefix: Option<String>,
+
     /// The route handlers implemented by `Resource`
     pub routes: Vec<Route>,
 
@@ -40,6 +43,7 @@ impl Resource {
             index,
             self_ty,
             generics,
+            prefix: None,
             routes: vec![],
             catches: vec![],
             destinations: vec![],
@@ -119,6 +123,9 @@ impl Resource {
         let match_extract = self.match_extract();
         let match_into_response = self.match_into_response();
 
+        let extract_predicates = self.extract_predicates(quote!(__B));
+        let extract_predicates_b = self.extract_predicates(quote!(B));
+
         // Define `Resource` on the struct.
         quote! {
             // Procedural macros expanding to macro_rules only stabilizes in 1.40,
@@ -159,6 +166,7 @@ impl Resource {
             impl<S, B, T> __GeneratedResource<S, B, T>
             where S: __tw::response::Serializer,
                   B: __tw::util::BufStream,
+                  #extract_predicates_b
             {
                 fn new(handler: T, serializer: S) -> Self {
                     let callsites = CallSites::new::<B>();
@@ -196,6 +204,7 @@ impl Resource {
             impl<__S, __B, #generics> __tw::routing::IntoResource<__S, __B> for #ty
             where __S: __tw::response::Serializer,
                   __B: __tw::util::BufStream,
+                  #extract_predicates
                   #where_predicates
             {
                 type Destination = #destination_ty;
@@ -215,6 +224,7 @@ impl Resource {
             impl<__S, __B, #generics> __tw::routing::Resource for __GeneratedResource<__S, __B, #ty>
             where __S: __tw::response::Serializer,
                   __B: __tw::util::BufStream,
+                  #extract_predicates
                   #where_predicates
             {
                 // The destination token is used to identify which action to
@@ -249,6 +259,7 @@ impl Resource {
             pub struct __ResponseFuture<__S, __B, __T, #generics>
             where __S: __tw::response::Serializer,
                   __B: __tw::util::BufStream,
+                  #extract_predicates
                   #where_predicates
             {
                 state: State<__B, #generic_idents>,
@@ -267,6 +278,7 @@ impl Resource {
             enum State<__B, #generics>
             where
                 __B: __tw::util::BufStream,
+                #extract_predicates
                 #where_predicates
             {
                 Extract(#extract_future_ty),
@@ -280,6 +292,7 @@ impl Resource {
             impl<__S, __B, #generics> __tw::routing::ResourceFuture for __ResponseFuture<__S, __B, #ty, #generic_idents>
             where __S: __tw::response::Serializer,
                   __B: __tw::util::BufStream,
+                  #extract_predicates
                   #where_predicates
             {
                 type Body = ResponseBody<#generic_idents>;
@@ -537,6 +550,24 @@ impl Resource {
         }
     }
 
+    /// Bounds requiring every argument type to be extractable from a request
+    /// body of type `body`.
+    ///
+    /// Some types, such as `Multipart`, only support a subset of body types, so
+    /// the bounds cannot be implied by `BufStream` alone.
+    fn extract_predicates(&self, body: TokenStream) -> TokenStream {
+        let predicates = self.routes.iter()
+            .flat_map(|route| route.args())
+            .map(|arg| {
+                let ty = &arg.ty;
+                quote! { #ty: __tw::extract::Extract<#body>, }
+            });
+
+        quote! {
+            #(#predicates)*
+        }
+    }
+
     fn dummy_const(&self) -> syn::Ident {
         // A (slightly) helpful string snippet to identify *which* service
         // implementation this scope is for
@@ -554,6 +585,8 @@ impl Resource {
     }
 
     fn callsites_def(&self) -> TokenStream {
+        let extract_predicates = self.extract_predicates(quote!(B));
+
         let fields = self.routes.iter().enumerate()
             .map(|(i, route)| {
                 let name = route_n(i);
@@ -566,6 +599,12 @@ impl Resource {
         let init = self.routes.iter().enumerate()
             .map(|(i, route)| {
                 let name = route_n(i);
+
+                let body_limit = match route.body_limit() {
+                    Some(limit) => quote!(.with_body_limit(#limit)),
+                    None => quote!(),
+                };
+
                 let init = route.args().iter()
                     .map(|arg| {
                         let new = arg.new_callsite();
@@ -573,7 +612,7 @@ impl Resource {
 
                         quote! {
                             {
-                                let callsite = #new;
+                                let callsite = #new #body_limit;
                                 let requires_body =
                                     <#ty as __tw::extract::Extract<B>>::requires_body(&callsite);
 
@@ -617,7 +656,10 @@ impl Resource {
             }
 
             impl CallSites {
-                fn new<B: __tw::util::BufStream>() -> CallSites {
+                fn new<B>() -> CallSites
+                where B: __tw::util::BufStream,
+                      #extract_predicates
+                {
                     CallSites {
                         #(#init),*
                     }
@@ -827,9 +869,13 @@ impl Resource {
     }
 
     fn build_routes_fn(&self) -> TokenStream {
+        // The resource type name, as reported by route introspection
+        let self_ty = &self.self_ty;
+        let resource = quote!(#self_ty).to_string().replace(" ", "");
+
         self.destination_syms(|route, destination| {
             let destination = destination.build_default();
-            route.build_route(destination)
+            route.build_route(destination, &resource)
         })
     }
 
@@ -35,18 +35,53 @@ impl Route {
         self.attributes.template()
     }
 
-    /// Route builder fn call to add the route definition.
-    pub fn build_route(&self, destination: TokenStream) -> TokenStream {
-        let method = self.attributes.method_expr();
-        let path = self.attributes.path_expr();
+    pub fn body_limit(&self) -> Option<u64> {
+        self.attributes.body_limit
+    }
 
-        quote! {
-            .insert({
-                __tw::routing::Route::new(#destination)
-                    .method(#method)
-                    .path(#path)
-            })
-        }
+    /// Route builder fn calls to add the route definitions, one for each
+    /// method and path matched by the handler.
+    pub fn build_route(&self, destination: TokenStream, resource: &str) -> TokenStream {
+        let handler = self.ident().to_string();
+
+        let content_type = match self.attributes.content_type {
+            Some(ref content_type) => quote!(.content_type(#content_type)),
+            None => quote!(),
+        };
+
+        let consumes = &self.attributes.consumes;
+
+        let guards: Vec<_> = self.attributes.guards.iter()
+            .map(|guard| guard.to_tokens())
+            .collect();
+
+        // Routes are named after the handler unless a name is provided
+        let name = match self.attributes.name {
+            Some(ref name) => name.clone(),
+            None => handler.clone(),
+        };
+
+        let inserts = self.attributes.routes.iter().map(|route| {
+            let method = route.method_expr();
+            let path = route.path_expr();
+            let guards = guards.clone();
+            let consumes = consumes.iter();
+
+            quote! {
+                .insert({
+                    __tw::routing::Route::new(#destination)
+                        .method(#method)
+                        .path(#path)
+                        #(.guard(#guards))*
+                        .name(#name)
+                        .handler(#resource, #handler)
+                        #content_type
+                        #(.consumes(#consumes))*
+                })
+            }
+        });
+
+        quote!(#(#inserts)*)
     }
 
     pub fn dispatch_fn(&self) -> TokenStream {
@@ -21,3 +21,277 @@ fn duplicate_routes(){
         }
     };
 }
+
+#[test]
+#[should_panic(expected = "duplicate routes with method")]
+fn duplicate_extension_routes(){
+    expand! {
+        impl Test{
+            #[route(method = "PROPFIND", path = "/foo")]
+            fn foo(&self){}
+
+            #[route(path = "/foo", method = "PROPFIND")]
+            fn bar(&self){}
+        }
+    };
+}
+
+#[test]
+#[should_panic(expected = "invalid HTTP method")]
+fn invalid_extension_method(){
+    expand! {
+        impl Test{
+            #[route(method = "NOT A METHOD", path = "/foo")]
+            fn foo(&self){}
+        }
+    };
+}
+
+#[test]
+#[should_panic(expected = "duplicate routes with method")]
+fn duplicate_constrained_routes(){
+    expand! {
+        impl Test{
+            #[get("/users/:id<u32>")]
+            fn foo(&self, id: u32){}
+
+            #[get("/users/:user_id<u32>/")]
+            fn bar(&self, user_id: u32){}
+        }
+    };
+}
+
+#[test]
+fn distinct_constrained_routes(){
+    expand! {
+        impl Test{
+            #[get("/users/:id<u32>")]
+            fn foo(&self, id: u32){}
+
+            #[get("/users/:name<[a-z-]+>")]
+            fn bar(&self, name: String){}
+
+            #[get("/users/:other")]
+            fn baz(&self, other: String){}
+        }
+    };
+}
+
+#[test]
+#[should_panic(expected = "duplicate routes with method")]
+fn duplicate_pattern_routes(){
+    expand! {
+        impl Test{
+            #[get("/files/:name.:ext")]
+            fn foo(&self, name: String, ext: String){}
+
+            #[get("/files/:file.:format")]
+            fn bar(&self, file: String, format: String){}
+        }
+    };
+}
+
+#[test]
+#[should_panic(expected = "captures must be separated by literal text")]
+fn adjacent_captures(){
+    expand! {
+        impl Test{
+            #[get("/files/:name:ext")]
+            fn foo(&self, name: String, ext: String){}
+        }
+    };
+}
+
+#[test]
+#[should_panic(expected = "invalid capture constraint `[a-z` in `route` attribute")]
+fn invalid_constraint(){
+    expand! {
+        impl Test{
+            #[get("/posts/:slug<[a-z>")]
+            fn foo(&self, slug: String){}
+        }
+    };
+}
+
+#[test]
+#[should_panic(expected = "duplicate routes with method")]
+fn duplicate_prefixed_routes(){
+    expand! {
+        #[web(prefix = "/admin")]
+        impl Test{
+            #[get("/users")]
+            fn foo(&self){}
+
+            #[get("/users/")]
+            fn bar(&self){}
+        }
+    };
+}
+
+#[test]
+#[should_panic(expected = "`prefix` is only supported on impl blocks")]
+fn method_prefix(){
+    expand! {
+        impl Test{
+            #[get("/users")]
+            #[web(prefix = "/admin")]
+            fn foo(&self){}
+        }
+    };
+}
+
+#[test]
+fn distinct_guarded_routes(){
+    expand! {
+        impl Test{
+            #[get("/users")]
+            #[web(guard(header = "x-api-version", value = "2"))]
+            fn foo(&self){}
+
+            #[get("/users")]
+            fn bar(&self){}
+        }
+    };
+}
+
+#[test]
+#[should_panic(expected = "duplicate routes with method")]
+fn duplicate_guarded_routes(){
+    expand! {
+        impl Test{
+            #[get("/users")]
+            #[web(guard(accept = "application/vnd.acme.v2+json"))]
+            fn foo(&self){}
+
+            #[get("/users/")]
+            #[web(guard(accept = "application/vnd.acme.v2+json"))]
+            fn bar(&self){}
+        }
+    };
+}
+
+#[test]
+fn consumes_routes(){
+    expand! {
+        impl Test{
+            #[post("/users")]
+            #[web(consumes = "application/json")]
+            fn foo(&self){}
+
+            #[post("/users")]
+            #[web(consumes = "text/csv")]
+            fn bar(&self){}
+        }
+    };
+}
+
+#[test]
+#[should_panic(expected = "duplicate routes with method")]
+fn duplicate_consumes_routes(){
+    expand! {
+        impl Test{
+            #[post("/users")]
+            #[web(consumes = "application/json, text/csv")]
+            fn foo(&self){}
+
+            #[post("/users")]
+            #[web(consumes = "Text/CSV, application/json")]
+            fn bar(&self){}
+        }
+    };
+}
+
+#[test]
+#[should_panic(expected = "`value` is only supported on header and query guards")]
+fn host_guard_value(){
+    expand! {
+        impl Test{
+            #[get("/users")]
+            #[web(guard(host = "api.example.com", value = "2"))]
+            fn foo(&self){}
+        }
+    };
+}
+
+#[test]
+fn multiple_routes(){
+    expand! {
+        impl Test{
+            #[get("/")]
+            #[get("/index.html")]
+            #[head("/")]
+            fn index(&self){}
+
+            #[get("/users/:id")]
+            #[route(method = "PROPFIND", path = "/u/:id")]
+            fn user(&self, id: u32){}
+        }
+    };
+}
+
+#[test]
+#[should_panic(expected = "duplicate routes with method")]
+fn duplicate_multiple_routes(){
+    expand! {
+        impl Test{
+            #[get("/")]
+            #[get("/index.html")]
+            fn foo(&self){}
+
+            #[get("/index.html/")]
+            fn bar(&self){}
+        }
+    };
+}
+
+#[test]
+#[should_panic(expected = "all paths of a handler must declare the same captures")]
+fn multiple_routes_different_captures(){
+    expand! {
+        impl Test{
+            #[get("/users/:id")]
+            #[get("/users/:id/posts/:post")]
+            fn foo(&self, id: u32){}
+        }
+    };
+}
+
+#[test]
+fn body_limit(){
+    expand! {
+        impl Test{
+            #[post("/foo")]
+            #[web(body_limit = "1MB")]
+            fn foo(&self, body: Vec<u8>){}
+
+            #[post("/bar")]
+            #[web(body_limit = 1024)]
+            fn bar(&self, body: Vec<u8>){}
+        }
+    };
+}
+
+#[test]
+#[should_panic(expected = "invalid `body_limit`")]
+fn invalid_body_limit(){
+    expand! {
+        impl Test{
+            #[post("/foo")]
+            #[web(body_limit = "1 parsec")]
+            fn foo(&self, body: Vec<u8>){}
+        }
+    };
+}
+
+#[test]
+fn impl_block_attributes(){
+    expand! {
+        /// Users resource
+        #[web(prefix = "/admin")]
+        #[allow(dead_code)]
+        impl Test{
+            #[get("/users")]
+            fn foo(&self){}
+        }
+    };
+}
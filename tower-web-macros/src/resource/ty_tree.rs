@@ -65,6 +65,10 @@ impl<'a> TyTree<'a, Arg> {
                         &route_match,
                         &callsites.#index.0);
 
+                    // The body is moved into the extract future. Types that
+                    // stream the body, such as `BodyStream`, complete
+                    // immediately, handing the body to the resource method
+                    // without reading it.
                     if callsites.#index.1 {
                         <#ty as __tw::extract::Extract<__B>>::extract_body(
                             &context,
@@ -0,0 +1,187 @@
+//! Route path syntax shared by `tower-web` and `tower-web-macros`.
+//!
+//! The `impl_web!` macro binds handler arguments to captures at compile time,
+//! while `tower-web` matches requests against the same paths at run time.
+//! Both parse path segments with `parse_segment`, so they always agree on the
+//! captures a path declares.
+//!
+//! This crate is an implementation detail of `tower-web` and has no stability
+//! guarantees.
+
+#![doc(html_root_url = "https://docs.rs/tower-web-path/0.1.0")]
+#![deny(missing_debug_implementations, missing_docs, rust_2018_idioms)]
+
+/// A route path segment, that is the text between two `/`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Segment<'a> {
+    /// A glob, such as `*path`, matching the rest of the request path.
+    Glob(&'a str),
+
+    /// Literal text and captures, such as `users` or `:name.:ext`.
+    ///
+    /// There always is at least one part, and two captures are always
+    /// separated by literal text.
+    Parts(Vec<Part<'a>>),
+}
+
+/// Part of a path segment.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Part<'a> {
+    /// Literal text.
+    Literal(&'a str),
+
+    /// A capture, such as `:id` or `:id<u32>`.
+    Capture {
+        /// Name used to bind the capture to a handler argument.
+        name: &'a str,
+
+        /// Constraint on the captured value, without the angle brackets.
+        constraint: Option<&'a str>,
+    },
+}
+
+/// Parse a route path segment.
+///
+/// Captures start with `:` and may be mixed with literal text, as in
+/// `:name.:ext`. A constraint may follow the capture name, as in `:id<u32>`.
+/// A segment starting with `*` is a glob.
+///
+/// # Panics
+///
+/// Panics if a capture has no name, if a constraint is not closed or if two
+/// captures are not separated by literal text.
+pub fn parse_segment(segment: &str) -> Segment<'_> {
+    if segment.starts_with('*') {
+        return Segment::Glob(&segment[1..]);
+    }
+
+    let mut parts = vec![];
+    let mut rest = segment;
+
+    while let Some(pos) = rest.find(':') {
+        if pos > 0 {
+            parts.push(Part::Literal(&rest[..pos]));
+        } else if let Some(&Part::Capture { .. }) = parts.last() {
+            panic!("captures must be separated by literal text; segment={:?}", segment);
+        }
+
+        rest = &rest[pos + 1..];
+
+        let end = rest.find(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .unwrap_or(rest.len());
+
+        assert!(end > 0, "expected capture name; segment={:?}", segment);
+
+        let name = &rest[..end];
+        rest = &rest[end..];
+
+        let constraint = if rest.starts_with('<') {
+            let end = constraint_end(rest).unwrap_or_else(|| {
+                panic!("unclosed capture constraint; segment={:?}", segment)
+            });
+
+            let constraint = &rest[1..end];
+            rest = &rest[end + 1..];
+            Some(constraint)
+        } else {
+            None
+        };
+
+        parts.push(Part::Capture { name, constraint });
+    }
+
+    if !rest.is_empty() || parts.is_empty() {
+        parts.push(Part::Literal(rest));
+    }
+
+    Segment::Parts(parts)
+}
+
+/// Find the `>` closing the capture constraint at the start of `rest`.
+fn constraint_end(rest: &str) -> Option<usize> {
+    let mut depth = 0;
+
+    for (i, c) in rest.char_indices() {
+        match c {
+            '<' => depth += 1,
+            '>' => {
+                depth -= 1;
+
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use super::Part::*;
+
+    fn capture<'a>(name: &'a str, constraint: Option<&'a str>) -> Part<'a> {
+        Capture { name, constraint }
+    }
+
+    #[test]
+    fn parse_literal() {
+        assert_eq!(parse_segment("users"), Segment::Parts(vec![Literal("users")]));
+        assert_eq!(parse_segment(""), Segment::Parts(vec![Literal("")]));
+    }
+
+    #[test]
+    fn parse_glob() {
+        assert_eq!(parse_segment("*path"), Segment::Glob("path"));
+    }
+
+    #[test]
+    fn parse_captures() {
+        assert_eq!(parse_segment(":id"), Segment::Parts(vec![capture("id", None)]));
+
+        assert_eq!(parse_segment(":name.:ext"), Segment::Parts(vec![
+            capture("name", None),
+            Literal("."),
+            capture("ext", None),
+        ]));
+
+        assert_eq!(parse_segment("v:major<u32>-:tag<[a-z]{2,}>"), Segment::Parts(vec![
+            Literal("v"),
+            capture("major", Some("u32")),
+            Literal("-"),
+            capture("tag", Some("[a-z]{2,}")),
+        ]));
+
+        // Angle brackets nest within a constraint
+        assert_eq!(parse_segment(":id<(?P<n>[0-9]+)>"), Segment::Parts(vec![
+            capture("id", Some("(?P<n>[0-9]+)")),
+        ]));
+    }
+
+    #[test]
+    #[should_panic(expected = "captures must be separated by literal text")]
+    fn adjacent_captures() {
+        parse_segment(":name:ext");
+    }
+
+    #[test]
+    #[should_panic(expected = "captures must be separated by literal text")]
+    fn adjacent_constrained_captures() {
+        parse_segment(":name<[a-z]+>:ext");
+    }
+
+    #[test]
+    #[should_panic(expected = "expected capture name")]
+    fn missing_capture_name() {
+        parse_segment("a:.b");
+    }
+
+    #[test]
+    #[should_panic(expected = "unclosed capture constraint")]
+    fn unclosed_constraint() {
+        parse_segment(":id<u32");
+    }
+}
@@ -41,6 +41,7 @@ use extract::{self, ExtractFuture};
 use response::{Context, Response, Serializer};
 use routing::{self, Resource, ResourceFuture, IntoResource, RouteSet, RouteMatch};
 use util::{BufStream, Chain};
+use util::buf_stream::SizeHint;
 use util::http::{HttpFuture, SealedFuture};
 
 use bytes::Buf;
@@ -325,6 +326,18 @@ impl Either {
             println!("            {}(ref mut f) => Ok(try_ready!(f.poll()).map({}).into()),", VARS[n], VARS[n]);
         }
 
+        println!("        }}");
+        println!("    }}");
+        println!("");
+        println!("    fn size_hint(&self) -> SizeHint {{");
+        println!("        use self::Either{}::*;", self.level);
+        println!("");
+        println!("        match *self {{");
+
+        for n in 0..self.level {
+            println!("            {}(ref f) => f.size_hint(),", VARS[n]);
+        }
+
         println!("        }}");
         println!("    }}");
         println!("}}");